@@ -0,0 +1,446 @@
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use log::{debug, info};
+use oxiclean_core::{CommandConfig, ModuleGraph, SharedConfig};
+use std::io::Write;
+
+/// A check that can run as part of `oxiclean check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CheckKind {
+    #[value(alias = "import-bloat")]
+    Bloat,
+    #[value(alias = "import-depth")]
+    Depth,
+    #[value(alias = "import-cycles")]
+    Cycles,
+    #[value(alias = "import-boundaries")]
+    Boundaries,
+    Barrels,
+    UnusedFiles,
+    UnusedExports,
+    Dependencies,
+    #[value(alias = "duplicates")]
+    DuplicatePackages,
+}
+
+impl CheckKind {
+    const ALL: &[CheckKind] = &[
+        CheckKind::Bloat,
+        CheckKind::Depth,
+        CheckKind::Cycles,
+        CheckKind::Boundaries,
+        CheckKind::Barrels,
+        CheckKind::UnusedFiles,
+        CheckKind::UnusedExports,
+        CheckKind::Dependencies,
+        CheckKind::DuplicatePackages,
+    ];
+
+    /// The check's command name, also used for its config file section
+    fn name(self) -> &'static str {
+        match self {
+            CheckKind::Bloat => "import-bloat",
+            CheckKind::Depth => "import-depth",
+            CheckKind::Cycles => "import-cycles",
+            CheckKind::Boundaries => "import-boundaries",
+            CheckKind::Barrels => "barrels",
+            CheckKind::UnusedFiles => "unused-files",
+            CheckKind::UnusedExports => "unused-exports",
+            CheckKind::Dependencies => "dependencies",
+            CheckKind::DuplicatePackages => "duplicate-packages",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CheckConfig {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Run every available check (the default when `--checks` is not given)
+    #[arg(long, conflicts_with = "checks")]
+    pub all: bool,
+
+    /// Comma-separated checks to run: bloat, depth, cycles, boundaries, barrels,
+    /// unused-files, unused-exports, dependencies, duplicate-packages
+    #[arg(long, value_delimiter = ',')]
+    pub checks: Vec<CheckKind>,
+
+    /// Threshold for the import-bloat check [default: 200]
+    #[arg(long)]
+    pub bloat_threshold: Option<usize>,
+
+    /// Threshold for the import-depth check [default: 10]
+    #[arg(long)]
+    pub depth_threshold: Option<usize>,
+}
+
+impl CheckConfig {
+    /// The selected checks, in a fixed order and without duplicates
+    fn selected(&self) -> Vec<CheckKind> {
+        if self.all || self.checks.is_empty() {
+            return CheckKind::ALL.to_vec();
+        }
+        CheckKind::ALL.iter().copied().filter(|c| self.checks.contains(c)).collect()
+    }
+}
+
+impl CommandConfig for CheckConfig {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    /// Initializes the shared settings only; each check reads its own config
+    /// file section when it runs
+    fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()
+    }
+}
+
+/// Runs the import bloat check over the graph and prints its report, returning
+/// the number of warnings
+pub fn report_bloat<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_import_bloat::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    print_bloat(&oxiclean_import_bloat::check_graph(graph, cfg), cfg, writer)
+}
+
+/// Prints the report of an import bloat check, returning the number of warnings
+pub fn print_bloat<W: Write>(
+    result: &oxiclean_import_bloat::CheckResult,
+    cfg: &oxiclean_import_bloat::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    debug!("Found {} warnings", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No bloat detected");
+        oxiclean_import_bloat::print_no_bloat_message(writer, cfg.threshold())?;
+    } else {
+        oxiclean_import_bloat::print_warnings_tree(writer, &result.warnings, cfg, cfg.threshold())?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the import depth check over the graph and prints its report, returning
+/// the number of warnings
+pub fn report_depth<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_import_depth::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    print_depth(&oxiclean_import_depth::check_graph(graph, cfg), cfg, writer)
+}
+
+/// Prints the report of an import depth check, returning the number of warnings
+pub fn print_depth<W: Write>(
+    result: &oxiclean_import_depth::CheckResult,
+    cfg: &oxiclean_import_depth::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    debug!("Found {} warnings", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No depth issues detected");
+        oxiclean_import_depth::print_no_depth_issues_message(writer, cfg.threshold())?;
+    } else {
+        oxiclean_import_depth::print_warnings_tree(writer, &result.warnings, cfg, cfg.threshold())?;
+    }
+    if !result.cycles.is_empty() {
+        writeln!(writer)?;
+        oxiclean_import_depth::print_cycles(writer, &result.cycles, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the import cycles check over the graph and prints its report, returning
+/// the number of cycles
+pub fn report_cycles<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_import_cycles::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_import_cycles::check_graph(graph, cfg);
+    debug!("Found {} cycles", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No import cycles detected");
+        oxiclean_import_cycles::print_no_cycles_message(writer, &result)?;
+    } else {
+        oxiclean_import_cycles::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the import boundaries check over the graph and prints its report,
+/// returning the number of violations
+pub fn report_boundaries<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_import_boundaries::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_import_boundaries::check_graph(graph, cfg)?;
+    debug!("Found {} boundary violations", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No boundary violations detected");
+        oxiclean_import_boundaries::print_no_violations_message(writer, &result)?;
+    } else {
+        oxiclean_import_boundaries::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the barrels check over the graph and prints its report, returning the
+/// number of wasteful barrel imports
+pub fn report_barrels<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_barrels::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_barrels::check_graph(graph, cfg);
+    debug!("Found {} wasteful barrel imports", result.warning_count());
+    if result.warning_count() == 0 {
+        info!("No wasteful barrel imports detected");
+        oxiclean_barrels::print_no_barrel_issues_message(writer, &result)?;
+    } else {
+        oxiclean_barrels::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warning_count())
+}
+
+/// Runs the unused files check over the graph and prints its report (or just
+/// the paths with `--list`), returning the number of unused files
+pub fn report_unused_files<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_unused_files::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_unused_files::check_graph(graph, cfg)?;
+    debug!("Found {} unused files", result.warnings.len());
+    if cfg.list {
+        oxiclean_unused_files::print_file_list(writer, &result, cfg)?;
+    } else if result.warnings.is_empty() {
+        info!("No unused files detected");
+        oxiclean_unused_files::print_no_unused_files_message(writer, &result)?;
+    } else {
+        oxiclean_unused_files::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the unused exports check over the graph and prints its report,
+/// returning the number of unused exports
+pub fn report_unused_exports<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_unused_exports::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_unused_exports::check_graph(graph, cfg)?;
+    debug!("Found {} unused exports", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No unused exports detected");
+        oxiclean_unused_exports::print_no_unused_exports_message(writer, &result)?;
+    } else {
+        oxiclean_unused_exports::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the dependencies check over the graph and prints its report, returning
+/// the number of dependency issues
+pub fn report_dependencies<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_dependencies::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_dependencies::check_graph(graph, cfg);
+    debug!("Found {} dependency issues", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No dependency issues detected");
+        oxiclean_dependencies::print_no_dependency_issues_message(writer, &result)?;
+    } else {
+        oxiclean_dependencies::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs the duplicate packages check over the graph and prints its report,
+/// returning the number of duplicated packages
+pub fn report_duplicate_packages<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &oxiclean_duplicate_packages::Config,
+    writer: &mut W,
+) -> Result<usize> {
+    let result = oxiclean_duplicate_packages::check_graph(graph, cfg);
+    debug!("Found {} duplicated packages", result.warnings.len());
+    if result.warnings.is_empty() {
+        info!("No duplicate packages detected");
+        oxiclean_duplicate_packages::print_no_duplicates_message(writer, &result)?;
+    } else {
+        oxiclean_duplicate_packages::print_warnings_tree(writer, &result, cfg)?;
+    }
+    Ok(result.warnings.len())
+}
+
+/// Runs each selected check over the shared graph and prints one report per
+/// check followed by a combined summary. Returns true if any check reported
+/// warnings.
+///
+/// `cfg.shared` must be initialized.
+pub fn run_checks<W: Write>(
+    graph: &ModuleGraph,
+    cfg: &CheckConfig,
+    writer: &mut W,
+) -> Result<bool> {
+    let checks = cfg.selected();
+    info!("Running checks: {:?}", checks);
+
+    let mut results: Vec<(CheckKind, usize)> = Vec::new();
+    for &check in &checks {
+        writeln!(writer, "{}\n", format!("── {} ──", check.name()).bold())?;
+
+        let warnings = match check {
+            CheckKind::Bloat => {
+                let mut check_cfg = oxiclean_import_bloat::Config {
+                    threshold: cfg.bloat_threshold,
+                    ..oxiclean_import_bloat::Config::from_shared(cfg.shared.clone())
+                };
+                check_cfg.initialize()?;
+                report_bloat(graph, &check_cfg, writer)?
+            }
+            CheckKind::Depth => {
+                let mut check_cfg = oxiclean_import_depth::Config {
+                    threshold: cfg.depth_threshold,
+                    ..oxiclean_import_depth::Config::from_shared(cfg.shared.clone())
+                };
+                check_cfg.initialize()?;
+                report_depth(graph, &check_cfg, writer)?
+            }
+            CheckKind::Cycles => {
+                let mut check_cfg = oxiclean_import_cycles::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_cycles(graph, &check_cfg, writer)?
+            }
+            CheckKind::Boundaries => {
+                let mut check_cfg =
+                    oxiclean_import_boundaries::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_boundaries(graph, &check_cfg, writer)?
+            }
+            CheckKind::Barrels => {
+                let mut check_cfg = oxiclean_barrels::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_barrels(graph, &check_cfg, writer)?
+            }
+            CheckKind::UnusedFiles => {
+                let mut check_cfg = oxiclean_unused_files::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_unused_files(graph, &check_cfg, writer)?
+            }
+            CheckKind::UnusedExports => {
+                let mut check_cfg =
+                    oxiclean_unused_exports::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_unused_exports(graph, &check_cfg, writer)?
+            }
+            CheckKind::Dependencies => {
+                let mut check_cfg = oxiclean_dependencies::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_dependencies(graph, &check_cfg, writer)?
+            }
+            CheckKind::DuplicatePackages => {
+                let mut check_cfg =
+                    oxiclean_duplicate_packages::Config::from_shared(cfg.shared.clone());
+                check_cfg.initialize()?;
+                report_duplicate_packages(graph, &check_cfg, writer)?
+            }
+        };
+        debug!("Check {} found {} warnings", check.name(), warnings);
+        writeln!(writer)?;
+        results.push((check, warnings));
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Checks".bold())?;
+    for (check, warnings) in &results {
+        if *warnings == 0 {
+            writeln!(writer, "  {} {}", "✓".green().bold(), check.name())?;
+        } else {
+            writeln!(
+                writer,
+                "  {} {} ({} warnings)",
+                "⚠".yellow().bold(),
+                check.name(),
+                warnings.to_string().yellow()
+            )?;
+        }
+    }
+
+    Ok(results.iter().any(|(_, warnings)| *warnings > 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, checks: Vec<CheckKind>) -> CheckConfig {
+        let mut cfg = CheckConfig {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            all: false,
+            checks,
+            bloat_threshold: None,
+            depth_threshold: None,
+        };
+        CommandConfig::initialize(&mut cfg).unwrap();
+        cfg
+    }
+
+    #[test]
+    fn test_run_checks_combines_reports() {
+        colored::control::set_override(false);
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "src/a.ts", "import './b';");
+        create_test_file(root, "src/b.ts", "import './a';");
+        create_test_file(root, "src/index.ts", "import './c';");
+        create_test_file(root, "src/c.ts", "// c");
+
+        // Selected checks run in a fixed order over one graph
+        let cfg = config(root, vec![CheckKind::Depth, CheckKind::Cycles]);
+        let graph = ModuleGraph::from_config(&cfg.shared).unwrap();
+        let mut out = Vec::new();
+        assert!(run_checks(&graph, &cfg, &mut out).unwrap());
+        let out = String::from_utf8(out).unwrap();
+        let depth = out.find("── import-depth ──").unwrap();
+        let cycles = out.find("── import-cycles ──").unwrap();
+        assert!(depth < cycles);
+        let summary = &out[out.find("Checks\n").unwrap()..];
+        assert_eq!(summary, "Checks\n  ✓ import-depth\n  ⚠ import-cycles (1 warnings)\n");
+
+        let cfg = config(root, vec![CheckKind::Depth]);
+        let mut out = Vec::new();
+        assert!(!run_checks(&graph, &cfg, &mut out).unwrap());
+    }
+}
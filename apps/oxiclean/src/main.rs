@@ -2,10 +2,13 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use log::{debug, info};
+use oxiclean_core::{CommandConfig, ModuleGraph, ModuleId, watch_project};
 
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, IsTerminal, Write};
 use std::time::Instant;
 
+mod check;
+
 #[derive(Parser)]
 #[command(name = "oxiclean")]
 #[command(about = "A collection of tools for cleaning up codebases", long_about = None)]
@@ -20,6 +23,28 @@ enum Commands {
     ImportBloat(oxiclean_import_bloat::Config),
     /// Check for excessive import depth in JavaScript/TypeScript projects
     ImportDepth(oxiclean_import_depth::Config),
+    /// Check for circular imports in JavaScript/TypeScript projects
+    ImportCycles(oxiclean_import_cycles::Config),
+    /// Check imports against architectural boundary rules from the config file
+    ImportBoundaries(oxiclean_import_boundaries::Config),
+    /// Find barrel files and the imports that pull in more than they use
+    Barrels(oxiclean_barrels::Config),
+    /// Find source files that no entry point reaches
+    UnusedFiles(oxiclean_unused_files::Config),
+    /// Find exported names that no module imports
+    UnusedExports(oxiclean_unused_exports::Config),
+    /// Find undeclared, unused and dev-only package dependencies of each workspace package
+    Dependencies(oxiclean_dependencies::Config),
+    /// Find packages with more than one copy reachable from the same entry
+    DuplicatePackages(oxiclean_duplicate_packages::Config),
+    /// Show fan-in, fan-out, reach, dependents and depth of each module
+    GraphStats(oxiclean_graph_stats::Config),
+    /// Show which entries import a file or package, and through which imports
+    Why(oxiclean_why::Config),
+    /// Show the shortest import chains from one file to another file or a package
+    Path(oxiclean_path::Config),
+    /// Run several checks over a single parse of the project, with one combined report
+    Check(check::CheckConfig),
 }
 
 fn main() -> Result<()> {
@@ -35,103 +60,292 @@ fn main() -> Result<()> {
     let start = Instant::now();
 
     match cli.command {
-        Commands::ImportBloat(cfg) => {
-            let num_threads = rayon::current_num_threads();
+        Commands::ImportBloat(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and thresholds
+            cfg.initialize()?;
             info!(
                 "Running import bloat check with threshold: {} (using {} threads)",
-                cfg.threshold, num_threads
+                cfg.threshold(),
+                rayon::current_num_threads()
             );
-            debug!("Config: root={:?}, entry_glob={:?}", cfg.root, cfg.entry_glob);
-
-            let result = oxiclean_import_bloat::run_import_bloat_check(cfg.clone())?;
-            debug!("Found {} warnings", result.warnings.len());
-
-            let elapsed_ms = start.elapsed().as_millis();
-
-            if !result.warnings.is_empty() {
-                oxiclean_import_bloat::print_warnings_tree(
-                    &mut stdout,
-                    &result.warnings,
-                    &cfg,
-                    cfg.threshold,
-                )?;
-
-                writeln!(
-                    stdout,
-                    "\n{} Finished in {}ms on {} files (using {} threads).",
-                    "●".bright_blue(),
-                    elapsed_ms.to_string().cyan(),
-                    result.files_analyzed.to_string().cyan(),
-                    num_threads.to_string().cyan()
-                )?;
-                stdout.flush()?;
-
-                // Non-zero exit to fail CI
-                std::process::exit(1);
-            } else {
-                info!("No bloat detected");
-                oxiclean_import_bloat::print_no_bloat_message(&mut stdout, cfg.threshold)?;
-                writeln!(
-                    stdout,
-                    "\n{} Finished in {}ms on {} files (using {} threads).",
-                    "●".bright_blue(),
-                    elapsed_ms.to_string().cyan(),
-                    result.files_analyzed.to_string().cyan(),
-                    num_threads.to_string().cyan()
-                )?;
-                stdout.flush()?;
-            }
+            debug!("Config: root={:?}, entry_glob={:?}", cfg.root(), cfg.shared.entry_glob);
 
-            Ok(())
+            // In watch mode, only entries reaching a changed module are checked again
+            let mut previous = None;
+            run_incremental(&cli, cfg, start, &mut stdout, |graph, cfg, changed, out| {
+                let result = match (previous.take(), changed) {
+                    (Some(previous), Some(changed)) => {
+                        oxiclean_import_bloat::recheck_entries(graph, cfg, previous, changed)
+                    }
+                    _ => oxiclean_import_bloat::check_graph(graph, cfg),
+                };
+                let warnings = check::print_bloat(&result, cfg, out)?;
+                previous = Some(result);
+                Ok(warnings)
+            })
         }
-        Commands::ImportDepth(cfg) => {
-            let num_threads = rayon::current_num_threads();
+        Commands::ImportDepth(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and thresholds
+            cfg.initialize()?;
             info!(
                 "Running import depth check with threshold: {} (using {} threads)",
-                cfg.threshold, num_threads
+                cfg.threshold(),
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, entry_glob={:?}", cfg.root(), cfg.shared.entry_glob);
+
+            // In watch mode, only entries reaching a changed module are checked again
+            let mut previous = None;
+            run_incremental(&cli, cfg, start, &mut stdout, |graph, cfg, changed, out| {
+                let result = match (previous.take(), changed) {
+                    (Some(previous), Some(changed)) => {
+                        oxiclean_import_depth::recheck_entries(graph, cfg, previous, changed)
+                    }
+                    _ => oxiclean_import_depth::check_graph(graph, cfg),
+                };
+                let warnings = check::print_depth(&result, cfg, out)?;
+                previous = Some(result);
+                Ok(warnings)
+            })
+        }
+        Commands::ImportCycles(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and allowlist
+            cfg.initialize()?;
+            info!(
+                "Running import cycles check with max length: {:?} (using {} threads)",
+                cfg.max_length,
+                rayon::current_num_threads()
             );
-            debug!("Config: root={:?}, entry_glob={:?}", cfg.root, cfg.entry_glob);
-
-            let result = oxiclean_import_depth::run_import_depth_check(cfg.clone())?;
-            debug!("Found {} warnings", result.warnings.len());
-
-            let elapsed_ms = start.elapsed().as_millis();
-
-            if !result.warnings.is_empty() {
-                oxiclean_import_depth::print_warnings_tree(
-                    &mut stdout,
-                    &result.warnings,
-                    &cfg,
-                    cfg.threshold,
-                )?;
-
-                writeln!(
-                    stdout,
-                    "\n{} Finished in {}ms on {} files (using {} threads).",
-                    "●".bright_blue(),
-                    elapsed_ms.to_string().cyan(),
-                    result.files_analyzed.to_string().cyan(),
-                    num_threads.to_string().cyan()
-                )?;
-                stdout.flush()?;
-
-                // Non-zero exit to fail CI
-                std::process::exit(1);
-            } else {
-                info!("No depth issues detected");
-                oxiclean_import_depth::print_no_depth_issues_message(&mut stdout, cfg.threshold)?;
-                writeln!(
-                    stdout,
-                    "\n{} Finished in {}ms on {} files (using {} threads).",
-                    "●".bright_blue(),
-                    elapsed_ms.to_string().cyan(),
-                    result.files_analyzed.to_string().cyan(),
-                    num_threads.to_string().cyan()
-                )?;
-                stdout.flush()?;
+            debug!("Config: root={:?}, entry_glob={:?}", cfg.root(), cfg.shared.entry_glob);
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_cycles(graph, cfg, out)
+            })
+        }
+        Commands::ImportBoundaries(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and rules
+            cfg.initialize()?;
+            info!(
+                "Running import boundaries check with {} rules (using {} threads)",
+                cfg.rules.len(),
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, rules={:?}", cfg.root(), cfg.only_rules);
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_boundaries(graph, cfg, out)
+            })
+        }
+        Commands::Barrels(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and limits
+            cfg.initialize()?;
+            info!(
+                "Running barrels check with min fan-out {} (using {} threads)",
+                cfg.min_fan_out(),
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, min_savings={}", cfg.root(), cfg.min_savings());
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_barrels(graph, cfg, out)
+            })
+        }
+        Commands::UnusedFiles(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and entry mode
+            cfg.initialize()?;
+            info!("Running unused files check (using {} threads)", rayon::current_num_threads());
+            debug!("Config: root={:?}, entries={:?}", cfg.root(), cfg.shared.entries);
+
+            if cfg.list && !cfg.shared.watch {
+                // Nothing but the paths, so the output can be piped into other tools
+                let graph = ModuleGraph::from_config(&cfg.shared)?;
+                let result = oxiclean_unused_files::check_graph(&graph, &cfg)?;
+                oxiclean_unused_files::print_file_list(&mut stdout, &result, &cfg)?;
+                if !result.warnings.is_empty() {
+                    std::process::exit(1);
+                }
+                return Ok(());
             }
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_unused_files(graph, cfg, out)
+            })
+        }
+        Commands::UnusedExports(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and entry mode
+            cfg.initialize()?;
+            info!("Running unused exports check (using {} threads)", rayon::current_num_threads());
+            debug!("Config: root={:?}, entries={:?}", cfg.root(), cfg.shared.entries);
 
-            Ok(())
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_unused_exports(graph, cfg, out)
+            })
+        }
+        Commands::Dependencies(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root
+            cfg.initialize()?;
+            info!("Running dependencies check (using {} threads)", rayon::current_num_threads());
+            debug!("Config: root={:?}, ignored={:?}", cfg.root(), cfg.ignore_dependencies);
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_dependencies(graph, cfg, out)
+            })
+        }
+        Commands::DuplicatePackages(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root
+            cfg.initialize()?;
+            info!(
+                "Running duplicate packages check (using {} threads)",
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, ignore={:?}", cfg.root(), cfg.ignore);
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                check::report_duplicate_packages(graph, cfg, out)
+            })
+        }
+        Commands::GraphStats(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the report sees the resolved root and filters
+            cfg.initialize()?;
+            info!(
+                "Computing graph stats sorted by {} (using {} threads)",
+                cfg.sort_key().label(),
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, filter={:?}", cfg.root(), cfg.filter);
+
+            // A report rather than a check: never exits non-zero
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                let stats = oxiclean_graph_stats::compute_stats(graph, cfg);
+                oxiclean_graph_stats::print_stats_table(out, &stats, cfg)?;
+                Ok(0)
+            })
+        }
+        Commands::Why(cli) => {
+            let mut cfg = cli.clone();
+            // Initialize up front so the query sees the resolved root and entry mode
+            cfg.initialize()?;
+            info!(
+                "Running why query for {} (using {} threads)",
+                cfg.target,
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, entries={:?}", cfg.root(), cfg.shared.entries);
+
+            // A query rather than a check: never exits non-zero for a reached target
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                let result = oxiclean_why::query_graph(graph, cfg)?;
+                oxiclean_why::print_routes(out, &result, cfg)?;
+                Ok(0)
+            })
+        }
+        Commands::Path(cli) => {
+            let mut cfg = cli.clone();
+            cfg.initialize()?;
+            info!(
+                "Running path query from {} to {} (using {} threads)",
+                cfg.from,
+                cfg.to,
+                rayon::current_num_threads()
+            );
+            debug!("Config: root={:?}, count={}", cfg.root(), cfg.count);
+
+            // A query rather than a check: never exits non-zero for a found chain
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                let result = oxiclean_path::query_graph(graph, cfg)?;
+                oxiclean_path::print_chains(out, &result, cfg)?;
+                Ok(0)
+            })
+        }
+        Commands::Check(cli) => {
+            let mut cfg = cli.clone();
+            cfg.initialize()?;
+            info!("Running combined checks (using {} threads)", rayon::current_num_threads());
+            debug!("Config: root={:?}, checks={:?}", cfg.shared.root(), cfg.checks);
+
+            run(&cli, cfg, start, &mut stdout, |graph, cfg, out| {
+                Ok(usize::from(check::run_checks(graph, cfg, out)?))
+            })
         }
     }
 }
+
+/// Builds the module graph for the initialized settings `cfg` and prints
+/// `report` for it, exiting non-zero if it found any warnings. In watch mode,
+/// reports again after every change instead, with settings reloaded from `cli`
+/// whenever the config file changes.
+fn run<C, W, F>(cli: &C, cfg: C, start: Instant, stdout: &mut W, mut report: F) -> Result<()>
+where
+    C: CommandConfig,
+    W: Write,
+    F: FnMut(&ModuleGraph, &C, &mut W) -> Result<usize>,
+{
+    run_incremental(cli, cfg, start, stdout, |graph, cfg, _, out| report(graph, cfg, out))
+}
+
+/// Like [`run`], also passing `report` the entries whose reachable modules
+/// changed since its previous call in watch mode, or None when every entry
+/// must be checked
+fn run_incremental<C, W, F>(
+    cli: &C,
+    cfg: C,
+    start: Instant,
+    stdout: &mut W,
+    mut report: F,
+) -> Result<()>
+where
+    C: CommandConfig,
+    W: Write,
+    F: FnMut(&ModuleGraph, &C, Option<&[ModuleId]>, &mut W) -> Result<usize>,
+{
+    if cfg.shared().watch {
+        let clear_screen = std::io::stdout().is_terminal();
+        return watch_project(cli, cfg, |cfg, rebuild| {
+            if clear_screen {
+                write!(stdout, "\x1B[2J\x1B[1;1H")?;
+            }
+            match rebuild.graph {
+                Ok(graph) => {
+                    report(graph, cfg, rebuild.changed_entries.as_deref(), stdout)?;
+                    print_finished(stdout, rebuild.started, graph.len())?;
+                }
+                Err(e) => writeln!(stdout, "{} {:#}", "✗".red().bold(), e)?,
+            }
+            writeln!(stdout, "\n{}", "Watching for changes (Ctrl-C to stop)...".dimmed())?;
+            stdout.flush()?;
+            Ok(())
+        });
+    }
+
+    let graph = ModuleGraph::from_config(cfg.shared())?;
+    let warnings = report(&graph, &cfg, None, stdout)?;
+    print_finished(stdout, start, graph.len())?;
+    stdout.flush()?;
+
+    if warnings > 0 {
+        // Non-zero exit to fail CI
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_finished<W: Write>(stdout: &mut W, start: Instant, files: usize) -> Result<()> {
+    writeln!(
+        stdout,
+        "\n{} Finished in {}ms on {} files (using {} threads).",
+        "●".bright_blue(),
+        start.elapsed().as_millis().to_string().cyan(),
+        files.to_string().cyan(),
+        rayon::current_num_threads().to_string().cyan()
+    )?;
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use anyhow::Result;
+use log::{debug, info, trace};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use oxiclean_core::{ImportedNames, ModuleGraph, ModuleId};
+
+use crate::{
+    config::Config,
+    types::{Barrel, BarrelImport, CheckResult, Suggestion},
+};
+
+pub fn run_barrels_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting barrels check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
+
+/// Runs the barrels check as a pass over an already-built module graph.
+///
+/// A barrel is a module whose exports are mostly re-exports, from at least
+/// `min_fan_out` modules. For each runtime import of a barrel from a project
+/// file, the modules it pulls in through the barrel are compared with the
+/// modules the imported names need when imported from where they are declared.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    let shapes: Vec<(ModuleId, (usize, usize, usize))> = graph
+        .modules()
+        .filter_map(|id| barrel_shape(graph, id).map(|shape| (id, shape)))
+        .filter(|&(_, (fan_out, _, _))| fan_out >= cfg.min_fan_out())
+        .collect();
+    let ids: Vec<ModuleId> = shapes.iter().map(|&(id, _)| id).collect();
+    let counts = graph.reachable_counts(&ids);
+    debug!("Computed reachability of {} barrels", counts.len());
+
+    let mut barrels = Vec::new();
+    for (id, (fan_out, re_exports, exports)) in shapes {
+        trace!("{} is a barrel re-exporting from {} modules", graph.rel_path(id), fan_out);
+
+        // The barrel itself is not counted: importing directly skips exactly that
+        let reachable = counts[&id] - 1;
+        // Imports of a barrel often name the same origins; the counts are only
+        // kept while the barrel is processed
+        let mut needed_counts: HashMap<Vec<ModuleId>, usize> = HashMap::new();
+        let mut imports: Vec<BarrelImport> = graph
+            .importers(id)
+            .iter()
+            .filter(|&&from| !graph.is_installed(from))
+            .flat_map(|&from| {
+                graph
+                    .runtime_imports(from)
+                    .filter(move |edge| edge.to == id)
+                    .map(move |e| (from, e))
+            })
+            .filter_map(|(from, edge)| {
+                let ImportedNames::Named(names) = &edge.names else {
+                    // Namespace imports and require() may use every export
+                    return None;
+                };
+                let origins: Vec<(String, Origin)> = names
+                    .iter()
+                    .map(|name| {
+                        let origin = resolve_export(graph, id, name)
+                            .unwrap_or(Origin { module: id, name: name.clone() });
+                        (name.clone(), origin)
+                    })
+                    .collect();
+                let mut modules: Vec<ModuleId> = origins.iter().map(|(_, o)| o.module).collect();
+                modules.sort();
+                modules.dedup();
+                let needed = *needed_counts
+                    .entry(modules)
+                    .or_insert_with_key(|modules| reached_without(graph, modules, id));
+                if reachable < needed + cfg.min_savings() {
+                    return None;
+                }
+
+                let suggestions = origins
+                    .into_iter()
+                    .filter(|(_, origin)| origin.module != id)
+                    .map(|(name, origin)| Suggestion {
+                        name,
+                        origin_name: origin.name,
+                        origin_file: graph.rel_path(origin.module),
+                        request: direct_request(graph.path(from), graph.path(origin.module)),
+                    })
+                    .collect();
+                Some(BarrelImport {
+                    from_file: graph.rel_path(from),
+                    line: edge.line,
+                    request: edge.request.clone(),
+                    through: reachable,
+                    needed,
+                    suggestions,
+                })
+            })
+            .collect();
+        imports.sort_by(|a, b| {
+            b.savings().cmp(&a.savings()).then_with(|| a.from_file.cmp(&b.from_file))
+        });
+
+        barrels.push(Barrel {
+            file: graph.rel_path(id),
+            fan_out,
+            re_exports,
+            exports,
+            reachable,
+            imports,
+        });
+    }
+    barrels.sort_by_key(|b| {
+        (Reverse(b.imports.iter().map(|i| i.savings()).sum::<usize>()), b.file.clone())
+    });
+
+    let warnings: usize = barrels.iter().map(|b| b.imports.len()).sum();
+    info!(
+        "Barrels check complete. Found {} barrels and {} wasteful imports",
+        barrels.len(),
+        warnings
+    );
+    CheckResult { barrels, files_analyzed: graph.len() }
+}
+
+/// The fan-out, re-export count and export count of a module whose exports are
+/// mostly re-exports, or `None` if it isn't one
+fn barrel_shape(graph: &ModuleGraph, id: ModuleId) -> Option<(usize, usize, usize)> {
+    let exports = graph.exports(id);
+    let stars = graph.star_exports(id);
+    let mut sources: HashSet<ModuleId> = stars.iter().copied().collect();
+    let mut re_exports = stars.len();
+    for export in exports {
+        if let Some(from) = &export.from {
+            re_exports += 1;
+            if let Some(target) = graph.resolved(id, &from.request) {
+                sources.insert(target);
+            }
+        }
+    }
+    let total = exports.len() + stars.len();
+    // "Mostly": more re-exports than own exports
+    (re_exports * 2 > total).then_some((sources.len(), re_exports, total))
+}
+
+/// Where an exported name is declared
+struct Origin {
+    module: ModuleId,
+    /// The name in `module`, `*` when it is the module's namespace
+    name: String,
+}
+
+/// Follows `name` exported by `id` through re-exports and `export *` to the
+/// module that declares it
+fn resolve_export(graph: &ModuleGraph, id: ModuleId, name: &str) -> Option<Origin> {
+    let mut visited = HashSet::new();
+    resolve_export_inner(graph, id, name, &mut visited)
+}
+
+fn resolve_export_inner(
+    graph: &ModuleGraph,
+    id: ModuleId,
+    name: &str,
+    visited: &mut HashSet<(ModuleId, String)>,
+) -> Option<Origin> {
+    if !visited.insert((id, name.to_string())) {
+        return None;
+    }
+    if let Some(export) = graph.exports(id).iter().find(|e| e.name == name) {
+        let Some(from) = &export.from else {
+            return Some(Origin { module: id, name: name.to_string() });
+        };
+        let target = graph.resolved(id, &from.request)?;
+        if from.name == "*" {
+            return Some(Origin { module: target, name: from.name.clone() });
+        }
+        return resolve_export_inner(graph, target, &from.name, visited);
+    }
+    // `export *` never re-exports the default export
+    if name == "default" {
+        return None;
+    }
+    graph.star_exports(id).iter().find_map(|&star| resolve_export_inner(graph, star, name, visited))
+}
+
+/// Number of modules other than `skip` reachable from any of `starts`
+fn reached_without(graph: &ModuleGraph, starts: &[ModuleId], skip: ModuleId) -> usize {
+    let reached = graph.reached_from(starts);
+    reached.iter().filter(|&&r| r).count() - usize::from(reached[skip.index()])
+}
+
+/// The specifier importing `target` directly from `from`: a package subpath for
+/// files in `node_modules`, a relative path otherwise, without the extension
+/// and `/index`
+fn direct_request(from: &Path, target: &Path) -> String {
+    let target = target.with_extension("");
+    let target = if target.file_name().is_some_and(|n| n == "index") {
+        target.parent().unwrap_or(&target).to_path_buf()
+    } else {
+        target
+    };
+
+    let components: Vec<Component> = target.components().collect();
+    if let Some(pos) =
+        components.iter().rposition(|c| *c == Component::Normal("node_modules".as_ref()))
+    {
+        let rest: PathBuf = components[pos + 1..].iter().collect();
+        return rest.to_string_lossy().replace('\\', "/");
+    }
+
+    let from_dir = from.parent().unwrap_or(from);
+    let from_parts: Vec<Component> = from_dir.components().collect();
+    let common = from_parts.iter().zip(&components).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from_parts.len() - common];
+    parts.extend(components[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    let joined = parts.join("/");
+    if joined.starts_with("..") {
+        joined
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        format!("./{}", joined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, min_fan_out: usize) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            min_fan_out: Some(min_fan_out),
+            min_savings: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    #[test]
+    fn test_barrel_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "src/ui/index.ts",
+            "export * from './button';\nexport { Table as Grid } from './table';\n\
+             export * as icons from './icons';",
+        );
+        create_test_file(root, "src/ui/button.ts", "export const Button = 1;");
+        create_test_file(root, "src/ui/table.ts", "import './heavy';\nexport const Table = 1;");
+        create_test_file(root, "src/ui/heavy.ts", "export const heavy = 1;");
+        create_test_file(root, "src/ui/icons.ts", "export const Icon = 1;");
+        create_test_file(root, "src/app/page.ts", "import { Button, Grid } from '../ui';");
+        create_test_file(root, "src/app/all.ts", "import * as ui from '../ui';");
+        create_test_file(root, "src/app/needs.ts", "import { Button, Grid, icons } from '../ui';");
+
+        let result = run_barrels_check(config(root, 3)).unwrap();
+        assert_eq!(result.barrels.len(), 1);
+        let barrel = &result.barrels[0];
+        assert_eq!(barrel.file, "src/ui/index.ts");
+        assert_eq!((barrel.fan_out, barrel.re_exports, barrel.exports), (3, 3, 3));
+        assert_eq!(barrel.reachable, 4);
+
+        // The namespace import can't be narrowed, and needs.ts uses every re-export
+        assert_eq!(barrel.imports.len(), 1);
+        let import = &barrel.imports[0];
+        assert_eq!(import.from_file, "src/app/page.ts");
+        assert_eq!((import.through, import.needed), (4, 3));
+        let suggestions: Vec<(&str, &str, &str)> = import
+            .suggestions
+            .iter()
+            .map(|s| (s.name.as_str(), s.origin_name.as_str(), s.request.as_str()))
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![("Button", "Button", "../ui/button"), ("Grid", "Table", "../ui/table")]
+        );
+
+        assert!(run_barrels_check(config(root, 4)).unwrap().barrels.is_empty());
+    }
+
+    #[test]
+    fn test_barrel_in_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "src/lib/index.ts",
+            "export * from './a';\nexport * from './b';\nexport * from './c';",
+        );
+        create_test_file(root, "src/lib/a.ts", "import './heavy';\nexport const a = 1;");
+        create_test_file(root, "src/lib/heavy.ts", "export const heavy = 1;");
+        // b imports the barrel back, so it needs everything the barrel loads
+        create_test_file(root, "src/lib/b.ts", "import { a } from './index';\nexport const b = a;");
+        create_test_file(root, "src/lib/c.ts", "export const c = 1;");
+        create_test_file(root, "src/app/one.ts", "import { c } from '../lib';");
+        create_test_file(root, "src/app/two.ts", "import { c } from '../lib';");
+        create_test_file(root, "src/app/three.ts", "import { b } from '../lib';");
+
+        let result = run_barrels_check(config(root, 3)).unwrap();
+        assert_eq!(result.barrels.len(), 1);
+        let barrel = &result.barrels[0];
+        assert_eq!(barrel.reachable, 4);
+        let imports: Vec<(&str, usize, usize)> =
+            barrel.imports.iter().map(|i| (i.from_file.as_str(), i.through, i.needed)).collect();
+        // three.ts needs everything through b, while b itself only needs a
+        assert_eq!(
+            imports,
+            vec![("src/app/one.ts", 4, 1), ("src/app/two.ts", 4, 1), ("src/lib/b.ts", 4, 2)]
+        );
+    }
+
+    #[test]
+    fn test_direct_request() {
+        assert_eq!(
+            direct_request(Path::new("/p/src/app/page.ts"), Path::new("/p/src/ui/button.tsx")),
+            "../ui/button"
+        );
+        assert_eq!(
+            direct_request(Path::new("/p/src/page.ts"), Path::new("/p/src/ui/index.ts")),
+            "./ui"
+        );
+        assert_eq!(
+            direct_request(
+                Path::new("/p/src/page.ts"),
+                Path::new("/p/node_modules/@mui/icons-material/esm/Add.js")
+            ),
+            "@mui/icons-material/esm/Add"
+        );
+    }
+}
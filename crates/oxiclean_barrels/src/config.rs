@@ -0,0 +1,77 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+pub const DEFAULT_MIN_FAN_OUT: usize = 5;
+pub const DEFAULT_MIN_SAVINGS: usize = 1;
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "barrels")]
+#[command(about = "Find barrel files and the imports that pull in more than they use")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Minimum number of modules a file must re-export from to count as a barrel [default: 5]
+    #[arg(long)]
+    pub min_fan_out: Option<usize>,
+
+    /// Only report imports that importing directly would shrink by at least
+    /// this many modules [default: 1]
+    #[arg(long)]
+    pub min_savings: Option<usize>,
+}
+
+/// The `[barrels]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct BarrelsSection {
+    min_fan_out: Option<usize>,
+    min_savings: Option<usize>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+
+        let section: BarrelsSection = self.shared.check_section("barrels")?;
+        self.min_fan_out = self.min_fan_out.or(section.min_fan_out);
+        self.min_savings = self.min_savings.or(section.min_savings);
+        debug!("Using min fan-out {} and min savings {}", self.min_fan_out(), self.min_savings());
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    pub fn min_fan_out(&self) -> usize {
+        self.min_fan_out.unwrap_or(DEFAULT_MIN_FAN_OUT)
+    }
+
+    pub fn min_savings(&self) -> usize {
+        self.min_savings.unwrap_or(DEFAULT_MIN_SAVINGS)
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
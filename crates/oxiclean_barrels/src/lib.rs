@@ -0,0 +1,51 @@
+//! Barrel file analysis for JavaScript/TypeScript projects.
+//!
+//! A barrel is an index file made mostly of re-exports. Importing one name from
+//! a barrel loads every module the barrel re-exports, which is the most common
+//! source of the bloat reported by `oxiclean_import_bloat`. This crate finds
+//! barrels, compares what each import of one pulls in with what the imported
+//! names actually need, and suggests the direct import for each name.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_barrels::{Config, run_barrels_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     min_fan_out: Some(5),
+//!     min_savings: Some(10),
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_barrels_check(cfg.clone())?;
+//!
+//! if result.warning_count() > 0 {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_barrels::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_barrels_check};
+pub use config::{Config, DEFAULT_MIN_FAN_OUT, DEFAULT_MIN_SAVINGS};
+pub use reporter::{print_no_barrel_issues_message, print_warnings_tree};
+pub use types::{Barrel, BarrelImport, CheckResult, Suggestion};
@@ -0,0 +1,101 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::CheckResult};
+
+pub fn print_no_barrel_issues_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No wasteful barrel imports detected");
+    writeln!(
+        writer,
+        "{} No wasteful barrel imports ({} barrel files found).",
+        "✓".green().bold(),
+        result.barrels.len()
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints each barrel with its wasteful imports and the direct imports to use instead
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} barrels", result.barrels.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Barrel imports pulling in unused modules ({} imports)\n",
+        "⚠".yellow().bold(),
+        result.warning_count().to_string().yellow()
+    )?;
+
+    for barrel in result.barrels.iter().filter(|b| !b.imports.is_empty()) {
+        writeln!(
+            writer,
+            "{} {}",
+            display(&barrel.file).blue().bold(),
+            format!(
+                "(barrel: {} of {} exports re-exported from {} modules, pulls in {} modules)",
+                barrel.re_exports, barrel.exports, barrel.fan_out, barrel.reachable
+            )
+            .dimmed()
+        )?;
+
+        for (idx, import) in barrel.imports.iter().enumerate() {
+            let last = idx == barrel.imports.len() - 1;
+            let prefix = if last { "└──" } else { "├──" };
+            writeln!(
+                writer,
+                "{} {}  {}  {} modules, needs {} {}",
+                prefix.dimmed(),
+                format!("{}:{}", display(&import.from_file), import.line).blue(),
+                format!("import '{}'", import.request).dimmed(),
+                import.through.to_string().red().bold(),
+                import.needed.to_string().green(),
+                format!("(saves {})", import.savings()).yellow()
+            )?;
+
+            let indent = if last { "    " } else { "│   " };
+            for suggestion in &import.suggestions {
+                let binding = match suggestion.origin_name.as_str() {
+                    "*" => format!("* as {}", suggestion.name),
+                    "default" => suggestion.name.clone(),
+                    origin if origin != suggestion.name => {
+                        format!("{{ {} as {} }}", origin, suggestion.name)
+                    }
+                    _ => format!("{{ {} }}", suggestion.name),
+                };
+                writeln!(
+                    writer,
+                    "{}  {} import {} from '{}'",
+                    indent.dimmed(),
+                    "→".dimmed(),
+                    binding,
+                    suggestion.request
+                )?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    let savings: usize = result.barrels.iter().flat_map(|b| &b.imports).map(|i| i.savings()).sum();
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Barrel files: {}", result.barrels.len())?;
+    writeln!(writer, "  Wasteful imports: {}", result.warning_count().to_string().yellow().bold())?;
+    writeln!(writer, "  Modules saved by importing directly: {}", savings.to_string().green())?;
+
+    writer.flush()?;
+    Ok(())
+}
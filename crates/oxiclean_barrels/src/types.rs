@@ -0,0 +1,66 @@
+/// A module made mostly of re-exports
+#[derive(Debug, Clone)]
+pub struct Barrel {
+    /// The barrel file, relative to the root
+    pub file: String,
+    /// Distinct modules it re-exports from
+    pub fan_out: usize,
+    /// Re-export statements and re-exported names, out of all exports
+    pub re_exports: usize,
+    pub exports: usize,
+    /// Modules the barrel pulls in, not counting itself
+    pub reachable: usize,
+    /// Imports of the barrel that pull in more than they need, most wasteful first
+    pub imports: Vec<BarrelImport>,
+}
+
+/// An import of a barrel, and what it would cost to import the used names directly
+#[derive(Debug, Clone)]
+pub struct BarrelImport {
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// Modules the import pulls in through the barrel, not counting the barrel
+    pub through: usize,
+    /// Modules the imported names actually need
+    pub needed: usize,
+    /// Where to import each used name from instead
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl BarrelImport {
+    /// Modules that importing directly would no longer load
+    pub fn savings(&self) -> usize {
+        self.through - self.needed
+    }
+}
+
+/// A direct import replacing a name imported through a barrel
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// The name as imported from the barrel
+    pub name: String,
+    /// The name in the module that declares it, `*` for a namespace re-export
+    pub origin_name: String,
+    /// The declaring file, relative to the root
+    pub origin_file: String,
+    /// Specifier to import it from
+    pub request: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Detected barrels, those with the most wasteful imports first
+    pub barrels: Vec<Barrel>,
+    pub files_analyzed: usize,
+}
+
+impl CheckResult {
+    /// Number of wasteful barrel imports across all barrels
+    pub fn warning_count(&self) -> usize {
+        self.barrels.iter().map(|b| b.imports.len()).sum()
+    }
+}
@@ -0,0 +1,521 @@
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use ignore::WalkBuilder;
+use log::{debug, info, trace};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use xxhash_rust::xxh3::{Xxh3, xxh3_64};
+
+use crate::{
+    constants::{CACHE_DIR_NAME, LOCKFILE_NAMES},
+    parser::parse_module,
+    resolver::{ResolveOptions, probed_dirs, resolve},
+    types::{ParsedModule, Specifier},
+};
+
+/// Bump whenever the parser's output or the cache layout changes
+const CACHE_FORMAT: u32 = 6;
+
+const CACHE_FILE_NAME: &str = "cache.json";
+
+/// Parse and resolve results for a graph build, optionally persisted between runs.
+///
+/// Parsed imports and exports are keyed by the file's content hash, so an unchanged file is
+/// never re-parsed. Resolutions are keyed by `(from_dir, request)` and are thrown
+/// away whenever a package.json, tsconfig/jsconfig, lockfile or the resolve
+/// options change. A cached resolution is also dropped when its target no longer
+/// exists or when a file was added to or removed from a directory it probed,
+/// which may hold a candidate of higher priority.
+#[derive(Debug, Default)]
+pub struct AnalysisCache {
+    root: PathBuf,
+    /// Where the cache is saved; `None` keeps it in memory only
+    file: Option<PathBuf>,
+    fingerprint: u64,
+    /// Resolve options the cache was loaded with, to find the directories each
+    /// resolution probed when saving
+    opts: ResolveOptions,
+    /// Parsed modules loaded from disk, keyed by absolute path
+    stored: HashMap<PathBuf, ParsedFile>,
+    /// Parsed modules seen during this run
+    parsed: DashMap<PathBuf, ParsedFile>,
+    resolutions: DashMap<(PathBuf, String), Option<PathBuf>>,
+    reused: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ParsedFile {
+    hash: u64,
+    module: ParsedModule,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredResolution {
+    from: String,
+    request: String,
+    to: String,
+}
+
+/// On-disk layout; paths are relative to the root so the cache survives moving the checkout
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheData {
+    version: String,
+    fingerprint: u64,
+    files: HashMap<String, ParsedFile>,
+    resolutions: Vec<StoredResolution>,
+    /// Listing hash of every directory probed by the stored resolutions; None
+    /// for a directory that didn't exist
+    listings: HashMap<String, Option<u64>>,
+}
+
+fn cache_version() -> String {
+    format!("{}-{}", CACHE_FORMAT, env!("CARGO_PKG_VERSION"))
+}
+
+/// `node_modules/.cache/oxiclean` when the project has `node_modules`, otherwise `.oxiclean-cache`
+pub fn default_cache_dir(root: &Path) -> PathBuf {
+    let node_modules = root.join("node_modules");
+    if node_modules.is_dir() {
+        node_modules.join(".cache").join("oxiclean")
+    } else {
+        root.join(CACHE_DIR_NAME)
+    }
+}
+
+impl AnalysisCache {
+    /// A cache that lives for a single run
+    pub fn in_memory(root: &Path) -> Self {
+        Self { root: root.to_path_buf(), ..Default::default() }
+    }
+
+    /// Loads the cache saved in `cache_dir`, if any. A missing, unreadable or
+    /// outdated cache file is not an error; the run simply starts cold.
+    pub fn load(root: &Path, cache_dir: &Path, opts: &ResolveOptions) -> Self {
+        let file = cache_dir.join(CACHE_FILE_NAME);
+        let fingerprint = resolution_fingerprint(root, opts);
+        let mut cache = Self {
+            root: root.to_path_buf(),
+            file: Some(file.clone()),
+            fingerprint,
+            opts: opts.clone(),
+            ..Default::default()
+        };
+
+        let data = match fs::read(&file) {
+            Ok(bytes) => match serde_json::from_slice::<CacheData>(&bytes) {
+                Ok(data) => data,
+                Err(e) => {
+                    debug!("Ignoring unreadable cache {}: {}", file.display(), e);
+                    return cache;
+                }
+            },
+            Err(_) => {
+                debug!("No cache found at {}", file.display());
+                return cache;
+            }
+        };
+        if data.version != cache_version() {
+            debug!("Ignoring cache from version {}", data.version);
+            return cache;
+        }
+
+        cache.stored =
+            data.files.into_iter().map(|(path, parsed)| (root.join(path), parsed)).collect();
+
+        if data.fingerprint == fingerprint {
+            let unchanged: HashSet<PathBuf> = data
+                .listings
+                .into_par_iter()
+                .map(|(dir, listing)| (root.join(dir), listing))
+                .filter(|(dir, listing)| dir_listing(dir) == *listing)
+                .map(|(dir, _)| dir)
+                .collect();
+            data.resolutions
+                .into_par_iter()
+                .filter_map(|r| {
+                    let (from, to) = (root.join(&r.from), root.join(&r.to));
+                    let fresh = to.is_file()
+                        && probed_dirs(opts, &from, &r.request)
+                            .iter()
+                            .all(|dir| unchanged.contains(dir));
+                    if !fresh {
+                        trace!("Dropping cached resolution of '{}' from {}", r.request, r.from);
+                    }
+                    fresh.then_some(((from, r.request), Some(to)))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .for_each(|(key, to)| {
+                    cache.resolutions.insert(key, to);
+                });
+        } else {
+            debug!("Package manifests or resolve options changed, discarding cached resolutions");
+        }
+
+        info!(
+            "Loaded cache with {} files and {} resolutions",
+            cache.stored.len(),
+            cache.resolutions.len()
+        );
+        cache
+    }
+
+    /// Every import specifier of a file, type-only ones included (unlike the
+    /// free [`crate::imports_for`]), re-parsing it only if its content changed
+    pub fn imports_for(&self, file: &Path) -> Result<Vec<Specifier>> {
+        Ok(self.parse(file)?.imports)
+    }
+
+    /// The imports and exports of a file, re-parsing it only if its content changed
+    pub fn parse(&self, file: &Path) -> Result<ParsedModule> {
+        if let Some(entry) = self.parsed.get(file) {
+            return Ok(entry.module.clone());
+        }
+
+        let bytes = fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let hash = xxh3_64(&bytes);
+        let module = match self.stored.get(file) {
+            Some(stored) if stored.hash == hash => {
+                trace!("Reusing cached imports for {}", file.display());
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                stored.module.clone()
+            }
+            _ => {
+                trace!("Parsing file for imports: {}", file.display());
+                let src = String::from_utf8(bytes)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                parse_module(file, &src)
+            }
+        };
+
+        self.parsed.insert(file.to_path_buf(), ParsedFile { hash, module: module.clone() });
+        Ok(module)
+    }
+
+    /// Resolves an import request, see [`crate::resolve`]
+    pub fn resolve(
+        &self,
+        opts: &ResolveOptions,
+        from_file: &Path,
+        request: &str,
+    ) -> Result<Option<PathBuf>> {
+        resolve(&self.root, opts, from_file, request, &self.resolutions)
+    }
+
+    /// Forgets the imports of changed files so they are re-read on next use
+    pub fn invalidate_files(&self, files: &[PathBuf]) {
+        for file in files {
+            trace!("Invalidating cached imports for {}", file.display());
+            self.parsed.remove(file);
+        }
+    }
+
+    /// Forgets every resolution, e.g. after files were added or removed or a
+    /// package manifest changed
+    pub fn invalidate_resolutions(&self) {
+        debug!("Invalidating {} cached resolutions", self.resolutions.len());
+        self.resolutions.clear();
+    }
+
+    /// Number of files whose imports were reused instead of re-parsed
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    /// Writes the cache back to disk (a no-op for in-memory caches)
+    pub fn save(&self) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+
+        // Created first so listings of the directory holding the cache are stable
+        let dir = file.parent().unwrap_or(&self.root);
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n")?;
+        }
+
+        // Keep entries for files outside this run's graph, unless the file is gone
+        let mut files: HashMap<String, ParsedFile> = self
+            .stored
+            .iter()
+            .filter(|(path, _)| !self.parsed.contains_key(*path) && path.is_file())
+            .map(|(path, parsed)| (self.rel(path), parsed.clone()))
+            .collect();
+        for entry in self.parsed.iter() {
+            files.insert(self.rel(entry.key()), entry.value().clone());
+        }
+
+        let mut probed: HashSet<PathBuf> = HashSet::new();
+        let resolutions = self
+            .resolutions
+            .iter()
+            .filter_map(|entry| {
+                let ((from, request), to) = (entry.key(), entry.value());
+                let to = to.as_ref()?;
+                probed.extend(probed_dirs(&self.opts, from, request));
+                Some(StoredResolution {
+                    from: self.rel(from),
+                    request: request.clone(),
+                    to: self.rel(to),
+                })
+            })
+            .collect();
+        let listings =
+            probed.into_par_iter().map(|dir| (self.rel(&dir), dir_listing(&dir))).collect();
+
+        let data = CacheData {
+            version: cache_version(),
+            fingerprint: self.fingerprint,
+            files,
+            resolutions,
+            listings,
+        };
+
+        // Write to a temporary file first so an interrupted run never leaves a truncated cache
+        let tmp = file.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&data)?)
+            .with_context(|| format!("Failed to write cache {}", tmp.display()))?;
+        fs::rename(&tmp, file)
+            .with_context(|| format!("Failed to write cache {}", file.display()))?;
+        debug!("Saved cache with {} files to {}", data.files.len(), file.display());
+        Ok(())
+    }
+
+    fn rel(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().to_string()
+    }
+}
+
+/// Hash of the names in a directory, or None if it doesn't exist (or isn't a
+/// directory)
+fn dir_listing(dir: &Path) -> Option<u64> {
+    let mut names: Vec<_> =
+        fs::read_dir(dir).ok()?.filter_map(|e| e.ok()).map(|e| e.file_name()).collect();
+    names.sort();
+    let mut hasher = Xxh3::new();
+    for name in names {
+        hasher.update(name.as_encoded_bytes());
+        hasher.update(b"/");
+    }
+    Some(hasher.digest())
+}
+
+/// Hashes everything that can change how a request resolves other than the
+/// contents of the directories it probes: the resolve options, every package.json and
+/// tsconfig/jsconfig file in the project, and the lockfiles.
+fn resolution_fingerprint(root: &Path, opts: &ResolveOptions) -> u64 {
+    let mut hasher = Xxh3::new();
+
+    let mut aliases: Vec<_> = opts.aliases.iter().collect();
+    aliases.sort();
+    for (alias, targets) in aliases {
+        hasher.update(alias.as_bytes());
+        for target in targets {
+            hasher.update(target.as_bytes());
+        }
+    }
+    for condition in &opts.conditions {
+        hasher.update(condition.as_bytes());
+    }
+
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .filter_entry(|e| e.file_name() != "node_modules")
+        .build();
+    let mut manifests: Vec<PathBuf> = walker
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|path| {
+            path.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+                name == "package.json"
+                    || (name.starts_with("tsconfig") && name.ends_with(".json"))
+                    || name == "jsconfig.json"
+                    || LOCKFILE_NAMES.contains(&name)
+            })
+        })
+        .collect();
+    manifests.sort();
+
+    for path in manifests {
+        trace!("Fingerprinting {}", path.display());
+        hasher.update(path.strip_prefix(root).unwrap_or(&path).to_string_lossy().as_bytes());
+        if let Ok(bytes) = fs::read(&path) {
+            hasher.update(&bytes);
+        }
+    }
+
+    hasher.digest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    #[test]
+    fn test_default_cache_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert_eq!(default_cache_dir(root), root.join(".oxiclean-cache"));
+
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        assert_eq!(default_cache_dir(root), root.join("node_modules/.cache/oxiclean"));
+    }
+
+    #[test]
+    fn test_imports_are_reused_until_content_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_dir = root.join(".oxiclean-cache");
+        let opts = ResolveOptions::default();
+
+        let file = create_test_file(root, "src/index.ts", "import './a';");
+        create_test_file(root, "src/a.ts", "// a");
+
+        let cold = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(cold.imports_for(&file).unwrap().len(), 1);
+        assert!(cold.resolve(&opts, &file, "./a").unwrap().is_some());
+        assert_eq!(cold.reused(), 0);
+        cold.save().unwrap();
+        assert!(cache_dir.join(".gitignore").exists());
+
+        let warm = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(warm.resolutions.len(), 1);
+        assert_eq!(warm.imports_for(&file).unwrap()[0].request, "./a");
+        assert_eq!(warm.reused(), 1);
+
+        fs::write(&file, "import './a'; import './b';").unwrap();
+        let changed = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(changed.imports_for(&file).unwrap().len(), 2);
+        assert_eq!(changed.reused(), 0);
+    }
+
+    #[test]
+    fn test_manifest_change_discards_resolutions() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_dir = root.join(".oxiclean-cache");
+        let opts = ResolveOptions::default();
+
+        create_test_file(root, "package.json", r#"{"name": "app"}"#);
+        let file = create_test_file(root, "src/index.ts", "import './a';");
+        create_test_file(root, "src/a.ts", "// a");
+
+        let cold = AnalysisCache::load(root, &cache_dir, &opts);
+        cold.imports_for(&file).unwrap();
+        cold.resolve(&opts, &file, "./a").unwrap();
+        cold.save().unwrap();
+
+        create_test_file(root, "package.json", r#"{"name": "app", "type": "module"}"#);
+        let warm = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(warm.resolutions.len(), 0);
+        // Parsed imports only depend on file content and survive
+        warm.imports_for(&file).unwrap();
+        assert_eq!(warm.reused(), 1);
+    }
+
+    #[test]
+    fn test_missing_targets_are_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_dir = root.join(".oxiclean-cache");
+        let opts = ResolveOptions::default();
+
+        let file = create_test_file(root, "src/index.ts", "import './a';");
+        let a = create_test_file(root, "src/a.ts", "// a");
+
+        let cold = AnalysisCache::load(root, &cache_dir, &opts);
+        cold.resolve(&opts, &file, "./a").unwrap();
+        cold.save().unwrap();
+
+        fs::remove_file(a).unwrap();
+        let warm = AnalysisCache::load(root, &cache_dir, &opts);
+        assert!(warm.resolve(&opts, &file, "./a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_higher_priority_candidates_discard_resolutions() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_dir = root.join(".oxiclean-cache");
+        let opts = ResolveOptions::default();
+
+        let file = create_test_file(root, "src/index.ts", "import './a'; import './utils';");
+        create_test_file(root, "src/a.js", "// a");
+        create_test_file(root, "src/utils.ts", "// utils");
+
+        let cold = AnalysisCache::load(root, &cache_dir, &opts);
+        let a = cold.resolve(&opts, &file, "./a").unwrap().unwrap();
+        assert!(a.ends_with("src/a.js"));
+        cold.resolve(&opts, &file, "./utils").unwrap();
+        cold.save().unwrap();
+
+        // Files elsewhere don't affect these resolutions
+        create_test_file(root, "lib/a.ts", "// a");
+        let warm = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(warm.resolutions.len(), 2);
+        warm.save().unwrap();
+
+        create_test_file(root, "src/a.ts", "// a");
+        create_test_file(root, "src/utils/index.ts", "// utils");
+        let warm = AnalysisCache::load(root, &cache_dir, &opts);
+        assert_eq!(warm.resolutions.len(), 0);
+        let a = warm.resolve(&opts, &file, "./a").unwrap().unwrap();
+        assert!(a.ends_with("src/a.ts"));
+        let utils = warm.resolve(&opts, &file, "./utils").unwrap().unwrap();
+        assert!(utils.ends_with("src/utils/index.ts"));
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let opts = ResolveOptions::default();
+
+        let file = create_test_file(root, "src/index.ts", "import './a';");
+        let cache = AnalysisCache::in_memory(root);
+        assert_eq!(cache.imports_for(&file).unwrap().len(), 1);
+        assert!(cache.resolve(&opts, &file, "./a").unwrap().is_none());
+
+        fs::write(&file, "import './a'; import './b';").unwrap();
+        create_test_file(root, "src/a.ts", "// a");
+        // Stale until invalidated
+        assert_eq!(cache.imports_for(&file).unwrap().len(), 1);
+        assert!(cache.resolve(&opts, &file, "./a").unwrap().is_none());
+
+        cache.invalidate_files(std::slice::from_ref(&file));
+        cache.invalidate_resolutions();
+        assert_eq!(cache.imports_for(&file).unwrap().len(), 2);
+        assert!(cache.resolve(&opts, &file, "./a").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_corrupt_cache_starts_cold() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_dir = root.join(".oxiclean-cache");
+        create_test_file(&cache_dir, "cache.json", "{ not json");
+
+        let cache = AnalysisCache::load(root, &cache_dir, &ResolveOptions::default());
+        assert!(cache.stored.is_empty());
+    }
+}
@@ -1,23 +1,119 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use ignore::WalkBuilder;
 use log::{debug, trace};
-use std::{collections::HashMap, path::PathBuf};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use crate::constants::JS_TS_EXTENSIONS;
+use crate::{
+    config_file::EntrySection,
+    constants::{DEFAULT_INCLUDE, DEFAULT_TEST_PATTERNS, IGNORE_FILE_NAME, JS_TS_EXTENSIONS},
+    glob::GlobMatcher,
+    resolver::resolve_file,
+    workspace::{WorkspacePackage, find_workspace_packages},
+};
 
+/// Build output directories that package.json fields commonly point into.
+/// When the built file doesn't exist we look for its source under `src/` instead.
+const BUILD_OUTPUT_DIRS: &[&str] = &["dist", "lib", "build", "out", "esm", "cjs", "es"];
+
+/// Next.js app router files that define routes
+const NEXT_APP_ROUTE_FILES: &[&str] = &[
+    "page",
+    "layout",
+    "template",
+    "loading",
+    "error",
+    "global-error",
+    "not-found",
+    "default",
+    "route",
+];
+
+/// How entry files are selected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryMode {
+    /// Every source file (filtered by entry globs) is an entry
+    #[default]
+    Files,
+    /// Only true roots: package.json entry fields and framework conventions
+    Auto,
+}
+
+impl FromStr for EntryMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "files" => Ok(EntryMode::Files),
+            "auto" => Ok(EntryMode::Auto),
+            other => Err(anyhow!("Unknown entry mode '{}' (expected 'files' or 'auto')", other)),
+        }
+    }
+}
+
+impl fmt::Display for EntryMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryMode::Files => write!(f, "files"),
+            EntryMode::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CollectorConfig {
     pub root: PathBuf,
-    pub entry_glob: Option<String>,
+    /// How entry files are selected
+    pub entries: EntryMode,
+    /// Glob patterns (relative to root) selecting entry files; `!`-prefixed patterns exclude
+    pub entry_glob: Vec<String>,
+    /// Glob patterns selecting project source files (defaults to `**/src/**`, matched
+    /// against the absolute path, when no include or entry glob is given)
+    pub include: Vec<String>,
+    /// Glob patterns for source files to skip
+    pub exclude: Vec<String>,
+    /// Keep test files (`*.test.*`, `*.spec.*`), which are excluded by default
+    pub include_tests: bool,
     pub tsconfig_paths: HashMap<String, Vec<String>>,
 }
 
-pub fn collect_entries(cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
-    debug!("Collecting entry files");
-    // If entries glob provided, walk and filter by suffix; else treat all top-level src files as entries
+/// Collects all JS/TS source files under the root that pass the include/exclude rules.
+///
+/// Files ignored by `.gitignore`, `.ignore` or `.oxicleanignore` are never returned.
+pub fn collect_source_files(cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
+    debug!("Collecting source files");
+    let default_include =
+        cfg.include.is_empty() && !cfg.entry_glob.iter().any(|g| !g.trim_start().starts_with('!'));
+    let include = if !cfg.include.is_empty() {
+        GlobMatcher::new(&cfg.include)?
+    } else if !default_include {
+        // Entry globs already say where to look, so don't restrict to src/
+        GlobMatcher::new::<&str>(&[])?
+    } else {
+        GlobMatcher::new(DEFAULT_INCLUDE)?
+    };
+
+    let mut exclude_patterns = cfg.exclude.clone();
+    if !cfg.include_tests {
+        exclude_patterns.extend(DEFAULT_TEST_PATTERNS.iter().map(|p| p.to_string()));
+    }
+    let exclude = GlobMatcher::new(&exclude_patterns)?;
+
     let mut files: Vec<PathBuf> = Vec::new();
     let root = &cfg.root;
     debug!("Walking directory tree from root: {}", root.display());
-    let walker = WalkBuilder::new(root).hidden(false).ignore(true).git_ignore(true).build();
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .ignore(true)
+        .git_ignore(true)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME)
+        .build();
 
     for res in walker {
         let dent = res?;
@@ -26,37 +122,361 @@ pub fn collect_entries(cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
             continue;
         }
 
-        // Skip test files (*.test.*, *.spec.*)
-        let path_str = p.to_string_lossy();
-        if path_str.contains(".test.") || path_str.contains(".spec.") {
-            trace!("Skipping test file: {}", path_str);
+        if !is_js_ts_file(p) {
             continue;
         }
 
-        if let Some(ext) = p.extension().and_then(|e| e.to_str())
-            && JS_TS_EXTENSIONS.contains(&ext)
-        {
-            // If entry_glob is set, check if the relative path from root contains the pattern
-            if let Some(gl) = &cfg.entry_glob {
-                if let Ok(rel_path) = p.strip_prefix(root) {
-                    let rel_str = rel_path.to_string_lossy();
-                    // Match if relative path contains the glob pattern
-                    if rel_str.contains(gl) {
-                        trace!("Matched entry file with glob '{}': {}", gl, rel_str);
-                        files.push(p.to_path_buf());
-                    }
+        let rel_path = p.strip_prefix(root).unwrap_or(p);
+        if exclude.has_includes() && exclude.is_match(rel_path) {
+            trace!("Skipping excluded file: {}", rel_path.display());
+            continue;
+        }
+        // The default include is matched against the absolute path, like the
+        // original `/src/` heuristic: a root inside a `src/` directory keeps
+        // every file
+        if !include.is_match(if default_include { p } else { rel_path }) {
+            continue;
+        }
+
+        trace!("Found source file: {}", rel_path.display());
+        files.push(p.to_path_buf());
+    }
+    debug!("Collected {} source files", files.len());
+    Ok(files)
+}
+
+/// Extra entries and ignored files of the checks looking for unused code
+#[derive(Debug, Clone)]
+pub struct ExtraEntries {
+    patterns: Vec<String>,
+    ignored: GlobMatcher,
+}
+
+impl ExtraEntries {
+    /// Combine the patterns of a check's config file section with the ones given
+    /// on the command line; both add up, config file patterns first
+    pub fn new(section: EntrySection, entries: &[String], ignore: &[String]) -> Result<Self> {
+        let patterns: Vec<String> = section.entries.into_iter().chain(entries.to_vec()).collect();
+        let ignore: Vec<String> = section.ignore.into_iter().chain(ignore.to_vec()).collect();
+        debug!(
+            "Using {} extra entry patterns and {} ignore patterns",
+            patterns.len(),
+            ignore.len()
+        );
+        Ok(Self { patterns, ignored: GlobMatcher::new(&ignore)? })
+    }
+
+    /// Test files and files matching the extra entry patterns, wherever they
+    /// are under the root
+    pub fn collect(&self, cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
+        let extra_cfg = CollectorConfig {
+            include: DEFAULT_TEST_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .chain(self.patterns.clone())
+                .collect(),
+            exclude: Vec::new(),
+            include_tests: true,
+            entry_glob: Vec::new(),
+            ..cfg.clone()
+        };
+        let files = collect_source_files(&extra_cfg)?;
+        debug!("Found {} extra entries", files.len());
+        Ok(files)
+    }
+
+    /// Whether a file, relative to the root, should never be reported
+    pub fn is_ignored(&self, rel_path: &Path) -> bool {
+        self.ignored.is_included(rel_path)
+    }
+}
+
+pub fn collect_entries(cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
+    debug!("Collecting entry files (mode: {})", cfg.entries);
+    // Entries are the source files matching the entry globs (all source files if none are given)
+    let matcher = GlobMatcher::new(&cfg.entry_glob)?;
+    let candidates = match cfg.entries {
+        EntryMode::Files => collect_source_files(cfg)?,
+        EntryMode::Auto => collect_auto_entries(cfg)?,
+    };
+    let files: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|p| {
+            let rel_path = p.strip_prefix(&cfg.root).unwrap_or(p);
+            let matched = matcher.is_match(rel_path);
+            if matched {
+                trace!("Matched entry file: {}", rel_path.display());
+            }
+            matched
+        })
+        .collect();
+    debug!("Collected {} entry files", files.len());
+    Ok(files)
+}
+
+/// Derives entry points from each workspace package's manifest fields
+/// (`main`, `module`, `exports`, `bin`, `browser`) and from framework
+/// conventions (Next.js `pages/` and `app/` routes, Vite HTML entries).
+fn collect_auto_entries(cfg: &CollectorConfig) -> Result<Vec<PathBuf>> {
+    let mut exclude_patterns = cfg.exclude.clone();
+    if !cfg.include_tests {
+        exclude_patterns.extend(DEFAULT_TEST_PATTERNS.iter().map(|p| p.to_string()));
+    }
+    let exclude = GlobMatcher::new(&exclude_patterns)?;
+
+    let mut entries: BTreeSet<PathBuf> = BTreeSet::new();
+    for pkg in find_workspace_packages(&cfg.root) {
+        trace!("Deriving entries for package {:?} at {:?}", pkg.name, pkg.dir);
+        for target in manifest_entry_targets(&pkg.manifest) {
+            let resolved = resolve_manifest_target(&pkg.dir, &target);
+            if resolved.is_empty() {
+                trace!("Could not resolve manifest entry '{}' in {:?}", target, pkg.dir);
+            }
+            entries.extend(resolved);
+        }
+        entries.extend(nextjs_entries(&pkg));
+        entries.extend(html_entries(&pkg.dir));
+    }
+
+    let files: Vec<PathBuf> = entries
+        .into_iter()
+        .filter(|p| {
+            let rel_path = p.strip_prefix(&cfg.root).unwrap_or(p);
+            !(exclude.has_includes() && exclude.is_match(rel_path))
+        })
+        .collect();
+    debug!("Derived {} entry files from package manifests and frameworks", files.len());
+    Ok(files)
+}
+
+/// Collects the file targets of the manifest fields that define entry points
+fn manifest_entry_targets(manifest: &serde_json::Value) -> Vec<String> {
+    let mut targets = Vec::new();
+
+    for field in ["main", "module"] {
+        if let Some(s) = manifest.get(field).and_then(|v| v.as_str()) {
+            targets.push(s.to_string());
+        }
+    }
+
+    match manifest.get("bin") {
+        Some(serde_json::Value::String(s)) => targets.push(s.to_string()),
+        Some(serde_json::Value::Object(map)) => {
+            targets.extend(map.values().filter_map(|v| v.as_str()).map(String::from));
+        }
+        _ => {}
+    }
+
+    match manifest.get("browser") {
+        Some(serde_json::Value::String(s)) => targets.push(s.to_string()),
+        Some(serde_json::Value::Object(map)) => {
+            // browser maps may also point to package names or `false`; keep file paths only
+            targets.extend(
+                map.values()
+                    .filter_map(|v| v.as_str())
+                    .filter(|s| s.starts_with('.'))
+                    .map(String::from),
+            );
+        }
+        _ => {}
+    }
+
+    if let Some(exports) = manifest.get("exports") {
+        collect_export_targets(exports, &mut targets);
+    }
+
+    targets
+}
+
+fn collect_export_targets(value: &serde_json::Value, targets: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) if s.starts_with("./") => targets.push(s.to_string()),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_export_targets(item, targets);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, v) in map {
+                // Type declarations are not runtime entries
+                if key == "types" || key == "typings" {
+                    continue;
                 }
-            } else {
-                // Heuristic: anything under src is considered
-                if p.to_string_lossy().contains("/src/") {
-                    trace!("Found entry file in /src/: {}", p.display());
-                    files.push(p.to_path_buf());
+                collect_export_targets(v, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Resolves a manifest target to source files, mapping build output back to
+/// `src/` when the built file doesn't exist (e.g. `dist/index.js` -> `src/index.ts`)
+fn resolve_manifest_target(pkg_dir: &Path, target: &str) -> Vec<PathBuf> {
+    let target = target.trim_start_matches("./");
+
+    if target.contains('*') {
+        let source_pattern = source_path_for(target)
+            .map(|p| format!("{}.{{{}}}", p, JS_TS_EXTENSIONS.join(",")))
+            .unwrap_or_default();
+        return match GlobMatcher::new(&[target.to_string(), source_pattern]) {
+            Ok(matcher) => walk_js_files(pkg_dir)
+                .into_iter()
+                .filter(|p| matcher.is_included(p.strip_prefix(pkg_dir).unwrap_or(p)))
+                .collect(),
+            Err(e) => {
+                trace!("Ignoring invalid exports pattern '{}': {}", target, e);
+                vec![]
+            }
+        };
+    }
+
+    if let Some(resolved) = resolve_file(&pkg_dir.join(target))
+        && is_js_ts_file(&resolved)
+    {
+        return vec![resolved];
+    }
+
+    source_path_for(target)
+        .and_then(|p| resolve_file(&pkg_dir.join(p)))
+        .filter(|p| is_js_ts_file(p))
+        .into_iter()
+        .collect()
+}
+
+/// Maps a build output path to its likely source path without extension,
+/// e.g. `dist/esm/utils/index.js` -> `src/utils/index`
+fn source_path_for(target: &str) -> Option<String> {
+    let without_ext = match Path::new(target).extension() {
+        Some(ext) => target.strip_suffix(&format!(".{}", ext.to_string_lossy()))?,
+        None => target,
+    };
+    let mut parts: Vec<&str> = without_ext.split('/').collect();
+    let build_dirs = parts.iter().take_while(|p| BUILD_OUTPUT_DIRS.contains(p)).count();
+    if build_dirs == 0 {
+        return None;
+    }
+    parts.drain(..build_dirs);
+    Some(format!("src/{}", parts.join("/")))
+}
+
+/// Next.js route files: everything under `pages/`, route segment files under
+/// `app/`, plus `middleware` and `instrumentation` (also inside `src/`)
+fn nextjs_entries(pkg: &WorkspacePackage) -> Vec<PathBuf> {
+    let has_next_config = ["next.config.js", "next.config.mjs", "next.config.ts"]
+        .iter()
+        .any(|f| pkg.dir.join(f).exists());
+    if !pkg.depends_on("next") && !has_next_config {
+        return vec![];
+    }
+    trace!("Detected Next.js package at {:?}", pkg.dir);
+
+    let mut entries = Vec::new();
+    for base in [pkg.dir.clone(), pkg.dir.join("src")] {
+        let pages = base.join("pages");
+        if pages.is_dir() {
+            entries.extend(walk_js_files(&pages));
+        }
+
+        let app = base.join("app");
+        if app.is_dir() {
+            entries.extend(walk_js_files(&app).into_iter().filter(|p| {
+                p.file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|stem| NEXT_APP_ROUTE_FILES.contains(&stem))
+            }));
+        }
+
+        for special in ["middleware", "instrumentation"] {
+            entries.extend(resolve_file(&base.join(special)).filter(|p| is_js_ts_file(p)));
+        }
+    }
+    entries
+}
+
+/// Module scripts referenced from HTML files at the package root (Vite convention)
+fn html_entries(pkg_dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(pkg_dir) else {
+        return vec![];
+    };
+
+    let mut entries = Vec::new();
+    for html in read_dir.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if html.extension().and_then(|e| e.to_str()) != Some("html") {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&html) else {
+            continue;
+        };
+        for src in module_script_sources(&content) {
+            // Vite treats absolute URLs as relative to the project root
+            let path = match src.strip_prefix('/') {
+                Some(rel) => pkg_dir.join(rel),
+                None => pkg_dir.join(src.trim_start_matches("./")),
+            };
+            match resolve_file(&path).filter(|p| is_js_ts_file(p)) {
+                Some(resolved) => {
+                    trace!("Found module script '{}' in {:?}", src, html);
+                    entries.push(resolved);
                 }
+                None => trace!("Could not resolve module script '{}' in {:?}", src, html),
             }
         }
     }
-    debug!("Collected {} entry files", files.len());
-    Ok(files)
+    entries
+}
+
+/// Extracts the `src` of every `<script type="module">` tag
+fn module_script_sources(html: &str) -> Vec<String> {
+    let mut sources = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        let tag_rest = &rest[start..];
+        let Some(end) = tag_rest.find('>') else {
+            break;
+        };
+        let tag = &tag_rest[..end];
+        if attribute_value(tag, "type") == Some("module")
+            && let Some(src) = attribute_value(tag, "src")
+            && !src.contains("://")
+        {
+            sources.push(src.to_string());
+        }
+        rest = &tag_rest[end..];
+    }
+    sources
+}
+
+/// The value of an attribute of a tag (without its closing `>`), quoted or not
+fn attribute_value<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let idx = tag.find(&format!(" {}=", name))?;
+    let value = &tag[idx + name.len() + 2..];
+    match value.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let value = &value[1..];
+            value.find(quote).map(|end| &value[..end])
+        }
+        _ => {
+            let end = value.find(char::is_whitespace).unwrap_or(value.len());
+            Some(&value[..end])
+        }
+    }
+}
+
+fn walk_js_files(dir: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(dir)
+        .hidden(false)
+        .git_ignore(true)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME)
+        .filter_entry(|e| e.file_name() != "node_modules")
+        .build()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && is_js_ts_file(p))
+        .collect()
+}
+
+fn is_js_ts_file(p: &Path) -> bool {
+    p.extension().and_then(|e| e.to_str()).is_some_and(|ext| JS_TS_EXTENSIONS.contains(&ext))
 }
 
 #[cfg(test)]
@@ -90,8 +510,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: None,
+            entry_glob: vec![],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -104,6 +525,23 @@ mod tests {
         assert!(entry_names.contains(&"Button.tsx".to_string()));
     }
 
+    #[test]
+    fn test_default_include_matches_absolute_path() {
+        let temp_dir = TempDir::new().unwrap();
+        // The root itself sits under a src/ directory
+        let root = temp_dir.path().join("src/project");
+        create_test_file(&root, "lib/utils.js", "// utils");
+        create_test_file(&root, "index.js", "// index");
+
+        let cfg = CollectorConfig { root: root.clone(), ..Default::default() };
+        assert_eq!(collect_entries(&cfg).unwrap().len(), 2);
+
+        // An explicit include is relative to the root
+        let cfg =
+            CollectorConfig { root, include: vec!["**/src/**".to_string()], ..Default::default() };
+        assert!(collect_entries(&cfg).unwrap().is_empty());
+    }
+
     #[test]
     fn test_collect_entries_with_glob() {
         let temp_dir = TempDir::new().unwrap();
@@ -115,8 +553,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: Some("index".to_string()),
+            entry_glob: vec!["**/index.*".to_string()],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -135,8 +574,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: None,
+            entry_glob: vec![],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -166,8 +606,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: None,
+            entry_glob: vec![],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -188,8 +629,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: None,
+            entry_glob: vec![],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -207,8 +649,9 @@ mod tests {
 
         let cfg = CollectorConfig {
             root: root.to_path_buf(),
-            entry_glob: Some("pages".to_string()),
+            entry_glob: vec!["src/pages/**".to_string()],
             tsconfig_paths: HashMap::new(),
+            ..Default::default()
         };
 
         let entries = collect_entries(&cfg).unwrap();
@@ -218,4 +661,412 @@ mod tests {
             assert!(entry.to_string_lossy().contains("pages"));
         }
     }
+
+    #[test]
+    fn test_collect_entries_with_double_star_glob() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/App.tsx", "// app");
+        create_test_file(root, "src/pages/Dashboard.tsx", "// dashboard");
+        create_test_file(root, "src/utils.ts", "// utils");
+        create_test_file(root, "lib/Widget.tsx", "// widget");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entry_glob: vec!["src/**/*.tsx".to_string()],
+            tsconfig_paths: HashMap::new(),
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let entry_names: Vec<String> =
+            entries.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert!(entry_names.contains(&"App.tsx".to_string()));
+        assert!(entry_names.contains(&"Dashboard.tsx".to_string()));
+    }
+
+    #[test]
+    fn test_collect_entries_with_multiple_and_negated_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "app/main.tsx", "// main");
+        create_test_file(root, "app/Button.stories.tsx", "// story");
+        create_test_file(root, "packages/ui/lib/index.ts", "// ui");
+        create_test_file(root, "packages/ui/src/internal.ts", "// internal");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entry_glob: vec![
+                "app/**".to_string(),
+                "packages/*/lib/**/*.{ts,tsx}".to_string(),
+                "!**/*.stories.tsx".to_string(),
+            ],
+            tsconfig_paths: HashMap::new(),
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let entry_names: Vec<String> =
+            entries.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert!(entry_names.contains(&"main.tsx".to_string()));
+        assert!(entry_names.contains(&"index.ts".to_string()));
+    }
+
+    #[test]
+    fn test_collect_entries_negated_glob_only_keeps_src_heuristic() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/index.ts", "// index");
+        create_test_file(root, "src/legacy/old.ts", "// old");
+        create_test_file(root, "lib/utils.ts", "// utils");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entry_glob: vec!["!src/legacy/**".to_string()],
+            tsconfig_paths: HashMap::new(),
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("src/index.ts"));
+    }
+
+    #[test]
+    fn test_collect_entries_invalid_glob() {
+        let temp_dir = TempDir::new().unwrap();
+        let cfg = CollectorConfig {
+            root: temp_dir.path().to_path_buf(),
+            entry_glob: vec!["src/[".to_string()],
+            tsconfig_paths: HashMap::new(),
+            ..Default::default()
+        };
+
+        assert!(collect_entries(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_collect_entries_with_include_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "app/main.ts", "// main");
+        create_test_file(root, "packages/ui/lib/Button.tsx", "// button");
+        create_test_file(root, "src/index.ts", "// not included");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            include: vec!["app/**".to_string(), "packages/*/lib/**".to_string()],
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let entry_names: Vec<String> =
+            entries.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert!(entry_names.contains(&"main.ts".to_string()));
+        assert!(entry_names.contains(&"Button.tsx".to_string()));
+    }
+
+    #[test]
+    fn test_collect_entries_with_exclude_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/index.ts", "// index");
+        create_test_file(root, "src/generated/api.ts", "// generated");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            exclude: vec!["src/generated/**".to_string()],
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("src/index.ts"));
+    }
+
+    #[test]
+    fn test_collect_entries_include_tests() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/file.ts", "// file");
+        create_test_file(root, "src/file.test.ts", "// test");
+        create_test_file(root, "src/__tests__/file.spec.ts", "// spec");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entry_glob: vec!["**/__tests__/**".to_string()],
+            include_tests: true,
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("__tests__/file.spec.ts"));
+
+        let cfg = CollectorConfig { entry_glob: vec![], ..cfg };
+        assert_eq!(collect_entries(&cfg).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_collect_entries_respects_oxicleanignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, ".oxicleanignore", "src/vendor/\n");
+        create_test_file(root, "src/index.ts", "// index");
+        create_test_file(root, "src/vendor/lib.ts", "// vendored");
+
+        let cfg = CollectorConfig { root: root.to_path_buf(), ..Default::default() };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("src/index.ts"));
+    }
+
+    #[test]
+    fn test_collect_source_files_ignores_entry_glob() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/index.ts", "// index");
+        create_test_file(root, "src/utils.ts", "// utils");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entry_glob: vec!["!src/utils.ts".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(collect_source_files(&cfg).unwrap().len(), 2);
+        assert_eq!(collect_entries(&cfg).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_extra_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "src/index.ts", "// index");
+        create_test_file(root, "src/index.test.ts", "// test");
+        create_test_file(root, "scripts/build.ts", "// script");
+        create_test_file(root, "tools/seed.ts", "// seed");
+
+        let section = EntrySection {
+            entries: vec!["scripts/**".to_string()],
+            ignore: vec!["src/generated/**".to_string()],
+        };
+        let extra =
+            ExtraEntries::new(section, &["tools/**".to_string()], &["src/legacy.ts".to_string()])
+                .unwrap();
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            exclude: vec!["scripts/**".to_string()],
+            ..Default::default()
+        };
+        let mut names: Vec<String> = extra
+            .collect(&cfg)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["build.ts", "index.test.ts", "seed.ts"]);
+
+        // Ignore patterns from the section and the command line add up
+        assert!(extra.is_ignored(Path::new("src/generated/api.ts")));
+        assert!(extra.is_ignored(Path::new("src/legacy.ts")));
+        assert!(!extra.is_ignored(Path::new("src/index.ts")));
+    }
+
+    fn auto_entry_names(root: &Path) -> Vec<String> {
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entries: EntryMode::Auto,
+            ..Default::default()
+        };
+        let mut names: Vec<String> = collect_entries(&cfg)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                let root = root.canonicalize().unwrap();
+                p.strip_prefix(&root).unwrap_or(p).to_string_lossy().replace('\\', "/")
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_entry_mode_from_str() {
+        assert_eq!("files".parse::<EntryMode>().unwrap(), EntryMode::Files);
+        assert_eq!("auto".parse::<EntryMode>().unwrap(), EntryMode::Auto);
+        assert!("everything".parse::<EntryMode>().is_err());
+    }
+
+    #[test]
+    fn test_auto_entries_from_manifest_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "packages/lib/package.json",
+            r#"{
+                "main": "./dist/index.js",
+                "module": "./dist/esm/index.mjs",
+                "bin": {"lib-cli": "./bin/cli.js"},
+                "browser": {"./dist/node.js": "./dist/browser.js", "fs": false},
+                "exports": {
+                    ".": {"types": "./dist/index.d.ts", "import": "./dist/esm/index.mjs"},
+                    "./utils": ["./src/utils.ts"]
+                }
+            }"#,
+        );
+        create_test_file(root, "packages/lib/src/index.ts", "// index");
+        create_test_file(root, "packages/lib/src/browser.ts", "// browser");
+        create_test_file(root, "packages/lib/src/utils.ts", "// utils");
+        create_test_file(root, "packages/lib/src/internal.ts", "// internal helper");
+        create_test_file(root, "packages/lib/bin/cli.js", "// cli");
+
+        assert_eq!(
+            auto_entry_names(root),
+            vec![
+                "packages/lib/bin/cli.js",
+                "packages/lib/src/browser.ts",
+                "packages/lib/src/index.ts",
+                "packages/lib/src/utils.ts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_entries_bin_paths_without_dot() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "package.json",
+            r#"{"bin": {"cli": "bin/cli.js", "admin": "bin/admin.js"}}"#,
+        );
+        create_test_file(root, "bin/cli.js", "// cli");
+        create_test_file(root, "bin/admin.js", "// admin");
+        create_test_file(root, "src/other.ts", "// other");
+
+        assert_eq!(auto_entry_names(root), vec!["bin/admin.js", "bin/cli.js"]);
+    }
+
+    #[test]
+    fn test_auto_entries_exports_wildcard() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "package.json",
+            r#"{"exports": {"./icons/*": "./dist/icons/*.js"}}"#,
+        );
+        create_test_file(root, "src/icons/arrow.tsx", "// arrow");
+        create_test_file(root, "src/icons/close.tsx", "// close");
+        create_test_file(root, "src/other.ts", "// other");
+
+        assert_eq!(auto_entry_names(root), vec!["src/icons/arrow.tsx", "src/icons/close.tsx"]);
+    }
+
+    #[test]
+    fn test_auto_entries_nextjs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "package.json", r#"{"dependencies": {"next": "14.0.0"}}"#);
+        create_test_file(root, "pages/index.tsx", "// home");
+        create_test_file(root, "pages/api/hello.ts", "// api");
+        create_test_file(root, "src/app/layout.tsx", "// layout");
+        create_test_file(root, "src/app/dashboard/page.tsx", "// page");
+        create_test_file(root, "src/app/dashboard/Chart.tsx", "// not a route file");
+        create_test_file(root, "src/middleware.ts", "// middleware");
+        create_test_file(root, "src/lib/db.ts", "// helper");
+
+        assert_eq!(
+            auto_entry_names(root),
+            vec![
+                "pages/api/hello.ts",
+                "pages/index.tsx",
+                "src/app/dashboard/page.tsx",
+                "src/app/layout.tsx",
+                "src/middleware.ts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_entries_vite_html() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "package.json", r#"{"devDependencies": {"vite": "5"}}"#);
+        create_test_file(
+            root,
+            "index.html",
+            r#"<html><body>
+                <script src="/analytics.js"></script>
+                <script type="module" src="/src/main.tsx"></script>
+                <script type="module" src="https://cdn.example.com/x.js"></script>
+                <script type=module src=/src/worker.ts></script>
+            </body></html>"#,
+        );
+        create_test_file(root, "src/main.tsx", "// main");
+        create_test_file(root, "src/worker.ts", "// worker");
+        create_test_file(root, "src/App.tsx", "// app");
+        create_test_file(root, "analytics.js", "// classic script");
+
+        assert_eq!(auto_entry_names(root), vec!["src/main.tsx", "src/worker.ts"]);
+    }
+
+    #[test]
+    fn test_auto_entries_respect_exclude_and_entry_glob() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "package.json", r#"{"dependencies": {"next": "14.0.0"}}"#);
+        create_test_file(root, "pages/index.tsx", "// home");
+        create_test_file(root, "pages/index.test.tsx", "// test");
+        create_test_file(root, "pages/admin/users.tsx", "// admin");
+        create_test_file(root, "pages/api/hello.ts", "// api");
+
+        let cfg = CollectorConfig {
+            root: root.to_path_buf(),
+            entries: EntryMode::Auto,
+            entry_glob: vec!["!pages/api/**".to_string()],
+            exclude: vec!["pages/admin/**".to_string()],
+            ..Default::default()
+        };
+
+        let entries = collect_entries(&cfg).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("pages/index.tsx"));
+    }
+
+    #[test]
+    fn test_source_path_for() {
+        assert_eq!(source_path_for("dist/index.js"), Some("src/index".to_string()));
+        assert_eq!(
+            source_path_for("dist/esm/utils/index.mjs"),
+            Some("src/utils/index".to_string())
+        );
+        assert_eq!(source_path_for("index.js"), None);
+    }
 }
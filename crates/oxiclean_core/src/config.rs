@@ -1,12 +1,279 @@
 use anyhow::{Result, anyhow};
+use clap::Args;
 use ignore::WalkBuilder;
-use log::{debug, trace};
+use log::{debug, info, trace};
+use serde::de::DeserializeOwned;
 use std::{
     collections::HashMap,
     env, fs,
     path::{Path, PathBuf},
 };
 
+use crate::{
+    cache::{AnalysisCache, default_cache_dir},
+    collector::{CollectorConfig, EntryMode},
+    config_file::ConfigFile,
+    resolver::{PackageBoundaries, ResolveOptions},
+};
+
+/// Project settings shared by every check, flattened into each check's `Config`.
+///
+/// Values given on the command line take precedence over the config file.
+#[derive(Debug, Clone, Default, Args)]
+pub struct SharedConfig {
+    /// Root directory of the project (defaults to git root)
+    #[arg(long)]
+    pub root: Option<PathBuf>,
+
+    /// Config file to use (default: oxiclean.json / oxiclean.toml found from the root upward)
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// How entry files are selected: `files` (every source file, the default) or `auto`
+    /// (package.json entry fields and framework conventions)
+    #[arg(long)]
+    pub entries: Option<EntryMode>,
+
+    /// Glob pattern(s) selecting entry files, relative to root (repeatable; prefix with `!` to exclude)
+    #[arg(long)]
+    pub entry_glob: Vec<String>,
+
+    /// Glob pattern(s) selecting source files to analyze (repeatable; default: `**/src/**`)
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Glob pattern(s) for source files to skip (repeatable)
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// Also analyze test files (`*.test.*`, `*.spec.*`)
+    #[arg(long, num_args = 0, default_missing_value = "true", overrides_with = "no_include_tests")]
+    pub include_tests: Option<bool>,
+
+    /// Don't analyze test files, even if the config file includes them
+    #[arg(long, overrides_with = "include_tests")]
+    pub no_include_tests: bool,
+
+    /// Package.json `exports` condition(s) to resolve, in priority order (repeatable)
+    #[arg(long = "condition")]
+    pub conditions: Vec<String>,
+
+    /// Treat every package in node_modules as a single module whose imports aren't followed
+    #[arg(
+        long,
+        num_args = 0,
+        default_missing_value = "true",
+        overrides_with = "no_collapse_packages"
+    )]
+    pub collapse_packages: Option<bool>,
+
+    /// Follow imports into node_modules packages, even if the config file collapses them
+    #[arg(long, overrides_with = "collapse_packages")]
+    pub no_collapse_packages: bool,
+
+    /// Package to treat as a single module even without --collapse-packages (repeatable)
+    #[arg(long)]
+    pub collapse_package: Vec<String>,
+
+    /// Package to follow into despite --collapse-packages (repeatable)
+    #[arg(long)]
+    pub expand_package: Vec<String>,
+
+    /// Directory for the persistent parse/resolve cache
+    /// (default: node_modules/.cache/oxiclean, or .oxiclean-cache without node_modules)
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Don't read or write the persistent cache
+    #[arg(long, num_args = 0, default_missing_value = "true", overrides_with = "cache")]
+    pub no_cache: Option<bool>,
+
+    /// Use the persistent cache, even if the config file disables it
+    #[arg(long, overrides_with = "no_cache")]
+    pub cache: bool,
+
+    /// Re-run whenever a source file changes
+    #[arg(long)]
+    pub watch: bool,
+
+    #[clap(skip)]
+    pub resolve: ResolveOptions,
+
+    #[clap(skip)]
+    pub config_file: Option<ConfigFile>,
+
+    /// Set once `initialize()` has run
+    #[clap(skip)]
+    pub initialized: bool,
+}
+
+impl SharedConfig {
+    /// Resolve the root directory, merge in the config file and load path aliases.
+    ///
+    /// Calling this more than once is a no-op.
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.initialized {
+            return Ok(());
+        }
+
+        let config_file = match &self.config {
+            Some(path) => Some(ConfigFile::load(path)?),
+            None => {
+                let start = match &self.root {
+                    Some(r) => r.clone(),
+                    None => env::current_dir()?,
+                };
+                ConfigFile::discover(&start)?
+            }
+        };
+        if let Some(file) = &config_file {
+            info!("Using config file: {}", file.path.display());
+        }
+
+        // Resolve root directory
+        let root = if let Some(r) = self.root.take() {
+            debug!("Using provided root directory: {:?}", r);
+            r.canonicalize().unwrap_or(r)
+        } else if let Some(r) = config_file.as_ref().and_then(|f| f.root()) {
+            debug!("Using root directory from config file: {:?}", r);
+            r.canonicalize().unwrap_or(r)
+        } else {
+            debug!("No root provided, searching for git root");
+            find_git_root()?
+        };
+        info!("Using root directory: {}", root.display());
+
+        // A `--no-…` flag (or `--cache`) sets the value its counterpart leaves unset
+        if self.no_include_tests {
+            self.include_tests = Some(false);
+        }
+        if self.no_collapse_packages {
+            self.collapse_packages = Some(false);
+        }
+        if self.cache {
+            self.no_cache = Some(false);
+        }
+
+        // Read tsconfig paths; aliases from the config file take precedence
+        debug!("Reading tsconfig paths");
+        let mut aliases = read_tsconfig_paths(&root);
+
+        if let Some(file) = &config_file {
+            if self.entries.is_none() {
+                self.entries = file.entries;
+            }
+            if self.entry_glob.is_empty() {
+                self.entry_glob = file.entry_glob.clone();
+            }
+            if self.include.is_empty() {
+                self.include = file.include.clone();
+            }
+            if self.exclude.is_empty() {
+                self.exclude = file.ignores.clone();
+            }
+            if self.conditions.is_empty() {
+                self.conditions = file.conditions.clone();
+            }
+            self.include_tests = self.include_tests.or(file.include_tests);
+            if self.cache_dir.is_none() {
+                self.cache_dir = file.cache_dir();
+            }
+            self.no_cache = self.no_cache.or(file.cache.map(|cache| !cache));
+            self.collapse_packages = self.collapse_packages.or(file.collapse_packages);
+            if self.collapse_package.is_empty() {
+                self.collapse_package = file.collapsed_packages.clone();
+            }
+            if self.expand_package.is_empty() {
+                self.expand_package = file.expanded_packages.clone();
+            }
+            aliases.extend(file.resolved_aliases());
+        }
+
+        debug!("Found {} path aliases", aliases.len());
+        self.resolve.aliases = aliases;
+        if !self.conditions.is_empty() {
+            self.resolve.conditions = self.conditions.clone();
+        }
+        self.resolve.packages = PackageBoundaries {
+            collapse_all: self.collapse_packages.unwrap_or(false),
+            collapse: self.collapse_package.clone(),
+            expand: self.expand_package.clone(),
+        };
+        if self.resolve.packages.is_active() {
+            debug!("Package boundaries: {:?}", self.resolve.packages);
+        }
+
+        self.root = Some(root);
+        self.config_file = config_file;
+        self.initialized = true;
+        Ok(())
+    }
+
+    /// Get the root directory, returning None if not initialized
+    pub fn root(&self) -> Option<&PathBuf> {
+        self.root.as_ref()
+    }
+
+    /// Entry collection settings for the resolved root
+    pub fn collector_config(&self) -> Result<CollectorConfig> {
+        let root = self.root().ok_or_else(|| anyhow!("Config not initialized"))?;
+        Ok(CollectorConfig {
+            root: root.clone(),
+            entries: self.entries.unwrap_or_default(),
+            entry_glob: self.entry_glob.clone(),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            include_tests: self.include_tests.unwrap_or(false),
+            tsconfig_paths: self.resolve.aliases.clone(),
+        })
+    }
+
+    /// The parse/resolve cache to build the module graph with
+    pub fn analysis_cache(&self) -> Result<AnalysisCache> {
+        let root = self.root().ok_or_else(|| anyhow!("Config not initialized"))?;
+        if self.no_cache.unwrap_or(false) {
+            debug!("Persistent cache disabled");
+            return Ok(AnalysisCache::in_memory(root));
+        }
+        let cache_dir = self.cache_dir.clone().unwrap_or_else(|| default_cache_dir(root));
+        debug!("Using cache directory: {}", cache_dir.display());
+        Ok(AnalysisCache::load(root, &cache_dir, &self.resolve))
+    }
+
+    /// The config file section for a check, or its default if there is none
+    pub fn check_section<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T> {
+        match &self.config_file {
+            Some(file) => file.check_section(name),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+/// The settings of a check or query: the [`SharedConfig`] it builds the module
+/// graph from, plus options of its own from the command line and its section
+/// of the config file
+pub trait CommandConfig: Clone {
+    /// The project settings shared by every check
+    fn shared(&self) -> &SharedConfig;
+    /// The shared settings, for replacing them
+    fn shared_mut(&mut self) -> &mut SharedConfig;
+
+    /// A config with the given shared settings, leaving every option of the
+    /// check to its config file section or default
+    fn from_shared(shared: SharedConfig) -> Self
+    where
+        Self: Default,
+    {
+        let mut cfg = Self::default();
+        *cfg.shared_mut() = shared;
+        cfg
+    }
+
+    /// Resolves the root and merges in the config file, including the check's
+    /// own section
+    fn initialize(&mut self) -> Result<()>;
+}
+
 pub fn find_git_root() -> Result<PathBuf> {
     debug!("Searching for git root");
     let mut current_dir = env::current_dir()?;
@@ -104,9 +371,16 @@ pub fn read_tsconfig_paths(root: &Path) -> HashMap<String, Vec<String>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use clap::Parser;
     use std::fs;
     use tempfile::TempDir;
 
+    #[derive(Parser)]
+    struct CommandLine {
+        #[command(flatten)]
+        shared: SharedConfig,
+    }
+
     fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
         let file_path = dir.join(path);
         if let Some(parent) = file_path.parent() {
@@ -157,6 +431,129 @@ mod tests {
         env::set_current_dir(original_dir).unwrap();
     }
 
+    #[test]
+    fn test_shared_config_merges_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "oxiclean.json",
+            r#"{
+                "entries": "auto",
+                "include": ["app/**"],
+                "ignores": ["app/generated/**"],
+                "includeTests": true,
+                "conditions": ["browser"],
+                "aliases": {"~lib/*": ["./lib/*"]}
+            }"#,
+        );
+
+        let mut cfg = SharedConfig { root: Some(root.to_path_buf()), ..Default::default() };
+        cfg.initialize().unwrap();
+
+        assert!(cfg.config_file.is_some());
+        assert_eq!(cfg.entries, Some(EntryMode::Auto));
+        assert_eq!(cfg.include, vec!["app/**"]);
+        assert_eq!(cfg.exclude, vec!["app/generated/**"]);
+        assert_eq!(cfg.include_tests, Some(true));
+        assert_eq!(cfg.resolve.conditions, vec!["browser"]);
+        assert!(cfg.resolve.aliases["~lib"][0].ends_with("lib"));
+
+        let collector = cfg.collector_config().unwrap();
+        assert_eq!(collector.entries, EntryMode::Auto);
+        assert_eq!(collector.include, vec!["app/**"]);
+    }
+
+    #[test]
+    fn test_shared_config_cli_overrides_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "oxiclean.toml",
+            "entries = \"auto\"\ninclude = [\"app/**\"]\nconditions = [\"browser\"]\n",
+        );
+
+        let mut cfg = SharedConfig {
+            root: Some(root.to_path_buf()),
+            entries: Some(EntryMode::Files),
+            include: vec!["src/**".to_string()],
+            conditions: vec!["node".to_string()],
+            ..Default::default()
+        };
+        cfg.initialize().unwrap();
+
+        assert_eq!(cfg.entries, Some(EntryMode::Files));
+        assert_eq!(cfg.include, vec!["src/**"]);
+        assert_eq!(cfg.resolve.conditions, vec!["node"]);
+    }
+
+    #[test]
+    fn test_shared_config_cli_flags_turn_off_config_file_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "oxiclean.json",
+            r#"{"includeTests": true, "collapsePackages": true, "cache": false}"#,
+        );
+
+        let args = [
+            "test",
+            "--root",
+            root.to_str().unwrap(),
+            "--no-include-tests",
+            "--no-collapse-packages",
+            "--cache",
+        ];
+        let mut cfg = CommandLine::parse_from(args).shared;
+        cfg.initialize().unwrap();
+        assert_eq!(cfg.include_tests, Some(false));
+        assert_eq!(cfg.collapse_packages, Some(false));
+        assert_eq!(cfg.no_cache, Some(false));
+        assert!(!cfg.resolve.packages.collapse_all);
+
+        // The last of a flag and its counterpart wins
+        let args =
+            ["test", "--root", root.to_str().unwrap(), "--no-include-tests", "--include-tests"];
+        let mut cfg = CommandLine::parse_from(args).shared;
+        cfg.initialize().unwrap();
+        assert_eq!(cfg.include_tests, Some(true));
+        // Settings not given on the command line come from the file
+        assert_eq!(cfg.collapse_packages, Some(true));
+        assert_eq!(cfg.no_cache, Some(true));
+    }
+
+    #[test]
+    fn test_shared_config_root_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        fs::create_dir_all(dir.join("web")).unwrap();
+        let config_path = create_test_file(dir, "config/oxiclean.json", r#"{"root": "../web"}"#);
+
+        let mut cfg = SharedConfig { config: Some(config_path), ..Default::default() };
+        cfg.initialize().unwrap();
+
+        assert_eq!(cfg.root().unwrap(), &dir.join("web").canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_shared_config_without_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let mut cfg = SharedConfig { root: Some(root.to_path_buf()), ..Default::default() };
+        cfg.initialize().unwrap();
+
+        assert!(cfg.config_file.is_none());
+        assert_eq!(cfg.resolve.conditions, ResolveOptions::default().conditions);
+        let section: crate::ThresholdSection = cfg.check_section("import-bloat").unwrap();
+        assert!(section.threshold.is_none());
+    }
+
     #[test]
     fn test_read_tsconfig_paths_simple() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,400 @@
+use anyhow::{Context, Result, anyhow};
+use log::{debug, trace};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{collector::EntryMode, glob::GlobMatcher};
+
+/// Config file names, in lookup order within a directory
+pub const CONFIG_FILE_NAMES: &[&str] = &["oxiclean.json", "oxiclean.toml"];
+
+/// Names of the per-check sections a config file may have
+pub const CHECK_SECTIONS: &[&str] = &[
+    "import-bloat",
+    "import-depth",
+    "import-cycles",
+    "unused-files",
+    "unused-exports",
+    "import-boundaries",
+    "barrels",
+    "dependencies",
+    "duplicate-packages",
+    "graph-stats",
+    "why",
+    "path",
+];
+
+/// A checked-in `oxiclean.json` / `oxiclean.toml`.
+///
+/// Top-level keys hold settings shared by every check; any other top-level
+/// table is a per-check section keyed by the check's command name:
+///
+/// ```toml
+/// entries = "auto"
+/// ignores = ["src/generated/**"]
+/// conditions = ["browser", "import"]
+///
+/// [aliases]
+/// "@/*" = ["./src/*"]
+///
+/// [import-bloat]
+/// threshold = 150
+/// overrides = [{ files = ["src/admin/**"], threshold = 400 }]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConfigFile {
+    /// Path of the file this config was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Project root, relative to the config file
+    pub root: Option<PathBuf>,
+    pub entries: Option<EntryMode>,
+    pub entry_glob: Vec<String>,
+    pub include: Vec<String>,
+    /// Glob patterns for source files to skip
+    pub ignores: Vec<String>,
+    pub include_tests: Option<bool>,
+    /// Path aliases in tsconfig `paths` form, with targets relative to the config file
+    pub aliases: HashMap<String, Vec<String>>,
+    /// Package.json `exports` conditions, in priority order
+    pub conditions: Vec<String>,
+    /// Set to `false` to disable the persistent cache
+    pub cache: Option<bool>,
+    /// Treat every package in node_modules as a single opaque module
+    pub collapse_packages: Option<bool>,
+    /// Packages to treat as single modules even without `collapsePackages`
+    pub collapsed_packages: Vec<String>,
+    /// Packages to follow into despite `collapsePackages`
+    pub expanded_packages: Vec<String>,
+    /// Cache directory, relative to the config file
+    pub cache_dir: Option<PathBuf>,
+    /// Per-check sections, keyed by check name (e.g. `import-bloat`)
+    #[serde(flatten)]
+    pub checks: HashMap<String, serde_json::Value>,
+}
+
+impl ConfigFile {
+    /// Loads a config file, choosing the format from its extension
+    pub fn load(path: &Path) -> Result<Self> {
+        debug!("Loading config file: {}", path.display());
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+        let mut cfg: ConfigFile = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            _ => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+        };
+        cfg.path = path.to_path_buf();
+
+        // Misspelled settings and sections would otherwise be dropped silently
+        let mut unknown: Vec<&String> =
+            cfg.checks.keys().filter(|k| !CHECK_SECTIONS.contains(&k.as_str())).collect();
+        unknown.sort();
+        if let Some(key) = unknown.first() {
+            return Err(anyhow!(
+                "Unknown key '{}' in {}: expected a shared setting or one of the check sections {}",
+                key,
+                path.display(),
+                CHECK_SECTIONS.join(", ")
+            ));
+        }
+        Ok(cfg)
+    }
+
+    /// Searches `start` and each of its ancestors for a config file
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        debug!("Searching for config file from: {}", start.display());
+        for dir in start.ancestors() {
+            for name in CONFIG_FILE_NAMES {
+                let candidate = dir.join(name);
+                trace!("Checking for config file at: {}", candidate.display());
+                if candidate.is_file() {
+                    return Self::load(&candidate).map(Some);
+                }
+            }
+        }
+        debug!("No config file found");
+        Ok(None)
+    }
+
+    /// Directory containing the config file; relative paths are resolved against it
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// The configured root as an absolute path
+    pub fn root(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|r| self.dir().join(r))
+    }
+
+    /// The configured cache directory as an absolute path
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|d| self.dir().join(d))
+    }
+
+    /// Aliases in the same form as [`crate::read_tsconfig_paths`]
+    pub fn resolved_aliases(&self) -> HashMap<String, Vec<String>> {
+        self.aliases
+            .iter()
+            .map(|(alias, targets)| {
+                let targets = targets
+                    .iter()
+                    .map(|t| {
+                        self.dir().join(t.trim_end_matches("/*")).to_string_lossy().to_string()
+                    })
+                    .collect();
+                (alias.trim_end_matches("/*").to_string(), targets)
+            })
+            .collect()
+    }
+
+    /// Deserializes the section for a check, or its default if the section is absent.
+    ///
+    /// Keys `T` doesn't know are an error. Keys of a `#[serde(flatten)]` field
+    /// can't be told apart from unknown ones, so sections list their fields
+    /// directly.
+    pub fn check_section<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T> {
+        let Some(value) = self.checks.get(name) else {
+            return Ok(T::default());
+        };
+        let mut unknown = Vec::new();
+        let section =
+            serde_ignored::deserialize(value.clone(), |key| unknown.push(key.to_string()))
+                .with_context(|| {
+                    format!("Invalid [{}] section in {}", name, self.path.display())
+                })?;
+        if let Some(key) = unknown.first() {
+            return Err(anyhow!(
+                "Unknown key '{}' in the [{}] section of {}",
+                key,
+                name,
+                self.path.display()
+            ));
+        }
+        Ok(section)
+    }
+}
+
+/// A threshold that applies to entry files matching `files` instead of the check's default
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThresholdOverride {
+    pub files: Vec<String>,
+    pub threshold: usize,
+}
+
+/// The per-check section of a check with nothing but a threshold and its overrides
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThresholdSection {
+    pub threshold: Option<usize>,
+    pub overrides: Vec<ThresholdOverride>,
+}
+
+/// The per-check section shared by the checks looking for unused code
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EntrySection {
+    /// Glob patterns for extra entry files
+    pub entries: Vec<String>,
+    /// Glob patterns for files never to report
+    pub ignore: Vec<String>,
+}
+
+/// Compiled per-path threshold overrides; the last matching override wins
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdOverrides {
+    overrides: Vec<(GlobMatcher, usize)>,
+}
+
+impl ThresholdOverrides {
+    pub fn new(overrides: &[ThresholdOverride]) -> Result<Self> {
+        let overrides = overrides
+            .iter()
+            .map(|o| {
+                if o.files.is_empty() {
+                    return Err(anyhow!("Threshold override without `files` patterns"));
+                }
+                Ok((GlobMatcher::new(&o.files)?, o.threshold))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { overrides })
+    }
+
+    /// The threshold for a path relative to the project root, or `default` if no override matches
+    pub fn threshold_for(&self, rel_path: &Path, default: usize) -> usize {
+        self.overrides
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.is_match(rel_path))
+            .map(|(_, threshold)| *threshold)
+            .unwrap_or(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    #[test]
+    fn test_load_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(
+            temp_dir.path(),
+            "oxiclean.json",
+            r#"{
+                "entries": "auto",
+                "entryGlob": ["src/**/*.tsx"],
+                "ignores": ["src/generated/**"],
+                "includeTests": true,
+                "conditions": ["browser"],
+                "aliases": {"@/*": ["./src/*"]},
+                "import-bloat": {"threshold": 150}
+            }"#,
+        );
+
+        let cfg = ConfigFile::load(&path).unwrap();
+        assert_eq!(cfg.entries, Some(EntryMode::Auto));
+        assert_eq!(cfg.entry_glob, vec!["src/**/*.tsx"]);
+        assert_eq!(cfg.ignores, vec!["src/generated/**"]);
+        assert_eq!(cfg.include_tests, Some(true));
+        assert_eq!(cfg.conditions, vec!["browser"]);
+
+        let aliases = cfg.resolved_aliases();
+        assert!(aliases["@"][0].ends_with("src"));
+
+        let section: ThresholdSection = cfg.check_section("import-bloat").unwrap();
+        assert_eq!(section.threshold, Some(150));
+        let missing: ThresholdSection = cfg.check_section("import-depth").unwrap();
+        assert_eq!(missing.threshold, None);
+    }
+
+    #[test]
+    fn test_load_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(
+            temp_dir.path(),
+            "oxiclean.toml",
+            r#"
+root = "app"
+include = ["app/**"]
+
+[import-depth]
+threshold = 12
+overrides = [{ files = ["app/legacy/**"], threshold = 30 }]
+"#,
+        );
+
+        let cfg = ConfigFile::load(&path).unwrap();
+        assert_eq!(cfg.root(), Some(temp_dir.path().join("app")));
+        assert_eq!(cfg.include, vec!["app/**"]);
+
+        let section: ThresholdSection = cfg.check_section("import-depth").unwrap();
+        assert_eq!(section.threshold, Some(12));
+        assert_eq!(section.overrides.len(), 1);
+        assert_eq!(section.overrides[0].threshold, 30);
+    }
+
+    #[test]
+    fn test_invalid_section_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(
+            temp_dir.path(),
+            "oxiclean.json",
+            r#"{"import-bloat": {"threshold": "lots"}}"#,
+        );
+
+        let cfg = ConfigFile::load(&path).unwrap();
+        assert!(cfg.check_section::<ThresholdSection>("import-bloat").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let json = create_test_file(temp_dir.path(), "oxiclean.json", r#"{"ignore": ["x/**"]}"#);
+        let err = ConfigFile::load(&json).unwrap_err().to_string();
+        assert!(err.contains("Unknown key 'ignore'"), "{}", err);
+
+        let toml =
+            create_test_file(temp_dir.path(), "oxiclean.toml", "[import_bloat]\nthreshold = 1\n");
+        let err = ConfigFile::load(&toml).unwrap_err().to_string();
+        assert!(err.contains("Unknown key 'import_bloat'"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_section_keys_are_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml = create_test_file(
+            temp_dir.path(),
+            "oxiclean.toml",
+            "[import-bloat]\ntreshold = 50\n\n[unused-files]\nentries = [\"scripts/**\"]\n",
+        );
+        let cfg = ConfigFile::load(&toml).unwrap();
+        let err = cfg.check_section::<ThresholdSection>("import-bloat").unwrap_err().to_string();
+        assert!(err.contains("Unknown key 'treshold' in the [import-bloat] section"), "{}", err);
+        let section: EntrySection = cfg.check_section("unused-files").unwrap();
+        assert_eq!(section.entries, vec!["scripts/**"]);
+
+        let json = create_test_file(
+            temp_dir.path(),
+            "oxiclean.json",
+            r#"{"import-depth": {"overrides": [{"files": ["a/**"], "threshold": 1, "tresh": 2}]}}"#,
+        );
+        let cfg = ConfigFile::load(&json).unwrap();
+        let err = cfg.check_section::<ThresholdSection>("import-depth").unwrap_err().to_string();
+        assert!(err.contains("Unknown key 'overrides.0.tresh'"), "{}", err);
+    }
+
+    #[test]
+    fn test_discover_walks_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "oxiclean.toml", "include = [\"src/**\"]");
+        let nested = root.join("packages").join("ui");
+        fs::create_dir_all(&nested).unwrap();
+
+        let cfg = ConfigFile::discover(&nested).unwrap().unwrap();
+        assert_eq!(cfg.path, root.join("oxiclean.toml"));
+        assert_eq!(cfg.include, vec!["src/**"]);
+    }
+
+    #[test]
+    fn test_discover_prefers_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "oxiclean.toml", "include = [\"toml/**\"]");
+        create_test_file(root, "oxiclean.json", r#"{"include": ["json/**"]}"#);
+
+        let cfg = ConfigFile::discover(root).unwrap().unwrap();
+        assert_eq!(cfg.include, vec!["json/**"]);
+    }
+
+    #[test]
+    fn test_threshold_overrides() {
+        let overrides = vec![
+            ThresholdOverride { files: vec!["src/admin/**".to_string()], threshold: 400 },
+            ThresholdOverride { files: vec!["src/admin/reports/**".to_string()], threshold: 800 },
+        ];
+        let thresholds = ThresholdOverrides::new(&overrides).unwrap();
+
+        assert_eq!(thresholds.threshold_for(Path::new("src/index.ts"), 200), 200);
+        assert_eq!(thresholds.threshold_for(Path::new("src/admin/users.ts"), 200), 400);
+        assert_eq!(thresholds.threshold_for(Path::new("src/admin/reports/q1.ts"), 200), 800);
+    }
+}
@@ -40,6 +40,32 @@ pub const INDEX_FILES: &[&str] = &[
     "index.cjs",
 ];
 
+/// Package.json `exports` conditions matched when none are configured
+pub const DEFAULT_CONDITIONS: &[&str] = &["import", "require"];
+
+/// Default include patterns for source files when none are configured, matched
+/// against the absolute path like the original `/src/` heuristic
+pub const DEFAULT_INCLUDE: &[&str] = &["**/src/**"];
+
+/// Patterns identifying test files, excluded unless tests are explicitly included
+pub const DEFAULT_TEST_PATTERNS: &[&str] = &["**/*.test.*", "**/*.spec.*"];
+
+/// Gitignore-style file listing paths oxiclean should never analyze
+pub const IGNORE_FILE_NAME: &str = ".oxicleanignore";
+
+/// Cache directory used when the project has no `node_modules` directory
+pub const CACHE_DIR_NAME: &str = ".oxiclean-cache";
+
+/// Lockfiles whose changes invalidate cached package resolutions
+pub const LOCKFILE_NAMES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lock",
+    "bun.lockb",
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
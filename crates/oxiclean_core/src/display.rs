@@ -0,0 +1,167 @@
+use log::{debug, trace};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Relativize a path to the current working directory for clickable links
+pub fn relativize_to_cwd(root: &Path, relative_to_root: &str) -> String {
+    let cwd = match env::current_dir() {
+        Ok(cwd) => cwd,
+        Err(_) => {
+            debug!("Failed to get current directory");
+            return relative_to_root.to_string();
+        }
+    };
+    trace!("Relativizing '{}' from root {:?} to cwd {:?}", relative_to_root, root, cwd);
+
+    // Reconstruct the absolute path
+    let abs_path = root.join(relative_to_root);
+
+    // Make it relative to cwd
+    match make_relative(&abs_path, &cwd) {
+        Some(rel_path) => {
+            let result = rel_path.to_string_lossy().to_string();
+            trace!("Relativized '{}' to '{}'", relative_to_root, result);
+            result
+        }
+        None => {
+            trace!("Could not relativize '{}', using original", relative_to_root);
+            relative_to_root.to_string()
+        }
+    }
+}
+
+/// The canonical path of a file named on the command line, relative to the
+/// current working directory or else to the root, if it exists
+pub fn resolve_file_arg(root: &Path, arg: &str) -> Option<PathBuf> {
+    let from_cwd = env::current_dir().ok().map(|cwd| cwd.join(arg));
+    from_cwd
+        .into_iter()
+        .chain([root.join(arg)])
+        .find(|path| path.is_file())
+        .and_then(|path| path.canonicalize().ok())
+}
+
+/// Create a relative path from `base` to `target`
+fn make_relative(target: &Path, base: &Path) -> Option<PathBuf> {
+    use std::path::Component;
+
+    let mut target_components = target.components();
+    let mut base_components = base.components();
+
+    let mut common_prefix_len = 0;
+    let mut target_parts = Vec::new();
+    let mut base_parts = Vec::new();
+
+    // Find common prefix
+    loop {
+        match (target_components.next(), base_components.next()) {
+            (Some(t), Some(b)) if t == b => {
+                common_prefix_len += 1;
+            }
+            (Some(t), Some(b)) => {
+                target_parts.push(t);
+                base_parts.push(b);
+                break;
+            }
+            (Some(t), None) => {
+                target_parts.push(t);
+                break;
+            }
+            (None, Some(_)) => {
+                // target is a prefix of base, need to go up
+                return Some(PathBuf::from("."));
+            }
+            (None, None) => {
+                // They are the same
+                return Some(PathBuf::from("."));
+            }
+        }
+    }
+
+    // Collect remaining components
+    target_parts.extend(target_components);
+    base_parts.extend(base_components);
+
+    // If there's no common prefix, we can't make a relative path
+    if common_prefix_len == 0 {
+        // Check if they at least share a root
+        let target_root = target.components().next();
+        let base_root = base.components().next();
+
+        if target_root != base_root {
+            return None;
+        }
+    }
+
+    // Build the relative path: "../" for each remaining base component,
+    // then append all remaining target components
+    let mut result = PathBuf::new();
+    for _ in &base_parts {
+        result.push("..");
+    }
+    for component in target_parts {
+        match component {
+            Component::Normal(p) => result.push(p),
+            Component::CurDir => {}
+            Component::ParentDir => result.push(".."),
+            Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+
+    if result.as_os_str().is_empty() { Some(PathBuf::from(".")) } else { Some(result) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_relative_same_dir() {
+        let target = Path::new("/project/src/file.ts");
+        let base = Path::new("/project/src");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from("file.ts")));
+    }
+
+    #[test]
+    fn test_make_relative_child_dir() {
+        let target = Path::new("/project/src/components/Button.tsx");
+        let base = Path::new("/project/src");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from("components/Button.tsx")));
+    }
+
+    #[test]
+    fn test_make_relative_parent_dir() {
+        let target = Path::new("/project/src/file.ts");
+        let base = Path::new("/project/src/components");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from("../file.ts")));
+    }
+
+    #[test]
+    fn test_make_relative_sibling_dir() {
+        let target = Path::new("/project/apps/web/index.ts");
+        let base = Path::new("/project/apps/api");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from("../web/index.ts")));
+    }
+
+    #[test]
+    fn test_make_relative_same_path() {
+        let target = Path::new("/project/src");
+        let base = Path::new("/project/src");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from(".")));
+    }
+
+    #[test]
+    fn test_make_relative_multiple_levels_up() {
+        let target = Path::new("/project/file.ts");
+        let base = Path::new("/project/apps/web/src");
+        let result = make_relative(target, base);
+        assert_eq!(result, Some(PathBuf::from("../../../file.ts")));
+    }
+}
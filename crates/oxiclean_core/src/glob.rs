@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use log::trace;
+use std::path::Path;
+
+/// A set of glob patterns matched against paths relative to the project root.
+///
+/// Patterns use gitignore-like glob semantics (`*` stays within a path segment,
+/// `**` crosses directories, `{a,b}` expands alternatives). Patterns prefixed
+/// with `!` are negated: a path matching any of them is excluded even if it
+/// matches a positive pattern.
+#[derive(Debug, Clone)]
+pub struct GlobMatcher {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl GlobMatcher {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let pattern = pattern.as_ref().trim();
+            if pattern.is_empty() {
+                continue;
+            }
+            match pattern.strip_prefix('!') {
+                Some(negated) => {
+                    trace!("Adding negated glob pattern: '{}'", negated);
+                    exclude.add(build_glob(negated)?);
+                }
+                None => {
+                    trace!("Adding glob pattern: '{}'", pattern);
+                    include.add(build_glob(pattern)?);
+                }
+            }
+        }
+
+        Ok(Self {
+            include: include.build().context("Failed to build glob set")?,
+            exclude: exclude.build().context("Failed to build negated glob set")?,
+        })
+    }
+
+    /// Returns true if at least one positive (non-negated) pattern was given
+    pub fn has_includes(&self) -> bool {
+        !self.include.is_empty()
+    }
+
+    /// Returns true if the path matches any positive pattern
+    pub fn is_included(&self, rel_path: &Path) -> bool {
+        self.include.is_match(rel_path)
+    }
+
+    /// Returns true if the path matches any negated pattern
+    pub fn is_excluded(&self, rel_path: &Path) -> bool {
+        self.exclude.is_match(rel_path)
+    }
+
+    /// Returns true if the path matches a positive pattern (or none were given)
+    /// and matches no negated pattern
+    pub fn is_match(&self, rel_path: &Path) -> bool {
+        (!self.has_includes() || self.is_included(rel_path)) && !self.is_excluded(rel_path)
+    }
+}
+
+fn build_glob(pattern: &str) -> Result<Glob> {
+    // Patterns are always relative to the root, so "./src/**" and "src/**" are equivalent
+    let pattern = pattern.trim_start_matches("./");
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("Invalid glob pattern '{}'", pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_star_matches_nested_and_top_level() {
+        let matcher = GlobMatcher::new(&["src/**/*.tsx"]).unwrap();
+        assert!(matcher.is_match(Path::new("src/App.tsx")));
+        assert!(matcher.is_match(Path::new("src/pages/home/Dashboard.tsx")));
+        assert!(!matcher.is_match(Path::new("src/utils.ts")));
+        assert!(!matcher.is_match(Path::new("lib/src/App.tsx")));
+    }
+
+    #[test]
+    fn test_single_star_stays_within_segment() {
+        let matcher = GlobMatcher::new(&["src/*.ts"]).unwrap();
+        assert!(matcher.is_match(Path::new("src/index.ts")));
+        assert!(!matcher.is_match(Path::new("src/nested/index.ts")));
+    }
+
+    #[test]
+    fn test_negated_patterns() {
+        let matcher = GlobMatcher::new(&["src/**/*.tsx", "!**/*.stories.tsx"]).unwrap();
+        assert!(matcher.is_match(Path::new("src/Button.tsx")));
+        assert!(!matcher.is_match(Path::new("src/Button.stories.tsx")));
+    }
+
+    #[test]
+    fn test_only_negated_patterns() {
+        let matcher = GlobMatcher::new(&["!**/legacy/**"]).unwrap();
+        assert!(!matcher.has_includes());
+        assert!(matcher.is_match(Path::new("src/index.ts")));
+        assert!(!matcher.is_match(Path::new("src/legacy/index.ts")));
+    }
+
+    #[test]
+    fn test_brace_expansion() {
+        let matcher = GlobMatcher::new(&["{app,packages/*/lib}/**/*.{ts,tsx}"]).unwrap();
+        assert!(matcher.is_match(Path::new("app/main.ts")));
+        assert!(matcher.is_match(Path::new("packages/ui/lib/Button.tsx")));
+        assert!(!matcher.is_match(Path::new("packages/ui/src/Button.tsx")));
+        assert!(!matcher.is_match(Path::new("app/main.js")));
+    }
+
+    #[test]
+    fn test_leading_dot_slash_is_ignored() {
+        let matcher = GlobMatcher::new(&["./src/**"]).unwrap();
+        assert!(matcher.is_match(Path::new("src/index.ts")));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        assert!(GlobMatcher::new(&["src/[.ts"]).is_err());
+    }
+}
@@ -0,0 +1,814 @@
+use anyhow::{Result, anyhow};
+use log::{debug, info, trace, warn};
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    cache::AnalysisCache,
+    collector::collect_entries,
+    config::SharedConfig,
+    display::resolve_file_arg,
+    resolver::ResolveOptions,
+    types::{Export, ImportedNames, SpecKind, Specifier},
+};
+
+/// Interned identifier of a module in a [`ModuleGraph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(u32);
+
+impl ModuleId {
+    pub(crate) fn from_index(index: usize) -> Self {
+        Self(index as u32)
+    }
+
+    /// Position of the module in the graph, suitable for indexing per-module tables
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for ModuleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A resolved import from one module to another
+#[derive(Debug, Clone)]
+pub struct Edge {
+    pub to: ModuleId,
+    /// The import specifier as written in the source
+    pub request: String,
+    pub kind: SpecKind,
+    /// 1-based line of the import in the importing file
+    pub line: u32,
+    /// The exports of the target module this import uses
+    pub names: ImportedNames,
+}
+
+/// The parse and resolve results of one module in a graph build layer
+struct Scanned {
+    id: ModuleId,
+    targets: Vec<(Specifier, PathBuf)>,
+    unresolved: Vec<Specifier>,
+    exports: Vec<Export>,
+    star_exports: Vec<String>,
+}
+
+/// The resolved import graph of a project, built once and shared by every check.
+///
+/// Modules are interned as [`ModuleId`]s in discovery order, so IDs are stable
+/// for a given set of entries. Each module keeps its outgoing edges in source
+/// order, and the reverse adjacency (importers) is available as well.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    root: PathBuf,
+    paths: Vec<PathBuf>,
+    ids: HashMap<PathBuf, ModuleId>,
+    imports: Vec<Vec<Edge>>,
+    /// Imports of each module that didn't resolve, which have no edge
+    unresolved: Vec<Vec<Specifier>>,
+    importers: Vec<Vec<ModuleId>>,
+    exports: Vec<Vec<Export>>,
+    star_exports: Vec<Vec<ModuleId>>,
+    /// Whether each module is a collapsed package rather than a file
+    collapsed: Vec<bool>,
+    entries: Vec<ModuleId>,
+}
+
+impl ModuleGraph {
+    /// Collects the entry files for an initialized config and builds their graph,
+    /// using and then saving the persistent cache
+    pub fn from_config(cfg: &SharedConfig) -> Result<Self> {
+        let cache = cfg.analysis_cache()?;
+        let graph = Self::from_config_with_cache(cfg, &cache)?;
+        if let Err(e) = cache.save() {
+            warn!("Failed to save cache: {:#}", e);
+        }
+        Ok(graph)
+    }
+
+    /// Collects the entry files for an initialized config and builds their graph
+    /// with the given cache
+    pub fn from_config_with_cache(cfg: &SharedConfig, cache: &AnalysisCache) -> Result<Self> {
+        let collector_cfg = cfg.collector_config()?;
+        let root = &collector_cfg.root;
+
+        debug!("Collecting entry files with glob: {:?}", collector_cfg.entry_glob);
+        let entries = collect_entries(&collector_cfg)?;
+        if entries.is_empty() {
+            warn!("No entry files found under {}", root.display());
+            return Err(anyhow!("No entry files found under {}", root.display()));
+        }
+        info!("Found {} entry files", entries.len());
+
+        let graph = Self::build_with_cache(root, &cfg.resolve, &entries, cache)?;
+        info!("Reused cached imports for {} of {} modules", cache.reused(), graph.len());
+        Ok(graph)
+    }
+
+    /// Builds the graph of every module reachable from `entries`.
+    ///
+    /// Modules are parsed and resolved in parallel, one breadth-first layer at a
+    /// time. Files that fail to parse are logged and left out of the graph, and
+    /// imports that fail to resolve are kept without an edge. Imports of a package collapsed by
+    /// `resolve_opts.packages` resolve to a single module for the package
+    /// directory, which is not parsed.
+    pub fn build(root: &Path, resolve_opts: &ResolveOptions, entries: &[PathBuf]) -> Result<Self> {
+        Self::build_with_cache(root, resolve_opts, entries, &AnalysisCache::in_memory(root))
+    }
+
+    /// Like [`ModuleGraph::build`], reusing parse and resolve results from `cache`
+    pub fn build_with_cache(
+        root: &Path,
+        resolve_opts: &ResolveOptions,
+        entries: &[PathBuf],
+        cache: &AnalysisCache,
+    ) -> Result<Self> {
+        info!("Building module graph from {} entries", entries.len());
+
+        let mut graph = Self { root: root.to_path_buf(), ..Default::default() };
+        let mut frontier = Vec::new();
+        for entry in entries {
+            let (id, is_new) = graph.intern(entry);
+            if is_new {
+                frontier.push(id);
+                graph.entries.push(id);
+            }
+        }
+
+        while !frontier.is_empty() {
+            trace!("Scanning {} modules", frontier.len());
+            let scanned: Vec<Scanned> = frontier
+                .par_iter()
+                .map(|&id| {
+                    let file = graph.path(id);
+                    let parsed = match cache.parse(file) {
+                        Ok(parsed) => parsed,
+                        Err(e) => {
+                            warn!("Error parsing imports for {}: {}", file.display(), e);
+                            return Scanned {
+                                id,
+                                targets: vec![],
+                                unresolved: vec![],
+                                exports: vec![],
+                                star_exports: vec![],
+                            };
+                        }
+                    };
+
+                    let mut targets = Vec::new();
+                    let mut unresolved = Vec::new();
+                    for spec in parsed.imports {
+                        match cache.resolve(resolve_opts, file, &spec.request) {
+                            Ok(Some(target)) => targets.push((spec, target)),
+                            Ok(None) => {
+                                trace!("Could not resolve import: '{}'", spec.request);
+                                unresolved.push(spec);
+                            }
+                            Err(e) => {
+                                warn!("Error resolving '{}': {}", spec.request, e);
+                                unresolved.push(spec);
+                            }
+                        }
+                    }
+                    Scanned {
+                        id,
+                        targets,
+                        unresolved,
+                        exports: parsed.exports,
+                        star_exports: parsed.star_exports,
+                    }
+                })
+                .collect();
+
+            // Intern sequentially so IDs don't depend on thread scheduling
+            let mut next = Vec::new();
+            for Scanned { id: from, targets, unresolved, exports, star_exports } in scanned {
+                for (spec, target) in targets {
+                    let collapsed = package_root(&target)
+                        .filter(|(_, name)| resolve_opts.packages.is_collapsed(name))
+                        .map(|(dir, _)| dir);
+                    let (to, is_new) = graph.intern(collapsed.as_deref().unwrap_or(&target));
+                    if is_new {
+                        match collapsed {
+                            Some(_) => graph.collapsed[to.index()] = true,
+                            None => next.push(to),
+                        }
+                    }
+                    graph.imports[from.index()].push(Edge {
+                        to,
+                        request: spec.request,
+                        kind: spec.kind,
+                        line: spec.line,
+                        names: spec.names,
+                    });
+                }
+                graph.unresolved[from.index()] = unresolved;
+                graph.star_exports[from.index()] = star_exports
+                    .iter()
+                    .filter_map(|request| graph.resolved(from, request))
+                    .collect();
+                graph.exports[from.index()] = exports;
+            }
+            frontier = next;
+        }
+
+        graph.link_importers();
+        debug!(
+            "Built module graph with {} modules ({} collapsed packages) and {} edges",
+            graph.len(),
+            graph.collapsed.iter().filter(|&&c| c).count(),
+            graph.imports.iter().map(Vec::len).sum::<usize>()
+        );
+        Ok(graph)
+    }
+
+    /// The same graph without re-export edges, for the checks that measure only
+    /// what modules import themselves. Modules stay, with the same IDs, even
+    /// when only re-exports reached them.
+    pub fn without_re_exports(&self) -> Self {
+        let mut graph = self.clone();
+        for edges in &mut graph.imports {
+            edges.retain(|edge| edge.kind != SpecKind::ReExport);
+        }
+        graph.importers = vec![Vec::new(); graph.len()];
+        graph.link_importers();
+        graph
+    }
+
+    /// Fills in the importers of every module from the outgoing edges
+    fn link_importers(&mut self) {
+        for from in self.modules() {
+            let mut seen = HashSet::new();
+            for edge in &self.imports[from.index()] {
+                if seen.insert(edge.to) {
+                    self.importers[edge.to.index()].push(from);
+                }
+            }
+        }
+    }
+
+    fn intern(&mut self, path: &Path) -> (ModuleId, bool) {
+        if let Some(&id) = self.ids.get(path) {
+            return (id, false);
+        }
+        let id = ModuleId(self.paths.len() as u32);
+        self.paths.push(path.to_path_buf());
+        self.ids.insert(path.to_path_buf(), id);
+        self.imports.push(Vec::new());
+        self.unresolved.push(Vec::new());
+        self.importers.push(Vec::new());
+        self.exports.push(Vec::new());
+        self.star_exports.push(Vec::new());
+        self.collapsed.push(false);
+        (id, true)
+    }
+
+    /// The project root the graph was built for
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Number of modules in the graph
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// All module IDs, in discovery order
+    pub fn modules(&self) -> impl Iterator<Item = ModuleId> + use<> {
+        (0..self.paths.len() as u32).map(ModuleId)
+    }
+
+    /// The entry modules the graph was built from
+    pub fn entries(&self) -> &[ModuleId] {
+        &self.entries
+    }
+
+    /// Looks up the module for a file path
+    pub fn id_of(&self, path: &Path) -> Option<ModuleId> {
+        self.ids.get(path).copied()
+    }
+
+    /// Absolute path of a module
+    pub fn path(&self, id: ModuleId) -> &Path {
+        &self.paths[id.index()]
+    }
+
+    /// Path of a module relative to the root, for display
+    pub fn rel_path(&self, id: ModuleId) -> String {
+        let path = self.path(id);
+        path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().to_string()
+    }
+
+    /// Outgoing edges of a module, in source order, including type-only imports
+    pub fn imports(&self, id: ModuleId) -> &[Edge] {
+        &self.imports[id.index()]
+    }
+
+    /// Outgoing edges of a module that remain at runtime, in source order
+    pub fn runtime_imports(&self, id: ModuleId) -> impl Iterator<Item = &Edge> {
+        self.imports(id).iter().filter(|edge| edge.kind.is_runtime())
+    }
+
+    /// Imports of a module that failed to resolve, in source order. They have
+    /// no edge, but still name the packages a module needs.
+    pub fn unresolved_imports(&self, id: ModuleId) -> &[Specifier] {
+        &self.unresolved[id.index()]
+    }
+
+    /// Modules that import `id`, including through type-only imports, each listed once
+    pub fn importers(&self, id: ModuleId) -> &[ModuleId] {
+        &self.importers[id.index()]
+    }
+
+    /// The module an import request of `id` resolved to
+    pub fn resolved(&self, id: ModuleId, request: &str) -> Option<ModuleId> {
+        self.imports(id).iter().find(|edge| edge.request == request).map(|edge| edge.to)
+    }
+
+    /// Names a module exports itself, including re-exports, in source order
+    pub fn exports(&self, id: ModuleId) -> &[Export] {
+        &self.exports[id.index()]
+    }
+
+    /// Modules whose exports `id` re-exports with `export * from`
+    pub fn star_exports(&self, id: ModuleId) -> &[ModuleId] {
+        &self.star_exports[id.index()]
+    }
+
+    /// Every module inside the package (or package subpath) `name`, such as
+    /// `lodash` or `@mui/material/styles`, in whichever installed copy
+    pub fn package_modules(&self, name: &str) -> Vec<ModuleId> {
+        self.modules().filter(|&id| in_package(self.path(id), name)).collect()
+    }
+
+    /// The modules a file or package named on the command line stands for, and
+    /// their paths relative to the root. `arg` is a file when it names one,
+    /// relative to the current directory or the root, and a package otherwise,
+    /// matched as by [`ModuleGraph::package_modules`]. A file nothing imports
+    /// is not in the graph, so it has a path but no module.
+    pub fn resolve_target(&self, arg: &str) -> Result<(Vec<ModuleId>, Vec<String>)> {
+        if let Some(path) = resolve_file_arg(&self.root, arg) {
+            return Ok(match self.id_of(&path) {
+                Some(id) => (vec![id], vec![self.rel_path(id)]),
+                None => {
+                    debug!("{:?} is not in the module graph", path);
+                    let rel = path.strip_prefix(&self.root).unwrap_or(&path);
+                    (Vec::new(), vec![rel.to_string_lossy().to_string()])
+                }
+            });
+        }
+        let ids = self.package_modules(arg);
+        if ids.is_empty() {
+            return Err(anyhow!("No file or package named '{}' in the module graph", arg));
+        }
+        let files = ids.iter().map(|&id| self.rel_path(id)).collect();
+        Ok((ids, files))
+    }
+
+    /// The package a module belongs to, from its path after the last
+    /// `node_modules` (such as `lodash` or `@mui/material`), or None for a
+    /// first-party module
+    pub fn package_name(&self, id: ModuleId) -> Option<String> {
+        self.package_root(id).map(|(_, name)| name)
+    }
+
+    /// The installed package directory a module belongs to, and the
+    /// package's name. A collapsed module is its package directory; any
+    /// other module is a file inside one.
+    pub fn package_root(&self, id: ModuleId) -> Option<(PathBuf, String)> {
+        let path = self.path(id);
+        package_root(path).filter(|(dir, _)| self.is_collapsed(id) || dir != path)
+    }
+
+    /// Whether a module is installed in `node_modules` rather than part of the
+    /// project
+    pub fn is_installed(&self, id: ModuleId) -> bool {
+        after_node_modules(self.path(id)).is_some()
+    }
+
+    /// Whether a module is a collapsed package, whose path is the package
+    /// directory and whose imports were not followed
+    pub fn is_collapsed(&self, id: ModuleId) -> bool {
+        self.collapsed[id.index()]
+    }
+
+    /// The entries whose reachable modules may differ from those of the same
+    /// entry in `previous`, an earlier build of the graph: every entry that
+    /// reaches a module that is new, is one of the `edited` files, or whose
+    /// imports resolve differently than before. Entries not in `previous` are
+    /// always included.
+    pub fn changed_entries(&self, previous: &ModuleGraph, edited: &[PathBuf]) -> Vec<ModuleId> {
+        let edited: HashSet<&Path> = edited.iter().map(PathBuf::as_path).collect();
+        let same_imports = |id: ModuleId, old: ModuleId| {
+            let (new, old) = (self.imports(id), previous.imports(old));
+            new.len() == old.len()
+                && new.iter().zip(old).all(|(a, b)| {
+                    a.request == b.request
+                        && a.kind == b.kind
+                        && self.path(a.to) == previous.path(b.to)
+                })
+        };
+        let mut stack: Vec<ModuleId> = self
+            .modules()
+            .filter(|&id| match previous.id_of(self.path(id)) {
+                Some(old) => {
+                    edited.contains(self.path(id))
+                        || self.is_collapsed(id) != previous.is_collapsed(old)
+                        || !same_imports(id, old)
+                }
+                None => true,
+            })
+            .collect();
+        trace!("{} modules changed since the previous build", stack.len());
+
+        // Everything that imports a changed module, directly or not
+        let mut affected = vec![false; self.len()];
+        for &id in &stack {
+            affected[id.index()] = true;
+        }
+        while let Some(id) = stack.pop() {
+            for &importer in self.importers(id) {
+                if !affected[importer.index()] {
+                    affected[importer.index()] = true;
+                    stack.push(importer);
+                }
+            }
+        }
+        self.entries.iter().copied().filter(|e| affected[e.index()]).collect()
+    }
+
+    /// A graph of `n` placeholder modules with the given edges, for tests
+    #[cfg(test)]
+    pub(crate) fn from_edges(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut graph = Self::default();
+        for i in 0..n {
+            graph.intern(Path::new(&format!("/m{}.js", i)));
+        }
+        for &(from, to) in edges {
+            graph.imports[from].push(Edge {
+                to: ModuleId::from_index(to),
+                request: format!("./m{}", to),
+                kind: SpecKind::Static,
+                line: 1,
+                names: ImportedNames::All,
+            });
+            if !graph.importers[to].contains(&ModuleId::from_index(from)) {
+                graph.importers[to].push(ModuleId::from_index(from));
+            }
+        }
+        graph.entries = graph.modules().collect();
+        graph
+    }
+
+    /// Every module reachable from `start` through runtime imports, including
+    /// `start` itself
+    pub fn reachable(&self, start: ModuleId) -> HashSet<ModuleId> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(cur) = stack.pop() {
+            if !visited.insert(cur) {
+                continue;
+            }
+            for edge in self.runtime_imports(cur) {
+                if !visited.contains(&edge.to) {
+                    stack.push(edge.to);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Whether each module is reachable from one of `starts` through runtime
+    /// imports, indexed by module
+    pub fn reached_from(&self, starts: &[ModuleId]) -> Vec<bool> {
+        let mut reached = vec![false; self.len()];
+        let mut stack = starts.to_vec();
+        while let Some(id) = stack.pop() {
+            if !reached[id.index()] {
+                reached[id.index()] = true;
+                stack.extend(self.runtime_imports(id).map(|edge| edge.to));
+            }
+        }
+        reached
+    }
+}
+
+/// Whether a file belongs to the package (or package subpath) `name`: its path
+/// after the last `node_modules` starts with the segments of `name`
+fn in_package(path: &Path, name: &str) -> bool {
+    let Some(rest) = after_node_modules(path) else {
+        return false;
+    };
+    let segments: Vec<&str> = name.trim_end_matches('/').split('/').collect();
+    segments.len() < rest.len() && segments.iter().zip(&rest).all(|(s, c)| c.as_os_str() == *s)
+}
+
+/// The package directory of a path in node_modules, after its last
+/// `node_modules`, and the package's name
+fn package_root(path: &Path) -> Option<(PathBuf, String)> {
+    let components: Vec<Component> = path.components().collect();
+    let pos = components.iter().rposition(|c| *c == Component::Normal("node_modules".as_ref()))?;
+    let rest = &components[pos + 1..];
+    let scoped = rest.first()?.as_os_str().to_string_lossy().starts_with('@');
+    let len = if scoped { 2 } else { 1 };
+    if rest.len() < len {
+        return None;
+    }
+    let name: Vec<_> = rest[..len].iter().map(|c| c.as_os_str().to_string_lossy()).collect();
+    Some((components[..pos + 1 + len].iter().collect(), name.join("/")))
+}
+
+/// The components of a path after its last `node_modules`, if it has one
+fn after_node_modules(path: &Path) -> Option<Vec<Component<'_>>> {
+    let components: Vec<Component> = path.components().collect();
+    let pos = components.iter().rposition(|c| *c == Component::Normal("node_modules".as_ref()))?;
+    Some(components[pos + 1..].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::PackageBoundaries;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    #[test]
+    fn test_build_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(
+            root,
+            "src/index.js",
+            "import './a'; import './missing'; const b = import('./b');",
+        );
+        create_test_file(root, "src/a.js", "import './c';");
+        create_test_file(root, "src/b.js", "import './c';");
+        create_test_file(root, "src/c.js", "// c");
+
+        let graph = ModuleGraph::build(root, &ResolveOptions::default(), &[entry]).unwrap();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.entries().len(), 1);
+
+        let index = graph.entries()[0];
+        let edges = graph.imports(index);
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].request, "./a");
+        assert_eq!(edges[0].kind, SpecKind::Static);
+        assert_eq!(edges[1].request, "./b");
+        assert_eq!(edges[1].kind, SpecKind::Dynamic);
+        assert_eq!(graph.rel_path(edges[0].to), "src/a.js");
+        let unresolved = graph.unresolved_imports(index);
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].request, "./missing");
+
+        let c = graph.imports(edges[0].to)[0].to;
+        assert_eq!(graph.importers(c).len(), 2);
+        assert_eq!(graph.importers(index).len(), 0);
+        assert_eq!(graph.id_of(graph.path(c)), Some(c));
+    }
+
+    #[test]
+    fn test_changed_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let opts = ResolveOptions::default();
+
+        let a = create_test_file(root, "src/a.js", "import './shared'; import './c';");
+        let b = create_test_file(root, "src/b.js", "import './shared'; import './d';");
+        create_test_file(root, "src/shared.js", "// shared");
+        create_test_file(root, "src/c.js", "// c");
+        let d = create_test_file(root, "src/d.js", "// d");
+        let entries = [a.clone(), b.clone()];
+        let before = ModuleGraph::build(root, &opts, &entries).unwrap();
+
+        // Nothing changed
+        let same = ModuleGraph::build(root, &opts, &entries).unwrap();
+        assert!(same.changed_entries(&before, &[]).is_empty());
+
+        // An edit below one entry only affects that entry
+        fs::write(&d, "import './e';").unwrap();
+        create_test_file(root, "src/e.js", "// e");
+        let after = ModuleGraph::build(root, &opts, &entries).unwrap();
+        let changed = after.changed_entries(&before, std::slice::from_ref(&d));
+        assert_eq!(changed.iter().map(|&e| after.path(e)).collect::<Vec<_>>(), vec![b.as_path()]);
+
+        // An edited shared module affects both, and a new entry is always included
+        let f = create_test_file(root, "src/f.js", "// f");
+        let grown = ModuleGraph::build(root, &opts, &[a, b, f]).unwrap();
+        let edited = [root.join("src/shared.js")];
+        assert_eq!(grown.changed_entries(&after, &edited).len(), 3);
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        let entry = create_test_file(&root, "src/index.js", "import 'lib';");
+        create_test_file(&root, "src/unused.js", "// nothing imports this");
+        create_test_file(&root, "node_modules/lib/package.json", r#"{ "main": "index.js" }"#);
+        create_test_file(&root, "node_modules/lib/index.js", "require('./x');");
+        create_test_file(&root, "node_modules/lib/x.js", "");
+        let graph = ModuleGraph::build(&root, &ResolveOptions::default(), &[entry]).unwrap();
+
+        let (ids, files) = graph.resolve_target("src/index.js").unwrap();
+        assert_eq!((ids, files), (vec![graph.entries()[0]], vec!["src/index.js".to_string()]));
+        let (ids, files) = graph.resolve_target("src/unused.js").unwrap();
+        assert!(ids.is_empty());
+        assert_eq!(files, vec!["src/unused.js"]);
+        let (ids, files) = graph.resolve_target("lib").unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(files, vec!["node_modules/lib/index.js", "node_modules/lib/x.js"]);
+        assert!(graph.resolve_target("missing").is_err());
+    }
+
+    #[test]
+    fn test_collapsed_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(
+            root,
+            "src/index.js",
+            "import 'lodash'; import '@s/ui'; import 'react';",
+        );
+        for (name, main) in [("lodash", "index.js"), ("@s/ui", "index.js"), ("react", "index.js")] {
+            create_test_file(
+                root,
+                &format!("node_modules/{}/package.json", name),
+                &format!(r#"{{ "name": "{}", "main": "{}" }}"#, name, main),
+            );
+            create_test_file(root, &format!("node_modules/{}/index.js", name), "require('./x');");
+            create_test_file(root, &format!("node_modules/{}/x.js", name), "");
+        }
+
+        let build = |packages: PackageBoundaries| {
+            let opts = ResolveOptions { packages, ..Default::default() };
+            ModuleGraph::build(root, &opts, std::slice::from_ref(&entry)).unwrap()
+        };
+
+        let graph = build(PackageBoundaries::default());
+        assert_eq!(graph.len(), 7);
+
+        // Each package is one module, its directory; react is expanded
+        let graph = build(PackageBoundaries {
+            collapse_all: true,
+            expand: vec!["react".to_string()],
+            ..Default::default()
+        });
+        let targets: Vec<ModuleId> =
+            graph.imports(graph.entries()[0]).iter().map(|e| e.to).collect();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.rel_path(targets[0]), "node_modules/lodash");
+        assert_eq!(graph.package_name(targets[1]).as_deref(), Some("@s/ui"));
+        assert!(graph.is_collapsed(targets[1]));
+        assert!(graph.imports(targets[1]).is_empty());
+        assert!(!graph.is_collapsed(targets[2]));
+        assert_eq!(graph.imports(targets[2]).len(), 1);
+
+        let graph =
+            build(PackageBoundaries { collapse: vec!["lodash".to_string()], ..Default::default() });
+        assert_eq!(graph.len(), 6);
+    }
+
+    #[test]
+    fn test_type_only_imports_are_not_runtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(
+            root,
+            "src/index.ts",
+            "import type { A } from './a';\nimport { b } from './b';",
+        );
+        create_test_file(root, "src/a.ts", "export type A = string;");
+        create_test_file(root, "src/b.ts", "export const b = 1;");
+
+        let graph = ModuleGraph::build(root, &ResolveOptions::default(), &[entry]).unwrap();
+        assert_eq!(graph.len(), 3);
+
+        let index = graph.entries()[0];
+        assert_eq!(graph.imports(index).len(), 2);
+        assert_eq!(graph.imports(index)[0].kind, SpecKind::Type);
+        let runtime: Vec<_> = graph.runtime_imports(index).map(|e| e.request.as_str()).collect();
+        assert_eq!(runtime, vec!["./b"]);
+        assert_eq!(graph.reachable(index).len(), 2);
+    }
+
+    #[test]
+    fn test_without_re_exports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(root, "src/index.ts", "import { a } from './ui';");
+        create_test_file(root, "src/ui.ts", "export * from './a';\nimport './b';");
+        create_test_file(root, "src/a.ts", "export const a = 1;");
+        create_test_file(root, "src/b.ts", "// b");
+
+        let graph = ModuleGraph::build(root, &ResolveOptions::default(), &[entry]).unwrap();
+        let ui = graph.imports(graph.entries()[0])[0].to;
+        assert_eq!(graph.imports(ui)[0].kind, SpecKind::ReExport);
+        assert_eq!(graph.reachable(ui).len(), 3);
+
+        let own = graph.without_re_exports();
+        assert_eq!(own.len(), 4);
+        let requests: Vec<_> = own.imports(ui).iter().map(|e| e.request.as_str()).collect();
+        assert_eq!(requests, vec!["./b"]);
+        assert_eq!(own.reachable(ui).len(), 2);
+        assert!(own.importers(graph.resolved(ui, "./a").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_build_graph_circular() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(root, "src/index.js", "import './a';");
+        create_test_file(root, "src/a.js", "import './b';");
+        create_test_file(root, "src/b.js", "import './a';");
+
+        let graph = ModuleGraph::build(root, &ResolveOptions::default(), &[entry]).unwrap();
+        assert_eq!(graph.len(), 3);
+
+        let index = graph.entries()[0];
+        assert_eq!(graph.reachable(index).len(), 3);
+        let a = graph.imports(index)[0].to;
+        assert_eq!(graph.reachable(a).len(), 2);
+        assert_eq!(graph.importers(a).len(), 2);
+    }
+
+    #[test]
+    fn test_shared_modules_are_interned_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let first = create_test_file(root, "src/first.js", "import './shared';");
+        let second = create_test_file(root, "src/second.js", "import './shared';");
+        create_test_file(root, "src/shared.js", "// shared");
+
+        let graph =
+            ModuleGraph::build(root, &ResolveOptions::default(), &[first.clone(), second, first])
+                .unwrap();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.entries().len(), 2);
+
+        let shared = graph.imports(graph.entries()[0])[0].to;
+        assert_eq!(graph.imports(graph.entries()[1])[0].to, shared);
+    }
+
+    #[test]
+    fn test_in_package() {
+        let path = Path::new("/p/node_modules/a/node_modules/@s/b/lib/x.js");
+        assert!(in_package(path, "@s/b"));
+        assert!(in_package(path, "@s/b/lib"));
+        assert!(!in_package(path, "a"));
+        assert!(!in_package(path, "@s/b/lib/x.js"));
+        assert!(!in_package(Path::new("/p/src/a/index.js"), "a"));
+    }
+
+    #[test]
+    fn test_package_name() {
+        let mut graph = ModuleGraph::default();
+        let paths = [
+            "/p/node_modules/a/node_modules/@s/b/lib/x.js",
+            "/p/node_modules/lodash/fp.js",
+            "/p/src/index.js",
+        ];
+        let ids: Vec<ModuleId> = paths.iter().map(|p| graph.intern(Path::new(p)).0).collect();
+        assert_eq!(graph.package_name(ids[0]).as_deref(), Some("@s/b"));
+        assert_eq!(graph.package_name(ids[1]).as_deref(), Some("lodash"));
+        assert_eq!(graph.package_name(ids[2]), None);
+        assert!(graph.is_installed(ids[1]) && !graph.is_installed(ids[2]));
+        assert_eq!(
+            graph.package_root(ids[1]),
+            Some((PathBuf::from("/p/node_modules/lodash"), "lodash".to_string()))
+        );
+        let (dir, _) = graph.intern(Path::new("/p/node_modules/react"));
+        assert_eq!(graph.package_root(dir), None);
+        graph.collapsed[dir.index()] = true;
+        assert_eq!(
+            graph.package_root(dir),
+            Some((PathBuf::from("/p/node_modules/react"), "react".to_string()))
+        );
+    }
+}
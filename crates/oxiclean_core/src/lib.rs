@@ -2,22 +2,55 @@
 //!
 //! This crate provides shared functionality for analyzing JavaScript/TypeScript
 //! projects, including:
-//! - Parsing import statements from JS/TS files
-//! - Resolving module paths (relative, node_modules, tsconfig paths)
-//! - Collecting entry files from a project
-//! - Configuration utilities (git root finding, tsconfig reading)
+//! - Parsing import and export statements from JS/TS files
+//! - Resolving module paths (relative, node_modules, tsconfig paths), optionally
+//!   collapsing packages into single opaque modules
+//! - Collecting entry files from a project (all source files, or roots derived
+//!   from package.json fields and framework conventions)
+//! - Building the resolved module graph shared by every check, with an
+//!   on-disk cache of parse and resolve results and incremental rebuilds in
+//!   watch mode
+//! - Discovering workspace packages
+//! - Matching root-relative paths against glob patterns
+//! - Displaying root-relative paths relative to the working directory
+//! - Configuration utilities (git root finding, tsconfig reading, the
+//!   `oxiclean.json` / `oxiclean.toml` config file and settings shared by all checks)
 
+mod cache;
 mod collector;
 mod config;
+mod config_file;
 mod constants;
+mod display;
+mod glob;
+mod graph;
 mod parser;
 mod resolver;
+mod scc;
 mod types;
+mod watch;
+mod workspace;
 
 // Re-export public API
-pub use collector::{CollectorConfig, collect_entries};
-pub use config::{find_git_root, read_tsconfig_paths};
-pub use constants::{INDEX_FILES, JS_TS_EXTENSIONS, RESOLVE_EXTENSIONS};
-pub use parser::imports_for;
-pub use resolver::resolve;
-pub use types::{SpecKind, Specifier};
+pub use cache::{AnalysisCache, default_cache_dir};
+pub use collector::{
+    CollectorConfig, EntryMode, ExtraEntries, collect_entries, collect_source_files,
+};
+pub use config::{CommandConfig, SharedConfig, find_git_root, read_tsconfig_paths};
+pub use config_file::{
+    CHECK_SECTIONS, CONFIG_FILE_NAMES, ConfigFile, EntrySection, ThresholdOverride,
+    ThresholdOverrides, ThresholdSection,
+};
+pub use constants::{
+    CACHE_DIR_NAME, DEFAULT_CONDITIONS, DEFAULT_INCLUDE, DEFAULT_TEST_PATTERNS, IGNORE_FILE_NAME,
+    INDEX_FILES, JS_TS_EXTENSIONS, LOCKFILE_NAMES, RESOLVE_EXTENSIONS,
+};
+pub use display::{relativize_to_cwd, resolve_file_arg};
+pub use glob::GlobMatcher;
+pub use graph::{Edge, ModuleGraph, ModuleId};
+pub use parser::{imports_for, minified_size, parse_module};
+pub use resolver::{PackageBoundaries, ResolveOptions, resolve};
+pub use scc::Condensation;
+pub use types::{Export, ImportedNames, ParsedModule, ReExport, SpecKind, Specifier};
+pub use watch::{Rebuild, merge_entry_results, watch_project};
+pub use workspace::{WorkspacePackage, find_workspace_packages};
@@ -6,12 +6,16 @@ use oxc_ast::ast::*;
 use oxc_parser::{Parser as OxcParser, ParserReturn};
 use oxc_span::SourceType;
 use std::{
+    collections::HashSet,
     fs,
     path::{Path, PathBuf},
 };
 
-use crate::types::{SpecKind, Specifier};
+use crate::types::{Export, ImportedNames, ParsedModule, ReExport, SpecKind, Specifier};
 
+/// The imports of a file that remain at runtime, including re-exports, memoized
+/// in `cache`. Type-only imports are left out; [`parse_module`] returns those
+/// too, marked [`SpecKind::Type`], along with the file's exports.
 pub fn imports_for(
     file: &Path,
     cache: &DashMap<PathBuf, Vec<Specifier>>,
@@ -24,25 +28,38 @@ pub fn imports_for(
     trace!("Parsing file for imports: {}", file.display());
     let src =
         fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let specs: Vec<Specifier> =
+        parse_module(file, &src).imports.into_iter().filter(|s| s.kind.is_runtime()).collect();
+    cache.insert(file_buf, specs.clone());
+    Ok(specs)
+}
 
+/// Extracts the imports and exports from a file's source text.
+///
+/// Re-exports (`export { a } from './a'`, `export * from './b'`) are recorded both
+/// as exports and as [`SpecKind::ReExport`] imports that use no names, so the
+/// re-exported module is part of the graph while its names only count as used
+/// once the re-export is.
+pub fn parse_module(file: &Path, src: &str) -> ParsedModule {
     let st = source_type_for(file);
     let allocator = Allocator::default();
-    let ParserReturn { program, .. } = OxcParser::new(&allocator, &src, st).parse();
+    let ParserReturn { program, .. } = OxcParser::new(&allocator, src, st).parse();
 
+    let lines = LineIndex::new(src);
     let mut specs: Vec<Specifier> = Vec::new();
+    let mut exports: Vec<Export> = Vec::new();
+    let mut star_exports: Vec<String> = Vec::new();
+    let local_types = local_type_names(&program.body);
 
     for stmt in &program.body {
         match stmt {
             Statement::ImportDeclaration(decl) => {
-                // Skip type-only imports (import type { Foo } from 'bar')
-                if decl.import_kind.is_type() {
-                    trace!("Skipping type-only import declaration in {}", file.display());
-                    continue;
-                }
-
                 // Check if all specifiers are type-only (import { type Foo } from 'bar')
-                // If there's at least one non-type import, we should include it
-                let has_runtime_import = if let Some(specifiers) = &decl.specifiers {
+                // If there's at least one non-type import, it's a runtime import
+                let has_runtime_import = if decl.import_kind.is_type() {
+                    // import type { Foo } from 'bar'
+                    false
+                } else if let Some(specifiers) = &decl.specifiers {
                     specifiers.iter().any(|spec| match spec {
                         ImportDeclarationSpecifier::ImportSpecifier(s) => !s.import_kind.is_type(),
                         ImportDeclarationSpecifier::ImportDefaultSpecifier(_) => true,
@@ -53,21 +70,112 @@ pub fn imports_for(
                     true
                 };
 
-                if has_runtime_import {
-                    let req = decl.source.value.to_string();
+                let req = decl.source.value.to_string();
+                let kind = if has_runtime_import {
                     trace!("Found static import: '{}' in {}", req, file.display());
-                    specs.push(Specifier { request: req, kind: SpecKind::Static });
+                    SpecKind::Static
+                } else {
+                    trace!("Found type-only import: '{}' in {}", req, file.display());
+                    SpecKind::Type
+                };
+                specs.push(Specifier {
+                    request: req,
+                    kind,
+                    line: lines.line_of(decl.span.start),
+                    names: imported_names(decl.specifiers.as_ref().map(|s| &s[..])),
+                });
+            }
+            Statement::ExportNamedDeclaration(decl) => {
+                let line = lines.line_of(decl.span.start);
+                let decl_is_type = decl.export_kind.is_type();
+                if let Some(declaration) = &decl.declaration {
+                    let is_type = decl_is_type
+                        || matches!(
+                            declaration,
+                            Declaration::TSTypeAliasDeclaration(_)
+                                | Declaration::TSInterfaceDeclaration(_)
+                        );
+                    for name in declared_names(declaration) {
+                        exports.push(Export { name, line, is_type, from: None });
+                    }
+                    if let Declaration::VariableDeclaration(vd) = declaration {
+                        for decl in &vd.declarations {
+                            if let Some(init) = &decl.init {
+                                extract_require_from_expression(init, &lines, &mut specs);
+                            }
+                        }
+                    }
                 }
+
+                let source = decl.source.as_ref().map(|s| s.value.to_string());
+                for spec in &decl.specifiers {
+                    let local = spec.local.name().to_string();
+                    let is_type = decl_is_type
+                        || spec.export_kind.is_type()
+                        || (source.is_none() && local_types.contains(local.as_str()));
+                    let from = source
+                        .as_ref()
+                        .map(|request| ReExport { request: request.clone(), name: local });
+                    exports.push(Export {
+                        name: spec.exported.name().to_string(),
+                        line,
+                        is_type,
+                        from,
+                    });
+                }
+
+                if let Some(request) = source {
+                    trace!("Found re-export: '{}' in {}", request, file.display());
+                    let type_only = decl_is_type
+                        || (!decl.specifiers.is_empty()
+                            && decl.specifiers.iter().all(|s| s.export_kind.is_type()));
+                    specs.push(Specifier {
+                        request,
+                        kind: if type_only { SpecKind::Type } else { SpecKind::ReExport },
+                        line,
+                        names: ImportedNames::Nothing,
+                    });
+                }
+            }
+            Statement::ExportDefaultDeclaration(decl) => {
+                let is_type = matches!(
+                    decl.declaration,
+                    ExportDefaultDeclarationKind::TSInterfaceDeclaration(_)
+                );
+                exports.push(Export {
+                    name: "default".to_string(),
+                    line: lines.line_of(decl.span.start),
+                    is_type,
+                    from: None,
+                });
+            }
+            Statement::ExportAllDeclaration(decl) => {
+                let request = decl.source.value.to_string();
+                let line = lines.line_of(decl.span.start);
+                trace!("Found star re-export: '{}' in {}", request, file.display());
+                match &decl.exported {
+                    // export * as ns from './x'
+                    Some(exported) => exports.push(Export {
+                        name: exported.name().to_string(),
+                        line,
+                        is_type: decl.export_kind.is_type(),
+                        from: Some(ReExport { request: request.clone(), name: "*".to_string() }),
+                    }),
+                    None => star_exports.push(request.clone()),
+                }
+                let kind =
+                    if decl.export_kind.is_type() { SpecKind::Type } else { SpecKind::ReExport };
+                specs.push(Specifier { request, kind, line, names: ImportedNames::Nothing });
             }
             Statement::ExpressionStatement(es) => {
                 // Recursively extract all require() and import() calls
-                extract_require_from_expression(&es.expression, &mut specs);
+                extract_require_from_expression(&es.expression, &lines, &mut specs);
             }
             Statement::VariableDeclaration(vd) => {
                 // Handle const x = require('...') or const x = someFunc(require('...'))
                 for decl in &vd.declarations {
                     if let Some(init) = &decl.init {
-                        extract_require_from_expression(init, &mut specs);
+                        extract_require_from_expression(init, &lines, &mut specs);
                     }
                 }
             }
@@ -75,12 +183,89 @@ pub fn imports_for(
         }
     }
 
-    debug!("Found {} import specifiers in {}", specs.len(), file.display());
-    cache.insert(file_buf, specs.clone());
-    Ok(specs)
+    debug!(
+        "Found {} import specifiers and {} exports in {}",
+        specs.len(),
+        exports.len(),
+        file.display()
+    );
+    ParsedModule { imports: specs, exports, star_exports }
+}
+
+/// The exports an import declaration uses
+fn imported_names(specifiers: Option<&[ImportDeclarationSpecifier]>) -> ImportedNames {
+    let Some(specifiers) = specifiers else {
+        return ImportedNames::Nothing;
+    };
+    let mut names = Vec::new();
+    for spec in specifiers {
+        match spec {
+            ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                names.push(s.imported.name().to_string())
+            }
+            ImportDeclarationSpecifier::ImportDefaultSpecifier(_) => {
+                names.push("default".to_string())
+            }
+            ImportDeclarationSpecifier::ImportNamespaceSpecifier(_) => return ImportedNames::All,
+        }
+    }
+    ImportedNames::Named(names)
 }
 
-fn extract_require_from_expression(expr: &Expression, specs: &mut Vec<Specifier>) {
+/// Names bound by an exported declaration
+fn declared_names(declaration: &Declaration) -> Vec<String> {
+    match declaration {
+        Declaration::VariableDeclaration(vd) => vd
+            .declarations
+            .iter()
+            .flat_map(|d| d.id.get_binding_identifiers())
+            .map(|id| id.name.to_string())
+            .collect(),
+        Declaration::FunctionDeclaration(f) => f.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::ClassDeclaration(c) => c.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::TSTypeAliasDeclaration(t) => vec![t.id.name.to_string()],
+        Declaration::TSInterfaceDeclaration(t) => vec![t.id.name.to_string()],
+        Declaration::TSEnumDeclaration(e) => vec![e.id.name.to_string()],
+        Declaration::TSModuleDeclaration(m) => match &m.id {
+            TSModuleDeclarationName::Identifier(id) => vec![id.name.to_string()],
+            TSModuleDeclarationName::StringLiteral(_) => vec![],
+        },
+        Declaration::TSImportEqualsDeclaration(d) => vec![d.id.name.to_string()],
+    }
+}
+
+/// Top-level names that only exist as types: type aliases, interfaces and type-only
+/// imports, so `export { Foo }` can be classified
+fn local_type_names<'a>(body: &'a [Statement<'a>]) -> HashSet<&'a str> {
+    let mut names = HashSet::new();
+    for stmt in body {
+        match stmt {
+            Statement::TSTypeAliasDeclaration(t) => {
+                names.insert(t.id.name.as_str());
+            }
+            Statement::TSInterfaceDeclaration(t) => {
+                names.insert(t.id.name.as_str());
+            }
+            Statement::ImportDeclaration(decl) => {
+                for spec in decl.specifiers.iter().flatten() {
+                    if decl.import_kind.is_type()
+                        || matches!(spec, ImportDeclarationSpecifier::ImportSpecifier(s) if s.import_kind.is_type())
+                    {
+                        names.insert(spec.local().name.as_str());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+fn extract_require_from_expression(
+    expr: &Expression,
+    lines: &LineIndex,
+    specs: &mut Vec<Specifier>,
+) {
     match expr {
         Expression::CallExpression(ce) => {
             // Check if this is a require() call
@@ -90,48 +275,58 @@ fn extract_require_from_expression(expr: &Expression, specs: &mut Vec<Specifier>
                 && let Some(Expression::StringLiteral(sl)) = ce.arguments[0].as_expression()
             {
                 trace!("Found require() call: '{}'", sl.value);
-                specs.push(Specifier { request: sl.value.to_string(), kind: SpecKind::Static });
+                specs.push(Specifier {
+                    request: sl.value.to_string(),
+                    kind: SpecKind::Static,
+                    line: lines.line_of(ce.span.start),
+                    names: ImportedNames::All,
+                });
             }
             // Recursively check arguments for nested require() calls
             for arg in &ce.arguments {
                 if let Some(arg_expr) = arg.as_expression() {
-                    extract_require_from_expression(arg_expr, specs);
+                    extract_require_from_expression(arg_expr, lines, specs);
                 }
             }
             // Also check the callee in case it's a complex expression
-            extract_require_from_expression(&ce.callee, specs);
+            extract_require_from_expression(&ce.callee, lines, specs);
         }
         Expression::ImportExpression(ie) => {
             if let Expression::StringLiteral(sl) = &ie.source {
                 trace!("Found dynamic import(): '{}'", sl.value);
-                specs.push(Specifier { request: sl.value.to_string(), kind: SpecKind::Dynamic });
+                specs.push(Specifier {
+                    request: sl.value.to_string(),
+                    kind: SpecKind::Dynamic,
+                    line: lines.line_of(ie.span.start),
+                    names: ImportedNames::All,
+                });
             }
         }
         // Handle other expression types that might contain nested expressions
         Expression::ArrayExpression(ae) => {
             for elem in &ae.elements {
                 if let Some(expr) = elem.as_expression() {
-                    extract_require_from_expression(expr, specs);
+                    extract_require_from_expression(expr, lines, specs);
                 }
             }
         }
         Expression::ObjectExpression(oe) => {
             for prop in &oe.properties {
                 if let Some(expr) = prop.as_property() {
-                    extract_require_from_expression(&expr.value, specs);
+                    extract_require_from_expression(&expr.value, lines, specs);
                 }
             }
         }
         Expression::ConditionalExpression(ce) => {
-            extract_require_from_expression(&ce.test, specs);
-            extract_require_from_expression(&ce.consequent, specs);
-            extract_require_from_expression(&ce.alternate, specs);
+            extract_require_from_expression(&ce.test, lines, specs);
+            extract_require_from_expression(&ce.consequent, lines, specs);
+            extract_require_from_expression(&ce.alternate, lines, specs);
         }
         Expression::AssignmentExpression(ae) => {
-            extract_require_from_expression(&ae.right, specs);
+            extract_require_from_expression(&ae.right, lines, specs);
         }
         Expression::ParenthesizedExpression(pe) => {
-            extract_require_from_expression(&pe.expression, specs);
+            extract_require_from_expression(&pe.expression, lines, specs);
         }
         _ => {
             // For other expression types, we don't recurse further
@@ -139,6 +334,65 @@ fn extract_require_from_expression(expr: &Expression, specs: &mut Vec<Specifier>
     }
 }
 
+/// Estimates the size of a source file once minified: comments are dropped and
+/// whitespace is only kept where it separates two words. Whitespace inside
+/// string literals is treated the same way, names are not mangled and types
+/// are not stripped, so this is an estimate rather than a minifier's output.
+/// Files that fail to parse count in full.
+pub fn minified_size(file: &Path, src: &str) -> usize {
+    let allocator = Allocator::default();
+    let ret = OxcParser::new(&allocator, src, source_type_for(file)).parse();
+    if ret.panicked {
+        return src.len();
+    }
+
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+    let bytes = src.as_bytes();
+    let mut comments = ret.program.comments.iter().map(|c| c.span).peekable();
+    let mut size = 0;
+    let mut last = None;
+    let mut gap = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(comment) = comments.next_if(|span| span.start as usize == i) {
+            gap = true;
+            i = comment.end as usize;
+            continue;
+        }
+        let b = bytes[i];
+        i += 1;
+        if b.is_ascii_whitespace() {
+            gap = true;
+            continue;
+        }
+        if gap && last.is_some_and(is_word) && is_word(b) {
+            size += 1;
+        }
+        gap = false;
+        size += 1;
+        last = Some(b);
+    }
+    trace!("{} is about {} of {} bytes minified", file.display(), size, src.len());
+    size
+}
+
+/// Maps byte offsets in a source file to 1-based line numbers
+struct LineIndex {
+    starts: Vec<u32>,
+}
+
+impl LineIndex {
+    fn new(src: &str) -> Self {
+        let starts =
+            std::iter::once(0).chain(src.match_indices('\n').map(|(i, _)| i as u32 + 1)).collect();
+        Self { starts }
+    }
+
+    fn line_of(&self, offset: u32) -> u32 {
+        self.starts.partition_point(|&start| start <= offset) as u32
+    }
+}
+
 fn source_type_for(path: &Path) -> SourceType {
     let ext = path.extension().and_then(|e| e.to_str());
 
@@ -260,6 +514,16 @@ mod tests {
         assert_eq!(imports.len(), 0);
     }
 
+    #[test]
+    fn test_type_only_import_recorded_as_type() {
+        let parsed = parse_module(
+            Path::new("test.ts"),
+            "import type { Foo } from './types';\nimport { type Bar } from './bar';",
+        );
+        assert_eq!(parsed.imports.len(), 2);
+        assert!(parsed.imports.iter().all(|s| s.kind == SpecKind::Type));
+    }
+
     #[test]
     fn test_mixed_type_and_runtime_import() {
         let temp_dir = TempDir::new().unwrap();
@@ -270,9 +534,10 @@ mod tests {
             "import { type Foo, bar } from './utils';",
         );
         let imports = imports_for(&file, &cache).unwrap();
-        // Should include because there's at least one runtime import (bar)
+        // A runtime import because there's at least one runtime specifier (bar)
         assert_eq!(imports.len(), 1);
         assert_eq!(imports[0].request, "./utils");
+        assert_eq!(imports[0].kind, SpecKind::Static);
     }
 
     #[test]
@@ -292,6 +557,91 @@ mod tests {
         assert!(requests.contains(&"./side-effect"));
     }
 
+    #[test]
+    fn test_import_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DashMap::new();
+        let file = create_test_file(
+            temp_dir.path(),
+            "test.js",
+            "import foo from './foo';\n\nconst bar = require('./bar');\nimport('./lazy');",
+        );
+        let imports = imports_for(&file, &cache).unwrap();
+        let lines: Vec<(&str, u32)> =
+            imports.iter().map(|s| (s.request.as_str(), s.line)).collect();
+        assert_eq!(lines, vec![("./foo", 1), ("./bar", 3), ("./lazy", 4)]);
+    }
+
+    #[test]
+    fn test_imported_names() {
+        let imports = parse_module(
+            Path::new("test.js"),
+            "import foo, { bar as baz } from './a';\nimport * as ns from './b';\n\
+             import './c';\nconst d = require('./d');",
+        )
+        .imports;
+        let names: Vec<&ImportedNames> = imports.iter().map(|s| &s.names).collect();
+        assert_eq!(
+            names,
+            vec![
+                &ImportedNames::Named(vec!["default".to_string(), "bar".to_string()]),
+                &ImportedNames::All,
+                &ImportedNames::Nothing,
+                &ImportedNames::All,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exports() {
+        let parsed = parse_module(
+            Path::new("test.ts"),
+            "export const a = 1, { b } = obj;\nexport function f() {}\n\
+             export type T = string;\ninterface I {}\nexport { I, a as aa };\n\
+             export default class {}\nexport { x as y } from './x';\n\
+             export * from './star';\nexport * as ns from './ns';",
+        );
+        let exports: Vec<(&str, u32, bool)> =
+            parsed.exports.iter().map(|e| (e.name.as_str(), e.line, e.is_type)).collect();
+        assert_eq!(
+            exports,
+            vec![
+                ("a", 1, false),
+                ("b", 1, false),
+                ("f", 2, false),
+                ("T", 3, true),
+                ("I", 5, true),
+                ("aa", 5, false),
+                ("default", 6, false),
+                ("y", 7, false),
+                ("ns", 9, false),
+            ]
+        );
+        assert_eq!(
+            parsed.exports[7].from,
+            Some(ReExport { request: "./x".to_string(), name: "x".to_string() })
+        );
+        assert_eq!(parsed.exports[8].from.as_ref().unwrap().name, "*");
+        assert_eq!(parsed.star_exports, vec!["./star"]);
+
+        // Re-exports are imports that use no names
+        let requests: Vec<&str> = parsed.imports.iter().map(|s| s.request.as_str()).collect();
+        assert_eq!(requests, vec!["./x", "./star", "./ns"]);
+        assert!(parsed.imports.iter().all(|s| s.names == ImportedNames::Nothing));
+        assert!(parsed.imports.iter().all(|s| s.kind == SpecKind::ReExport));
+    }
+
+    #[test]
+    fn test_minified_size() {
+        let src = "// header\nimport { a } from './a';\n\n/* block */\nexport const b = a + 1;\n";
+        assert_eq!(
+            minified_size(Path::new("test.ts"), src),
+            "import{a}from'./a';export const b=a+1;".len()
+        );
+        // Nothing to parse
+        assert_eq!(minified_size(Path::new("test.ts"), ""), 0);
+    }
+
     #[test]
     fn test_cache_behavior() {
         let temp_dir = TempDir::new().unwrap();
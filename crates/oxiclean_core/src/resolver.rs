@@ -8,16 +8,77 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::constants::{INDEX_FILES, RESOLVE_EXTENSIONS};
+use crate::constants::{DEFAULT_CONDITIONS, INDEX_FILES, RESOLVE_EXTENSIONS};
+
+/// Settings that affect how import requests are resolved
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// Path aliases (from tsconfig.json `paths` and the oxiclean config file),
+    /// keyed by alias with any trailing `/*` stripped, mapping to absolute targets
+    pub aliases: HashMap<String, Vec<String>>,
+    /// Package.json `exports` conditions to match, in priority order
+    /// (`default` is always tried last)
+    pub conditions: Vec<String>,
+    /// Packages in node_modules the module graph stops at. Applied to resolved
+    /// paths, so not part of the cache fingerprint
+    pub packages: PackageBoundaries,
+}
+
+impl ResolveOptions {
+    /// Whether a request matches one of the path aliases, so it names a
+    /// project file rather than a package
+    pub fn is_aliased(&self, request: &str) -> bool {
+        !alias_candidates(self, request).is_empty()
+    }
+}
 
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            aliases: HashMap::new(),
+            conditions: DEFAULT_CONDITIONS.iter().map(|c| c.to_string()).collect(),
+            packages: PackageBoundaries::default(),
+        }
+    }
+}
+
+/// Which packages in node_modules are collapsed into a single opaque module,
+/// whose own imports are not followed
+#[derive(Debug, Clone, Default)]
+pub struct PackageBoundaries {
+    /// Collapse every package not listed in `expand`
+    pub collapse_all: bool,
+    /// Packages to collapse even when `collapse_all` is off
+    pub collapse: Vec<String>,
+    /// Packages to follow into even when `collapse_all` is on
+    pub expand: Vec<String>,
+}
+
+impl PackageBoundaries {
+    pub fn is_collapsed(&self, package: &str) -> bool {
+        !self.expand.iter().any(|p| p == package)
+            && (self.collapse_all || self.collapse.iter().any(|p| p == package))
+    }
+
+    /// Whether any package is collapsed at all
+    pub fn is_active(&self) -> bool {
+        self.collapse_all || !self.collapse.is_empty()
+    }
+}
+
+/// Resolves an import request made from `from_file`.
+///
+/// Results are cached per `(directory of from_file, request)`, since every file in a
+/// directory resolves a given request the same way.
 pub fn resolve(
     root: &Path,
-    tsconfig_paths: &HashMap<String, Vec<String>>,
+    opts: &ResolveOptions,
     from_file: &Path,
     request: &str,
     cache: &DashMap<(PathBuf, String), Option<PathBuf>>,
 ) -> Result<Option<PathBuf>> {
-    let key = (from_file.to_path_buf(), request.to_string());
+    let from_dir = from_file.parent().unwrap_or(root);
+    let key = (from_dir.to_path_buf(), request.to_string());
     if let Some(v) = cache.get(&key) {
         trace!("Cache hit for resolve: '{}' from {}", request, from_file.display());
         return Ok(v.clone());
@@ -28,8 +89,7 @@ pub fn resolve(
         if request.starts_with("./") || request.starts_with("../") || request.starts_with("/") {
             // Relative imports
             trace!("Resolving as relative import: '{}'", request);
-            let base = from_file.parent().unwrap_or(root);
-            let p = clean(base.join(request).to_string_lossy().to_string());
+            let p = clean(from_dir.join(request).to_string_lossy().to_string());
             let result = resolve_file(Path::new(&p));
             if result.is_some() {
                 trace!("Resolved relative import '{}' to {:?}", request, result);
@@ -40,49 +100,21 @@ pub fn resolve(
         } else {
             // Check tsconfig path aliases first
             trace!("Checking tsconfig path aliases for '{}'", request);
-            let mut alias_resolved = None;
-            for (alias, targets) in tsconfig_paths {
-                // Handle wildcard aliases (e.g., "@components/*")
-                let alias_pattern = alias.trim_end_matches("/*");
-                let matches = if alias.ends_with("/*") {
-                    request.starts_with(alias_pattern) && request.len() > alias_pattern.len()
-                } else {
-                    request.starts_with(alias)
-                };
-
-                if matches {
-                    trace!("Matched alias '{}' for request '{}'", alias, request);
-                    // Replace alias with target path
-                    let remainder = if alias.ends_with("/*") {
-                        request.get(alias_pattern.len()..).unwrap_or("").trim_start_matches('/')
-                    } else {
-                        request.trim_start_matches(alias).trim_start_matches('/')
-                    };
-                    for target in targets {
-                        let candidate = if remainder.is_empty() {
-                            PathBuf::from(target)
-                        } else {
-                            PathBuf::from(target).join(remainder)
-                        };
-                        if let Some(resolved) = resolve_file(&candidate) {
-                            trace!("Resolved alias '{}' to {:?}", alias, resolved);
-                            alias_resolved = Some(resolved);
-                            break;
-                        }
-                    }
-                    if alias_resolved.is_some() {
-                        break;
-                    }
+            let alias_resolved = alias_candidates(opts, request).iter().find_map(|candidate| {
+                let resolved = resolve_file(candidate);
+                if resolved.is_some() {
+                    trace!("Resolved alias candidate {:?} to {:?}", candidate, resolved);
                 }
-            }
+                resolved
+            });
 
             if alias_resolved.is_some() {
                 alias_resolved
             } else {
                 // Fallback to node_modules resolution - start from the file's directory
                 trace!("Resolving as node_modules package: '{}'", request);
-                let start_dir = from_file.parent().unwrap_or(root);
-                let result = resolve_node_module_from_dir(start_dir, request, root);
+                let result =
+                    resolve_node_module_from_dir(from_dir, request, root, &opts.conditions);
                 if result.is_some() {
                     trace!("Resolved node_modules package '{}' to {:?}", request, result);
                 } else {
@@ -99,7 +131,64 @@ pub fn resolve(
     Ok(resolved)
 }
 
-fn resolve_file(p: &Path) -> Option<PathBuf> {
+/// The paths a request maps to through the path aliases it matches, in the
+/// order they are tried
+fn alias_candidates(opts: &ResolveOptions, request: &str) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    for (alias, targets) in &opts.aliases {
+        // Handle wildcard aliases (e.g., "@components/*")
+        let alias_pattern = alias.trim_end_matches("/*");
+        let matches = if alias.ends_with("/*") {
+            request.starts_with(alias_pattern) && request.len() > alias_pattern.len()
+        } else {
+            request.starts_with(alias)
+        };
+        if !matches {
+            continue;
+        }
+
+        trace!("Matched alias '{}' for request '{}'", alias, request);
+        // Replace alias with target path
+        let remainder = if alias.ends_with("/*") {
+            request.get(alias_pattern.len()..).unwrap_or("").trim_start_matches('/')
+        } else {
+            request.trim_start_matches(alias).trim_start_matches('/')
+        };
+        for target in targets {
+            candidates.push(if remainder.is_empty() {
+                PathBuf::from(target)
+            } else {
+                PathBuf::from(target).join(remainder)
+            });
+        }
+    }
+    candidates
+}
+
+/// The directories whose contents decide which file a request made from
+/// `from_dir` resolves to, apart from package manifests and node_modules: for
+/// each candidate path, its parent (for the path itself and its extensions)
+/// and the path itself (for its index files)
+pub(crate) fn probed_dirs(opts: &ResolveOptions, from_dir: &Path, request: &str) -> Vec<PathBuf> {
+    let candidates =
+        if request.starts_with("./") || request.starts_with("../") || request.starts_with("/") {
+            vec![clean(from_dir.join(request).to_string_lossy().to_string())]
+        } else {
+            alias_candidates(opts, request)
+        };
+    let mut dirs: Vec<PathBuf> = candidates
+        .into_iter()
+        .flat_map(|candidate| {
+            let parent = candidate.parent().map(Path::to_path_buf);
+            parent.into_iter().chain([candidate])
+        })
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+pub(crate) fn resolve_file(p: &Path) -> Option<PathBuf> {
     // Try exact path first (but only if it's a file, not a directory)
     if p.exists() && p.is_file() {
         return Some(p.canonicalize().unwrap_or_else(|_| p.to_path_buf()));
@@ -140,13 +229,14 @@ fn resolve_node_module_from_dir(
     start_dir: &Path,
     pkg: &str,
     workspace_root: &Path,
+    conditions: &[String],
 ) -> Option<PathBuf> {
     trace!("Walking up from {:?} to find node_modules for '{}'", start_dir, pkg);
     // Walk up the directory tree looking for node_modules
     let mut current_dir = start_dir;
 
     loop {
-        let result = resolve_node_module(current_dir, pkg);
+        let result = resolve_node_module(current_dir, pkg, conditions);
         if result.is_some() {
             return result;
         }
@@ -163,7 +253,7 @@ fn resolve_node_module_from_dir(
     None
 }
 
-fn resolve_node_module(root: &Path, pkg: &str) -> Option<PathBuf> {
+fn resolve_node_module(root: &Path, pkg: &str, conditions: &[String]) -> Option<PathBuf> {
     // Handle scoped packages like @nominal-io/ui
     let nm = root.join("node_modules").join(pkg);
     if !nm.exists() {
@@ -179,37 +269,17 @@ fn resolve_node_module(root: &Path, pkg: &str) -> Option<PathBuf> {
     {
         // Try exports field first (modern packages)
         if let Some(exports) = v.get("exports") {
-            // Handle string exports
-            if let Some(s) = exports.as_str() {
-                let p = nm.join(s.trim_start_matches("./"));
+            // Subpath exports ({ ".": ..., "./utils": ... }) vs. a bare condition map for "."
+            let dot_export = match exports.as_object() {
+                Some(obj) if obj.keys().any(|k| k.starts_with('.')) => obj.get("."),
+                _ => Some(exports),
+            };
+            if let Some(target) = dot_export.and_then(|e| exports_target(e, conditions)) {
+                let p = nm.join(target.trim_start_matches("./"));
                 if let Some(resolved) = resolve_file(&p) {
                     return Some(resolved);
                 }
             }
-            // Handle object exports - look for "." or "./index" entry
-            if let Some(obj) = exports.as_object() {
-                // Try "." first (default export)
-                if let Some(dot_export) = obj.get(".") {
-                    if let Some(s) = dot_export.as_str() {
-                        let p = nm.join(s.trim_start_matches("./"));
-                        if let Some(resolved) = resolve_file(&p) {
-                            return Some(resolved);
-                        }
-                    }
-                    // Handle conditional exports like { ".": { "import": "./dist/index.js" } }
-                    if let Some(conditions) = dot_export.as_object() {
-                        // Prefer import, then require, then default
-                        for key in ["import", "require", "default"] {
-                            if let Some(s) = conditions.get(key).and_then(|x| x.as_str()) {
-                                let p = nm.join(s.trim_start_matches("./"));
-                                if let Some(resolved) = resolve_file(&p) {
-                                    return Some(resolved);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
         }
 
         // Try module field (ESM entry point)
@@ -240,6 +310,24 @@ fn resolve_node_module(root: &Path, pkg: &str) -> Option<PathBuf> {
     None
 }
 
+/// Picks the target of an `exports` entry, following (possibly nested)
+/// conditional exports like `{ "import": { "browser": "./dist/index.js" } }`
+fn exports_target<'a>(value: &'a serde_json::Value, conditions: &[String]) -> Option<&'a str> {
+    match value {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Array(items) => {
+            items.iter().find_map(|item| exports_target(item, conditions))
+        }
+        serde_json::Value::Object(obj) => conditions
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once("default"))
+            .filter_map(|key| obj.get(key))
+            .find_map(|v| exports_target(v, conditions)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +343,10 @@ mod tests {
         file_path
     }
 
+    fn opts_with_aliases(aliases: HashMap<String, Vec<String>>) -> ResolveOptions {
+        ResolveOptions { aliases, ..Default::default() }
+    }
+
     #[test]
     fn test_resolve_relative_same_dir() {
         let temp_dir = TempDir::new().unwrap();
@@ -263,7 +355,8 @@ mod tests {
         let from_file = create_test_file(root, "src/file.js", "// test");
         let target_file = create_test_file(root, "src/utils.js", "// utils");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "./utils", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "./utils", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -277,7 +370,8 @@ mod tests {
         let from_file = create_test_file(root, "src/components/Button.js", "// test");
         let target_file = create_test_file(root, "src/utils.js", "// utils");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "../utils", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "../utils", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -292,7 +386,8 @@ mod tests {
         let target_file = create_test_file(root, "src/utils.ts", "// utils");
 
         // Request without extension should resolve to .ts file
-        let resolved = resolve(root, &HashMap::new(), &from_file, "./utils", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "./utils", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -307,7 +402,8 @@ mod tests {
         let target_file = create_test_file(root, "src/utils/index.js", "// utils");
 
         // Request directory should resolve to index.js
-        let resolved = resolve(root, &HashMap::new(), &from_file, "./utils", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "./utils", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -328,8 +424,14 @@ mod tests {
             vec![root.join("src/components").to_string_lossy().to_string()],
         );
 
-        let resolved =
-            resolve(root, &tsconfig_paths, &from_file, "@components/Button", &cache).unwrap();
+        let resolved = resolve(
+            root,
+            &opts_with_aliases(tsconfig_paths),
+            &from_file,
+            "@components/Button",
+            &cache,
+        )
+        .unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -350,8 +452,14 @@ mod tests {
             vec![root.join("src/components").to_string_lossy().to_string()],
         );
 
-        let resolved =
-            resolve(root, &tsconfig_paths, &from_file, "@components/Button", &cache).unwrap();
+        let resolved = resolve(
+            root,
+            &opts_with_aliases(tsconfig_paths),
+            &from_file,
+            "@components/Button",
+            &cache,
+        )
+        .unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), target_file.canonicalize().unwrap());
@@ -364,7 +472,8 @@ mod tests {
         let root = temp_dir.path();
         let from_file = create_test_file(root, "src/file.js", "// test");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "./nonexistent", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "./nonexistent", &cache).unwrap();
         assert!(resolved.is_none());
     }
 
@@ -377,11 +486,13 @@ mod tests {
         let _target_file = create_test_file(root, "src/utils.js", "// utils");
 
         // First call
-        let resolved1 = resolve(root, &HashMap::new(), &from_file, "./utils", &cache).unwrap();
+        let resolved1 =
+            resolve(root, &ResolveOptions::default(), &from_file, "./utils", &cache).unwrap();
         assert!(resolved1.is_some());
 
         // Second call should use cache
-        let resolved2 = resolve(root, &HashMap::new(), &from_file, "./utils", &cache).unwrap();
+        let resolved2 =
+            resolve(root, &ResolveOptions::default(), &from_file, "./utils", &cache).unwrap();
         assert!(resolved2.is_some());
         assert_eq!(resolved1.unwrap(), resolved2.unwrap());
 
@@ -403,7 +514,8 @@ mod tests {
         fs::write(&pkg_json, r#"{"main": "lib/index.js"}"#).unwrap();
         let main_file = create_test_file(&pkg_dir, "lib/index.js", "// main");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "test-pkg", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), main_file.canonicalize().unwrap());
@@ -423,7 +535,8 @@ mod tests {
         fs::write(&pkg_json, r#"{"exports": "./dist/index.js"}"#).unwrap();
         let main_file = create_test_file(&pkg_dir, "dist/index.js", "// main");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "test-pkg", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), main_file.canonicalize().unwrap());
@@ -443,7 +556,8 @@ mod tests {
         fs::write(&pkg_json, r#"{"exports": {".": "./dist/index.js"}}"#).unwrap();
         let main_file = create_test_file(&pkg_dir, "dist/index.js", "// main");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "test-pkg", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), main_file.canonicalize().unwrap());
@@ -461,7 +575,8 @@ mod tests {
         fs::create_dir_all(&pkg_dir).unwrap();
         let index_file = create_test_file(&pkg_dir, "index.js", "// index");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "test-pkg", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), index_file.canonicalize().unwrap());
@@ -480,9 +595,60 @@ mod tests {
         // File in subdirectory should still find root node_modules
         let from_file = create_test_file(root, "src/nested/deep/file.js", "// test");
 
-        let resolved = resolve(root, &HashMap::new(), &from_file, "test-pkg", &cache).unwrap();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
         assert!(resolved.is_some());
         // Normalize paths for comparison
         assert_eq!(resolved.unwrap().canonicalize().unwrap(), index_file.canonicalize().unwrap());
     }
+
+    #[test]
+    fn test_resolve_node_modules_with_nested_conditions() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DashMap::new();
+        let root = temp_dir.path();
+        let from_file = create_test_file(root, "src/file.js", "// test");
+
+        let pkg_dir = root.join("node_modules").join("test-pkg");
+        create_test_file(
+            &pkg_dir,
+            "package.json",
+            r#"{"exports": {".": {"import": {"types": "./index.d.ts", "default": "./esm/index.js"}, "require": "./cjs/index.js"}}}"#,
+        );
+        let esm_file = create_test_file(&pkg_dir, "esm/index.js", "// esm");
+        create_test_file(&pkg_dir, "cjs/index.js", "// cjs");
+
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
+        assert_eq!(resolved.unwrap().canonicalize().unwrap(), esm_file.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_node_modules_with_custom_conditions() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DashMap::new();
+        let root = temp_dir.path();
+        let from_file = create_test_file(root, "src/file.js", "// test");
+
+        // Bare condition map (no "." key)
+        let pkg_dir = root.join("node_modules").join("test-pkg");
+        create_test_file(
+            &pkg_dir,
+            "package.json",
+            r#"{"exports": {"node": "./node.js", "browser": "./browser.js", "default": "./index.js"}}"#,
+        );
+        create_test_file(&pkg_dir, "node.js", "// node");
+        let browser_file = create_test_file(&pkg_dir, "browser.js", "// browser");
+        let index_file = create_test_file(&pkg_dir, "index.js", "// index");
+
+        let opts = ResolveOptions { conditions: vec!["browser".to_string()], ..Default::default() };
+        let resolved = resolve(root, &opts, &from_file, "test-pkg", &cache).unwrap();
+        assert_eq!(resolved.unwrap().canonicalize().unwrap(), browser_file.canonicalize().unwrap());
+
+        // No matching condition falls back to "default"
+        let cache = DashMap::new();
+        let resolved =
+            resolve(root, &ResolveOptions::default(), &from_file, "test-pkg", &cache).unwrap();
+        assert_eq!(resolved.unwrap().canonicalize().unwrap(), index_file.canonicalize().unwrap());
+    }
 }
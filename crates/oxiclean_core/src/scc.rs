@@ -0,0 +1,420 @@
+use log::debug;
+use std::collections::HashMap;
+
+use crate::graph::{Edge, ModuleGraph, ModuleId};
+
+const UNVISITED: u32 = u32::MAX;
+
+/// The strongly connected components of a [`ModuleGraph`], collapsed into a DAG.
+///
+/// Components are numbered in reverse topological order: every successor of a
+/// component has a smaller index, so iterating `0..len()` visits dependencies
+/// before their importers.
+#[derive(Debug, Clone)]
+pub struct Condensation {
+    component: Vec<u32>,
+    members: Vec<Vec<ModuleId>>,
+    successors: Vec<Vec<u32>>,
+}
+
+impl Condensation {
+    /// Number of components
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// The component containing a module
+    pub fn component_of(&self, id: ModuleId) -> usize {
+        self.component[id.index()] as usize
+    }
+
+    /// Modules in a component; more than one means they form an import cycle
+    pub fn members(&self, component: usize) -> &[ModuleId] {
+        &self.members[component]
+    }
+
+    /// Components directly imported by a component, each listed once
+    pub fn successors(&self, component: usize) -> impl Iterator<Item = usize> + '_ {
+        self.successors[component].iter().map(|&c| c as usize)
+    }
+}
+
+impl ModuleGraph {
+    /// The strongly connected components over runtime imports
+    pub fn condensation(&self) -> Condensation {
+        self.condensation_with(|edge| edge.kind.is_runtime())
+    }
+
+    /// Computes the strongly connected components over the edges accepted by
+    /// `include`, with an iterative Tarjan's algorithm so deep import chains
+    /// can't overflow the stack
+    pub fn condensation_with(&self, include: impl Fn(&Edge) -> bool) -> Condensation {
+        let n = self.len();
+        let mut t = Tarjan {
+            index: vec![UNVISITED; n],
+            lowlink: vec![0; n],
+            on_stack: vec![false; n],
+            stack: Vec::new(),
+            call_stack: Vec::new(),
+            next_index: 0,
+        };
+        let mut component = vec![UNVISITED; n];
+        let mut members: Vec<Vec<ModuleId>> = Vec::new();
+
+        for start in self.modules() {
+            if t.index[start.index()] != UNVISITED {
+                continue;
+            }
+            t.visit(start.index());
+
+            while let Some((v, next_edge)) = t.call_stack.last_mut() {
+                let v = *v as usize;
+                if let Some(edge) = self.imports(ModuleId::from_index(v)).get(*next_edge) {
+                    *next_edge += 1;
+                    if !include(edge) {
+                        continue;
+                    }
+                    let w = edge.to.index();
+                    if t.index[w] == UNVISITED {
+                        t.visit(w);
+                    } else if t.on_stack[w] {
+                        t.lowlink[v] = t.lowlink[v].min(t.index[w]);
+                    }
+                    continue;
+                }
+
+                t.call_stack.pop();
+                if let Some(&(parent, _)) = t.call_stack.last() {
+                    let parent = parent as usize;
+                    t.lowlink[parent] = t.lowlink[parent].min(t.lowlink[v]);
+                }
+
+                if t.lowlink[v] == t.index[v] {
+                    let id = members.len() as u32;
+                    let mut scc = Vec::new();
+                    while let Some(w) = t.stack.pop() {
+                        t.on_stack[w as usize] = false;
+                        component[w as usize] = id;
+                        scc.push(ModuleId::from_index(w as usize));
+                        if w as usize == v {
+                            break;
+                        }
+                    }
+                    scc.sort();
+                    members.push(scc);
+                }
+            }
+        }
+
+        let successors = members
+            .iter()
+            .enumerate()
+            .map(|(c, scc)| {
+                let mut succ: Vec<u32> = scc
+                    .iter()
+                    .flat_map(|&m| self.imports(m))
+                    .filter(|edge| include(edge))
+                    .map(|edge| component[edge.to.index()])
+                    .filter(|&s| s as usize != c)
+                    .collect();
+                succ.sort_unstable();
+                succ.dedup();
+                succ
+            })
+            .collect();
+
+        debug!("Condensed {} modules into {} components", n, members.len());
+        Condensation { component, members, successors }
+    }
+
+    /// Number of modules reachable from each of `targets` (including the target
+    /// itself), without materializing a set per module.
+    ///
+    /// Reachable sets are computed once per component as bitsets over components,
+    /// in dependency order, and each bitset is dropped as soon as every importer
+    /// has merged it in, so memory stays proportional to the widest layer of the
+    /// graph rather than to its size squared.
+    pub fn reachable_counts(&self, targets: &[ModuleId]) -> HashMap<ModuleId, usize> {
+        self.reachable_weights(targets, |_| 1)
+            .into_iter()
+            .map(|(t, count)| (t, count as usize))
+            .collect()
+    }
+
+    /// Total weight of the modules reachable from each of `targets` (including
+    /// the target itself), such as their size in bytes, computed like
+    /// [`Self::reachable_counts`]
+    pub fn reachable_weights(
+        &self,
+        targets: &[ModuleId],
+        weight: impl Fn(ModuleId) -> u64,
+    ) -> HashMap<ModuleId, u64> {
+        let dag = self.condensation();
+        let successors: Vec<Vec<usize>> =
+            (0..dag.len()).map(|c| dag.successors(c).collect()).collect();
+        let weights: Vec<u64> =
+            (0..dag.len()).map(|c| dag.members(c).iter().map(|&m| weight(m)).sum()).collect();
+        let sums = closure_sums(&dag, targets, 0..dag.len(), &successors, &weights);
+        targets.iter().map(|&t| (t, sums[dag.component_of(t)])).collect()
+    }
+
+    /// Number of modules that reach each of `targets` through runtime imports
+    /// (including the target itself): the reverse of [`Self::reachable_counts`],
+    /// computed the same way from importers down to dependencies
+    pub fn dependent_counts(&self, targets: &[ModuleId]) -> HashMap<ModuleId, usize> {
+        let dag = self.condensation();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); dag.len()];
+        for c in 0..dag.len() {
+            for s in dag.successors(c) {
+                predecessors[s].push(c);
+            }
+        }
+        let sizes: Vec<u64> = (0..dag.len()).map(|c| dag.members(c).len() as u64).collect();
+        let counts = closure_sums(&dag, targets, (0..dag.len()).rev(), &predecessors, &sizes);
+        targets.iter().map(|&t| (t, counts[dag.component_of(t)] as usize)).collect()
+    }
+}
+
+impl Condensation {
+    /// The longest chain of components below each component, indexed by
+    /// component: 0 for components that import nothing
+    pub fn depths(&self) -> Vec<usize> {
+        let mut depths = vec![0; self.len()];
+        for c in 0..self.len() {
+            depths[c] = self.successors(c).map(|s| depths[s] + 1).max().unwrap_or(0);
+        }
+        depths
+    }
+}
+
+/// Total weight of the components in the transitive closure of each component
+/// over `neighbors`, for the components of `targets`. `order` must visit every
+/// component after all of its neighbors.
+///
+/// Only the closure of the targets is visited, and bitsets span just the
+/// components in it, so checking a few targets of a large graph stays cheap.
+fn closure_sums(
+    dag: &Condensation,
+    targets: &[ModuleId],
+    order: impl Iterator<Item = usize>,
+    neighbors: &[Vec<usize>],
+    weights: &[u64],
+) -> Vec<u64> {
+    let nc = dag.len();
+
+    let mut needed = vec![false; nc];
+    for &t in targets {
+        needed[dag.component_of(t)] = true;
+    }
+
+    // Components in the closure of a target, numbered densely for the bitsets
+    const OUTSIDE: usize = usize::MAX;
+    let mut slot = vec![OUTSIDE; nc];
+    let mut components = Vec::new();
+    let mut stack: Vec<usize> = (0..nc).filter(|&c| needed[c]).collect();
+    while let Some(c) = stack.pop() {
+        if slot[c] != OUTSIDE {
+            continue;
+        }
+        slot[c] = components.len();
+        components.push(c);
+        stack.extend(neighbors[c].iter().filter(|&&s| slot[s] == OUTSIDE));
+    }
+    let words = components.len().div_ceil(64);
+
+    let mut pending = vec![0u32; nc];
+    for &c in &components {
+        for &s in &neighbors[c] {
+            pending[s] += 1;
+        }
+    }
+
+    let mut sets: Vec<Vec<u64>> = vec![Vec::new(); nc];
+    let mut sums: Vec<u64> = vec![0; nc];
+    let mut live = 0usize;
+    let mut peak = 0usize;
+
+    for c in order.filter(|&c| slot[c] != OUTSIDE) {
+        let mut bits = vec![0u64; words];
+        bits[slot[c] / 64] |= 1 << (slot[c] % 64);
+        for &s in &neighbors[c] {
+            for (word, other) in bits.iter_mut().zip(&sets[s]) {
+                *word |= other;
+            }
+            pending[s] -= 1;
+            if pending[s] == 0 {
+                sets[s] = Vec::new();
+                live -= 1;
+            }
+        }
+
+        if needed[c] {
+            sums[c] = bits
+                .iter()
+                .enumerate()
+                .flat_map(|(w, &word)| BitIter { word, base: w * 64 })
+                .map(|r| weights[components[r]])
+                .sum();
+        }
+        if pending[c] > 0 {
+            sets[c] = bits;
+            live += 1;
+            peak = peak.max(live);
+        }
+    }
+
+    debug!(
+        "Computed closure sums over {} of {} components with at most {} live bitsets of {} words",
+        components.len(),
+        nc,
+        peak,
+        words
+    );
+    sums
+}
+
+/// State of an in-progress Tarjan's SCC search
+struct Tarjan {
+    index: Vec<u32>,
+    lowlink: Vec<u32>,
+    on_stack: Vec<bool>,
+    stack: Vec<u32>,
+    /// Explicit DFS stack of (module, position of the next edge to explore)
+    call_stack: Vec<(u32, usize)>,
+    next_index: u32,
+}
+
+impl Tarjan {
+    fn visit(&mut self, v: usize) {
+        self.index[v] = self.next_index;
+        self.lowlink[v] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(v as u32);
+        self.on_stack[v] = true;
+        self.call_stack.push((v as u32, 0));
+    }
+}
+
+/// Iterates the indices of the set bits of a word
+struct BitIter {
+    word: u64,
+    base: usize,
+}
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.word == 0 {
+            return None;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.base + bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condensation_orders_dependencies_first() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3
+        let graph = ModuleGraph::from_edges(4, &[(0, 1), (1, 2), (2, 1), (2, 3)]);
+        let dag = graph.condensation();
+        assert_eq!(dag.len(), 3);
+
+        let id = ModuleId::from_index;
+        let cycle = dag.component_of(id(1));
+        assert_eq!(dag.component_of(id(2)), cycle);
+        assert_eq!(dag.members(cycle), &[id(1), id(2)]);
+
+        for c in 0..dag.len() {
+            for s in dag.successors(c) {
+                assert!(s < c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reachable_counts_match_traversal() {
+        // A pseudo-random graph with cycles, shared dependencies and isolated modules
+        let n = 200;
+        let mut edges = Vec::new();
+        let mut seed: u64 = 42;
+        for from in 0..n {
+            for _ in 0..3 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let to = (seed >> 33) as usize % n;
+                if !(seed >> 20).is_multiple_of(4) {
+                    edges.push((from, to));
+                }
+            }
+        }
+        let graph = ModuleGraph::from_edges(n, &edges);
+
+        let targets: Vec<_> = graph.modules().collect();
+        let counts = graph.reachable_counts(&targets);
+        let dependents = graph.dependent_counts(&targets);
+        let reachable: Vec<_> = targets.iter().map(|&id| graph.reachable(id)).collect();
+        for &id in &targets {
+            assert_eq!(counts[&id], reachable[id.index()].len(), "module {}", id);
+            let reaching = reachable.iter().filter(|r| r.contains(&id)).count();
+            assert_eq!(dependents[&id], reaching, "module {}", id);
+        }
+
+        // A few targets only visit their own closure, with the same result
+        let some: Vec<_> = targets.iter().copied().step_by(37).collect();
+        let some_counts = graph.reachable_counts(&some);
+        assert_eq!(some_counts.len(), some.len());
+        for id in some {
+            assert_eq!(some_counts[&id], counts[&id], "module {}", id);
+        }
+    }
+
+    #[test]
+    fn test_dependent_counts_and_depths() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3, 4 -> 3
+        let graph = ModuleGraph::from_edges(5, &[(0, 1), (1, 2), (2, 1), (2, 3), (4, 3)]);
+        let id = ModuleId::from_index;
+        let targets: Vec<_> = graph.modules().collect();
+        let counts = graph.dependent_counts(&targets);
+        let dependents: Vec<usize> = targets.iter().map(|t| counts[t]).collect();
+        assert_eq!(dependents, vec![1, 3, 3, 5, 1]);
+
+        let dag = graph.condensation();
+        let depths = dag.depths();
+        let depth_of = |i| depths[dag.component_of(id(i))];
+        assert_eq!((0..5).map(depth_of).collect::<Vec<_>>(), vec![2, 1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_reachable_weights() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3, with module i weighing 10^i
+        let graph = ModuleGraph::from_edges(4, &[(0, 1), (1, 2), (2, 1), (2, 3)]);
+        let id = ModuleId::from_index;
+        let targets: Vec<_> = graph.modules().collect();
+        let weights = graph.reachable_weights(&targets, |m| 10u64.pow(m.index() as u32));
+        assert_eq!(weights[&id(0)], 1111);
+        assert_eq!(weights[&id(1)], 1110);
+        assert_eq!(weights[&id(2)], 1110);
+        assert_eq!(weights[&id(3)], 1000);
+    }
+
+    #[test]
+    fn test_reachable_counts_deep_chain() {
+        // Deep enough to overflow a recursive implementation
+        let n = 50_000;
+        let edges: Vec<_> = (0..n - 1).map(|i| (i, i + 1)).collect();
+        let graph = ModuleGraph::from_edges(n, &edges);
+
+        let first = ModuleId::from_index(0);
+        let counts = graph.reachable_counts(&[first]);
+        assert_eq!(counts[&first], n);
+    }
+}
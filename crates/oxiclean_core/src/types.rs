@@ -1,12 +1,71 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Specifier {
     pub request: String,
-    #[allow(dead_code)]
     pub kind: SpecKind,
+    /// 1-based line of the import in the source file
+    pub line: u32,
+    /// The exports of the target module this import uses
+    pub names: ImportedNames,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SpecKind {
     Static,
     Dynamic,
+    /// `export { a } from './a'` and `export * from './a'`: loaded at runtime,
+    /// but not an import of the re-exporting module's own
+    ReExport,
+    /// `import type { Foo } from 'bar'`, or an import whose specifiers are all
+    /// `type`: erased at compile time, so it has no runtime cost
+    Type,
+}
+
+impl SpecKind {
+    /// Whether the import is still present at runtime
+    pub fn is_runtime(self) -> bool {
+        self != SpecKind::Type
+    }
+}
+
+/// Which exports of the target module an import refers to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportedNames {
+    /// `import foo, { bar } from './x'` uses `default` and `bar`
+    Named(Vec<String>),
+    /// Namespace imports, `require()` and `import()`, which may use any export
+    All,
+    /// Side-effect imports and re-exports, which use no export directly
+    Nothing,
+}
+
+/// A name exported by a module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Export {
+    /// The exported name, `default` for default exports
+    pub name: String,
+    /// 1-based line of the export in the source file
+    pub line: u32,
+    /// `export type`, interfaces and type aliases
+    pub is_type: bool,
+    /// Set when the export is re-exported from another module
+    pub from: Option<ReExport>,
+}
+
+/// The origin of a re-export, as in `export { name } from 'request'`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReExport {
+    pub request: String,
+    /// The name in the source module, `*` for `export * as ns from 'request'`
+    pub name: String,
+}
+
+/// Everything the parser extracts from a source file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParsedModule {
+    pub imports: Vec<Specifier>,
+    pub exports: Vec<Export>,
+    /// Requests of `export * from 'request'` statements
+    pub star_exports: Vec<String>,
 }
@@ -0,0 +1,348 @@
+use anyhow::{Context, Result, anyhow};
+use log::{debug, info, trace, warn};
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher,
+    event::{CreateKind, RemoveKind},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::CommandConfig,
+    config_file::CONFIG_FILE_NAMES,
+    constants::{CACHE_DIR_NAME, IGNORE_FILE_NAME, JS_TS_EXTENSIONS, LOCKFILE_NAMES},
+    graph::{ModuleGraph, ModuleId},
+};
+
+/// How long to wait for more events after the first one before re-running,
+/// so a save that touches several files triggers a single run
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// What changed in a batch of filesystem events
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Changes {
+    /// JS/TS files whose content may have changed
+    pub files: Vec<PathBuf>,
+    /// Files or directories were created, removed or renamed, so resolutions may change
+    pub structural: bool,
+    /// A package.json or lockfile changed
+    pub manifests: bool,
+    /// A tsconfig/jsconfig or oxiclean config file changed
+    pub config: bool,
+    /// Ignore files changed, so the set of entries may change
+    pub ignores: bool,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.files.is_empty()
+            && !self.structural
+            && !self.manifests
+            && !self.config
+            && !self.ignores
+    }
+}
+
+/// One build of the module graph in watch mode
+pub struct Rebuild<'a> {
+    /// The graph, or why it could not be built, e.g. because no entries are left
+    pub graph: Result<&'a ModuleGraph>,
+    /// The entries whose reachable modules may differ from those in the
+    /// previous graph passed to `on_graph`, see [`ModuleGraph::changed_entries`].
+    /// None when every entry must be checked: on the first build and after the
+    /// settings were reloaded.
+    pub changed_entries: Option<Vec<ModuleId>>,
+    /// When the build started
+    pub started: Instant,
+}
+
+/// Builds the module graph for the initialized settings `cfg`, hands it to
+/// `on_graph` together with the settings, and rebuilds it whenever a relevant
+/// file under the root changes, until the process is interrupted.
+///
+/// Each rebuild lists the entries whose reachable modules may have changed, so
+/// per-entry results for the other entries can be kept. Only changed files are
+/// re-parsed; resolutions are dropped when files or
+/// directories are added, removed or moved, or a package manifest changes.
+/// When the config file or a tsconfig/jsconfig changes, the settings are
+/// initialized again from `cli`, the uninitialized settings given on the
+/// command line, so new aliases, options and check sections take effect. A
+/// graph that fails to build (e.g. because no entries are left) or settings
+/// that fail to load are passed to `on_graph` as an error and watching
+/// continues.
+pub fn watch_project<C, F>(cli: &C, mut cfg: C, mut on_graph: F) -> Result<()>
+where
+    C: CommandConfig,
+    F: FnMut(&C, Rebuild<'_>) -> Result<()>,
+{
+    let mut root = cfg.shared().root().ok_or_else(|| anyhow!("Config not initialized"))?.clone();
+    let mut cache = cfg.shared().analysis_cache()?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+    info!("Watching {} for changes", root.display());
+
+    // The last graph handed to `on_graph`, and the files edited since
+    let mut previous: Option<ModuleGraph> = None;
+    let mut edited: Vec<PathBuf> = Vec::new();
+    loop {
+        let started = Instant::now();
+        match ModuleGraph::from_config_with_cache(cfg.shared(), &cache) {
+            Ok(graph) => {
+                let changed_entries =
+                    previous.as_ref().map(|previous| graph.changed_entries(previous, &edited));
+                if let Some(changed) = &changed_entries {
+                    debug!("{} of {} entries changed", changed.len(), graph.entries().len());
+                }
+                on_graph(&cfg, Rebuild { graph: Ok(&graph), changed_entries, started })?;
+                previous = Some(graph);
+                edited.clear();
+            }
+            Err(e) => {
+                on_graph(&cfg, Rebuild { graph: Err(e), changed_entries: None, started })?;
+            }
+        }
+        if let Err(e) = cache.save() {
+            warn!("Failed to save cache: {:#}", e);
+        }
+
+        loop {
+            let changes = classify(&next_events(&rx)?, cfg.shared().cache_dir.as_deref());
+            if changes.is_empty() {
+                trace!("Ignoring irrelevant filesystem events");
+                continue;
+            }
+            debug!("Detected changes: {:?}", changes);
+
+            if changes.config {
+                info!("Configuration changed, reloading settings");
+                let started = Instant::now();
+                let mut reloaded = cli.clone();
+                if let Err(e) = reloaded.initialize() {
+                    let graph = Err(e.context("Failed to reload settings"));
+                    on_graph(&cfg, Rebuild { graph, changed_entries: None, started })?;
+                    continue;
+                }
+                let new_root = reloaded.shared().root().cloned().unwrap_or_else(|| root.clone());
+                if new_root != root {
+                    watcher.unwatch(&root).ok();
+                    watcher
+                        .watch(&new_root, RecursiveMode::Recursive)
+                        .with_context(|| format!("Failed to watch {}", new_root.display()))?;
+                    info!("Watching {} for changes", new_root.display());
+                    root = new_root;
+                }
+                // Aliases and conditions are part of the cache's resolution fingerprint
+                cache = reloaded.shared().analysis_cache()?;
+                cfg = reloaded;
+                // Any result may depend on the settings, so check every entry again
+                previous = None;
+            }
+            cache.invalidate_files(&changes.files);
+            edited.extend(changes.files);
+            if changes.structural || changes.manifests {
+                cache.invalidate_resolutions();
+            }
+            break;
+        }
+    }
+}
+
+/// Combines the per-entry results of a check run over the `changed_entries` of
+/// `graph` only (`rechecked`) with the results of the previous run for the
+/// other entries, in the order of the graph's entries as a full run would
+/// give them. `from_file` gives the root-relative path of the entry an item
+/// belongs to; results of entries no longer in the graph are dropped.
+pub fn merge_entry_results<T>(
+    graph: &ModuleGraph,
+    changed_entries: &[ModuleId],
+    previous: Vec<T>,
+    rechecked: Vec<T>,
+    from_file: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let changed: HashSet<String> = changed_entries.iter().map(|&e| graph.rel_path(e)).collect();
+    let mut by_entry: HashMap<String, Vec<T>> = HashMap::new();
+    let kept = previous.into_iter().filter(|item| !changed.contains(from_file(item)));
+    for item in kept.chain(rechecked) {
+        by_entry.entry(from_file(&item).to_string()).or_default().push(item);
+    }
+    graph
+        .entries()
+        .iter()
+        .flat_map(|&entry| by_entry.remove(&graph.rel_path(entry)).unwrap_or_default())
+        .collect()
+}
+
+/// Blocks until at least one event arrives, then collects the rest of the burst
+fn next_events(rx: &Receiver<notify::Result<Event>>) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    let first = rx.recv().context("File watcher stopped")?;
+    events.extend(first.map_err(|e| warn!("File watcher error: {}", e)).ok());
+    while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+        events.extend(event.map_err(|e| warn!("File watcher error: {}", e)).ok());
+    }
+    Ok(events)
+}
+
+pub(crate) fn classify(events: &[Event], cache_dir: Option<&Path>) -> Changes {
+    let mut changes = Changes::default();
+
+    for event in events {
+        let structural = match event.kind {
+            EventKind::Create(_) | EventKind::Remove(_) => true,
+            EventKind::Modify(notify::event::ModifyKind::Name(_)) => true,
+            EventKind::Modify(_) | EventKind::Any => false,
+            EventKind::Access(_) | EventKind::Other => continue,
+        };
+
+        for path in &event.paths {
+            if is_ignored_path(path, cache_dir) {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            if name == "package.json" || LOCKFILE_NAMES.contains(&name) {
+                changes.manifests = true;
+            } else if CONFIG_FILE_NAMES.contains(&name)
+                || name == "jsconfig.json"
+                || (name.starts_with("tsconfig") && name.ends_with(".json"))
+            {
+                changes.config = true;
+            } else if name == IGNORE_FILE_NAME || name == ".gitignore" {
+                changes.ignores = true;
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| JS_TS_EXTENSIONS.contains(&ext))
+            {
+                if !changes.files.contains(path) {
+                    changes.files.push(path.clone());
+                }
+                changes.structural |= structural;
+            } else if structural && is_directory_event(event, path) {
+                // Moving a directory moves every file in it without an event per file
+                changes.structural = true;
+            }
+        }
+    }
+
+    changes
+}
+
+/// Whether a create, remove or rename event is about a directory. Removed and
+/// renamed-away paths can't be inspected, so on platforms that don't report
+/// the kind, a path without an extension is taken to be a directory.
+fn is_directory_event(event: &Event, path: &Path) -> bool {
+    match event.kind {
+        EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder) => true,
+        EventKind::Create(CreateKind::File) | EventKind::Remove(RemoveKind::File) => false,
+        _ => path.is_dir() || (!path.exists() && path.extension().is_none()),
+    }
+}
+
+fn is_ignored_path(path: &Path, cache_dir: Option<&Path>) -> bool {
+    if cache_dir.is_some_and(|dir| path.starts_with(dir)) {
+        return true;
+    }
+    path.components().any(|c| match c {
+        Component::Normal(name) => {
+            name == "node_modules" || name == ".git" || name == CACHE_DIR_NAME
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{DataChange, ModifyKind, RenameMode};
+    use tempfile::TempDir;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_classify_content_change() {
+        let events = vec![
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Content)), "/p/src/a.ts"),
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Content)), "/p/src/a.ts"),
+            event(EventKind::Access(notify::event::AccessKind::Any), "/p/src/b.ts"),
+        ];
+        let changes = classify(&events, None);
+        assert_eq!(changes.files, vec![PathBuf::from("/p/src/a.ts")]);
+        assert!(!changes.structural);
+    }
+
+    #[test]
+    fn test_classify_structural_and_manifest_changes() {
+        let events = vec![
+            event(EventKind::Create(CreateKind::File), "/p/src/new.tsx"),
+            event(EventKind::Modify(ModifyKind::Any), "/p/package.json"),
+            event(EventKind::Remove(RemoveKind::File), "/p/tsconfig.base.json"),
+        ];
+        let changes = classify(&events, None);
+        assert_eq!(changes.files, vec![PathBuf::from("/p/src/new.tsx")]);
+        assert!(changes.structural);
+        assert!(changes.manifests);
+        assert!(changes.config);
+    }
+
+    #[test]
+    fn test_classify_directory_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let moved = temp_dir.path().join("b");
+        std::fs::create_dir(&moved).unwrap();
+
+        // `mv src/a src/b`: only the directory itself is reported
+        let events = vec![
+            event(EventKind::Modify(ModifyKind::Name(RenameMode::From)), "/p/src/a"),
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::To))).add_path(moved),
+        ];
+        let changes = classify(&events, None);
+        assert!(changes.files.is_empty());
+        assert!(changes.structural);
+        assert!(!changes.is_empty());
+
+        let removed = vec![event(EventKind::Remove(RemoveKind::Folder), "/p/src/old.d")];
+        assert!(classify(&removed, None).structural);
+
+        // Other files without a JS/TS extension are still irrelevant
+        let created = vec![event(EventKind::Create(CreateKind::File), "/p/src/notes.md")];
+        assert!(classify(&created, None).is_empty());
+    }
+
+    #[test]
+    fn test_merge_entry_results() {
+        // Entries /m0.js, /m1.js and /m2.js; /m3.js was an entry before
+        let graph = ModuleGraph::from_edges(3, &[]);
+        let changed = [ModuleId::from_index(1)];
+        let previous = vec![("/m0.js", "old"), ("/m1.js", "old"), ("/m3.js", "old")];
+        let rechecked = vec![("/m1.js", "new"), ("/m1.js", "new too")];
+
+        let merged = merge_entry_results(&graph, &changed, previous, rechecked, |item| item.0);
+        assert_eq!(merged, vec![("/m0.js", "old"), ("/m1.js", "new"), ("/m1.js", "new too")]);
+    }
+
+    #[test]
+    fn test_classify_ignores_irrelevant_paths() {
+        let events = vec![
+            event(EventKind::Create(CreateKind::File), "/p/node_modules/react/index.js"),
+            event(EventKind::Modify(ModifyKind::Any), "/p/.git/index"),
+            event(EventKind::Modify(ModifyKind::Any), "/p/.oxiclean-cache/cache.json"),
+            event(EventKind::Modify(ModifyKind::Any), "/p/build/out.js"),
+            event(EventKind::Modify(ModifyKind::Any), "/p/README.md"),
+        ];
+        let changes = classify(&events, Some(Path::new("/p/build")));
+        assert!(changes.is_empty());
+    }
+}
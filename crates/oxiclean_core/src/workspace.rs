@@ -0,0 +1,131 @@
+use ignore::WalkBuilder;
+use log::{debug, trace};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A package.json found in the project (outside `node_modules`).
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+    /// The `name` field, if present
+    pub name: Option<String>,
+    /// Directory containing the package.json
+    pub dir: PathBuf,
+    /// Parsed package.json contents
+    pub manifest: serde_json::Value,
+}
+
+impl WorkspacePackage {
+    /// Returns true if the package lists `dep` in any of its dependency fields
+    pub fn depends_on(&self, dep: &str) -> bool {
+        ["dependencies", "devDependencies", "peerDependencies", "optionalDependencies"]
+            .iter()
+            .any(|field| self.declares_in(field, dep))
+    }
+
+    /// Returns true if the dependency field `field` (e.g. `devDependencies`) lists `dep`
+    pub fn declares_in(&self, field: &str, dep: &str) -> bool {
+        self.manifest.get(field).and_then(|d| d.get(dep)).is_some()
+    }
+
+    /// The package names listed in the dependency field `field`, sorted
+    pub fn dependency_names(&self, field: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .manifest
+            .get(field)
+            .and_then(|d| d.as_object())
+            .map(|deps| deps.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        names.sort_unstable();
+        names
+    }
+}
+
+/// Finds every package.json under the root, which covers both single-package
+/// projects and monorepos regardless of the workspace tool in use.
+///
+/// Respects `.gitignore` and never descends into `node_modules`.
+pub fn find_workspace_packages(root: &Path) -> Vec<WorkspacePackage> {
+    debug!("Searching for workspace packages under {:?}", root);
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .filter_entry(|e| e.file_name() != "node_modules")
+        .build();
+
+    let mut packages = Vec::new();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.file_name().and_then(|n| n.to_str()) != Some("package.json") {
+            continue;
+        }
+        let Some(manifest) = fs::read_to_string(path)
+            .ok()
+            .and_then(|txt| serde_json::from_str::<serde_json::Value>(&txt).ok())
+        else {
+            trace!("Skipping unreadable package.json at {:?}", path);
+            continue;
+        };
+        let dir = path.parent().unwrap_or(root).to_path_buf();
+        let name = manifest.get("name").and_then(|n| n.as_str()).map(String::from);
+        trace!("Found workspace package {:?} at {:?}", name, dir);
+        packages.push(WorkspacePackage { name, dir, manifest });
+    }
+
+    packages.sort_by(|a, b| a.dir.cmp(&b.dir));
+    debug!("Found {} workspace packages", packages.len());
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    #[test]
+    fn test_find_workspace_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "package.json", r#"{"name": "root", "private": true}"#);
+        create_test_file(root, "packages/ui/package.json", r#"{"name": "@acme/ui"}"#);
+        create_test_file(root, "packages/broken/package.json", "not json");
+        create_test_file(root, "node_modules/react/package.json", r#"{"name": "react"}"#);
+
+        let packages = find_workspace_packages(root);
+        let names: Vec<_> = packages.iter().filter_map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, vec!["root", "@acme/ui"]);
+    }
+
+    #[test]
+    fn test_depends_on() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(
+            root,
+            "package.json",
+            r#"{"dependencies": {"react": "^18"}, "devDependencies": {"next": "14"}}"#,
+        );
+
+        let packages = find_workspace_packages(root);
+        assert_eq!(packages.len(), 1);
+        assert!(packages[0].depends_on("react"));
+        assert!(packages[0].depends_on("next"));
+        assert!(!packages[0].depends_on("vite"));
+        assert!(packages[0].declares_in("devDependencies", "next"));
+        assert!(!packages[0].declares_in("dependencies", "next"));
+        assert_eq!(packages[0].dependency_names("dependencies"), vec!["react"]);
+        assert!(packages[0].dependency_names("peerDependencies").is_empty());
+    }
+}
@@ -0,0 +1,455 @@
+use anyhow::Result;
+use log::{debug, info, trace};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use oxiclean_core::{ModuleGraph, find_workspace_packages};
+
+use crate::{
+    config::Config,
+    types::{CheckResult, ImportSite, IssueKind, Warning},
+};
+
+/// Node.js core modules, which are importable without being installed
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Fields that declare a dependency needed at runtime
+const RUNTIME_FIELDS: &[&str] = &["dependencies", "peerDependencies", "optionalDependencies"];
+
+pub fn run_dependencies_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting dependencies check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
+
+/// Runs the dependencies check as a pass over an already-built module graph.
+///
+/// Every project file belongs to the workspace package with the nearest
+/// package.json. Its imports of packages, whether installed in `node_modules` or
+/// other workspace packages, are checked against that package.json, and the
+/// `dependencies` no file of the package imports are reported as unused.
+/// Imports that don't resolve are checked by their specifier, so results
+/// don't depend on `node_modules` being installed. Resolution only tells
+/// whether an undeclared package is installed anyway (`phantom`). A
+/// package with .jsx/.tsx files uses the JSX import sources, whose runtime the
+/// compiler imports.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    let root = graph.root();
+    let packages = find_workspace_packages(root);
+    let package_dirs: Vec<PathBuf> =
+        packages.iter().map(|p| p.dir.strip_prefix(root).unwrap_or(&p.dir).to_path_buf()).collect();
+    let workspace_names: HashSet<&str> =
+        packages.iter().filter_map(|p| p.name.as_deref()).collect();
+
+    // Per package: the packages its files import, and the problematic import sites
+    let mut used: Vec<HashSet<String>> = vec![HashSet::new(); packages.len()];
+    let mut owns_files = vec![false; packages.len()];
+    let mut has_jsx = vec![false; packages.len()];
+    let mut issues: BTreeMap<(usize, IssueKind, String), (bool, Vec<ImportSite>)> = BTreeMap::new();
+
+    for from in graph.modules() {
+        if graph.is_installed(from) {
+            continue;
+        }
+        let from_file = graph.rel_path(from);
+        let Some(owner) = owner_of(&package_dirs, Path::new(&from_file)) else {
+            trace!("{} is outside every workspace package", from_file);
+            continue;
+        };
+        owns_files[owner] = true;
+        has_jsx[owner] |=
+            matches!(graph.path(from).extension().and_then(|e| e.to_str()), Some("jsx" | "tsx"));
+        let package = &packages[owner];
+        let is_dev_file = cfg.is_dev_file(Path::new(&from_file));
+
+        // Unresolved imports count too: the package may just not be installed
+        let resolved = graph.imports(from).iter().map(|e| (&e.request, e.kind, e.line, Some(e.to)));
+        let unresolved =
+            graph.unresolved_imports(from).iter().map(|s| (&s.request, s.kind, s.line, None));
+        for (request, spec_kind, line, to) in resolved.chain(unresolved) {
+            let Some(name) = package_name(request) else {
+                continue;
+            };
+            let installed = to.is_some_and(|to| graph.is_installed(to));
+            // Bare specifiers resolving to project files are path aliases
+            let aliased = match to {
+                Some(_) => !installed && !workspace_names.contains(name),
+                None => cfg.shared.resolve.is_aliased(request),
+            };
+            if aliased {
+                continue;
+            }
+            if package.name.as_deref() == Some(name) || cfg.is_ignored(name) {
+                continue;
+            }
+            used[owner].insert(name.to_string());
+
+            // Type-only imports are satisfied by the package's `@types/*` package
+            let declared = package.depends_on(name)
+                || (!spec_kind.is_runtime() && package.depends_on(&types_package(name)));
+            let kind = if !declared {
+                IssueKind::Undeclared
+            } else if spec_kind.is_runtime()
+                && !is_dev_file
+                && !RUNTIME_FIELDS.iter().any(|field| package.declares_in(field, name))
+            {
+                IssueKind::DevInRuntime
+            } else {
+                continue;
+            };
+            trace!("{}:{} imports {} ({:?})", from_file, line, name, kind);
+            let (phantom, sites) =
+                issues.entry((owner, kind, name.to_string())).or_insert((false, Vec::new()));
+            *phantom |= kind == IssueKind::Undeclared && installed;
+            sites.push(ImportSite { file: from_file.clone(), line, request: request.clone() });
+        }
+    }
+
+    for (owner, _) in has_jsx.iter().enumerate().filter(|(_, jsx)| **jsx) {
+        used[owner].extend(cfg.jsx_import_sources.iter().cloned());
+    }
+
+    // Packages without analyzed files (e.g. a monorepo root holding only tooling)
+    // can't be judged for unused dependencies
+    for (owner, package) in packages.iter().enumerate().filter(|(i, _)| owns_files[*i]) {
+        for name in package.dependency_names("dependencies") {
+            if used[owner].contains(name) || name.starts_with("@types/") || cfg.is_ignored(name) {
+                continue;
+            }
+            trace!("{:?} never imports its dependency {}", package.dir, name);
+            issues.insert((owner, IssueKind::Unused, name.to_string()), (false, Vec::new()));
+        }
+    }
+
+    let warnings: Vec<Warning> = issues
+        .into_iter()
+        .map(|((owner, kind, dependency), (phantom, mut sites))| {
+            sites.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+            Warning {
+                package: display_dir(&package_dirs[owner]),
+                package_name: packages[owner].name.clone(),
+                kind,
+                dependency,
+                phantom,
+                sites,
+            }
+        })
+        .collect();
+    let packages_checked = owns_files.iter().filter(|&&owns| owns).count();
+    debug!("Checked {} of {} workspace packages", packages_checked, packages.len());
+
+    info!("Dependencies check complete. Found {} issues", warnings.len());
+    CheckResult { warnings, packages_checked, files_analyzed: graph.len() }
+}
+
+/// The package a bare specifier imports: `@scope/name` or `name`, without any
+/// subpath. `None` for relative and absolute paths, `#` subpath imports, URLs
+/// and Node.js builtins
+fn package_name(request: &str) -> Option<&str> {
+    if request.starts_with(['.', '/', '#']) || request.contains(':') {
+        return None;
+    }
+    let mut segments = request.splitn(3, '/');
+    let first = segments.next()?;
+    let end = if first.starts_with('@') {
+        if first.len() == 1 {
+            return None;
+        }
+        first.len() + 1 + segments.next().filter(|s| !s.is_empty())?.len()
+    } else {
+        first.len()
+    };
+    let name = &request[..end];
+    (!name.is_empty() && !NODE_BUILTINS.contains(&name)).then_some(name)
+}
+
+/// The DefinitelyTyped package providing the types of a package:
+/// `@types/name`, or `@types/scope__name` for `@scope/name`
+fn types_package(name: &str) -> String {
+    format!("@types/{}", name.trim_start_matches('@').replacen('/', "__", 1))
+}
+
+/// Index of the package whose directory is the nearest ancestor of `rel_path`
+fn owner_of(package_dirs: &[PathBuf], rel_path: &Path) -> Option<usize> {
+    package_dirs
+        .iter()
+        .enumerate()
+        .filter(|(_, dir)| rel_path.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(i, _)| i)
+}
+
+fn display_dir(dir: &Path) -> String {
+    if dir.as_os_str().is_empty() { ".".to_string() } else { dir.to_string_lossy().to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, ignore_dependencies: &[&str]) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            dev_files: vec![],
+            ignore_dependencies: ignore_dependencies.iter().map(|d| d.to_string()).collect(),
+            jsx_import_sources: vec![],
+            dev_matcher: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_package(root: &Path, name: &str) {
+        create_test_file(
+            root,
+            &format!("node_modules/{}/package.json", name),
+            &format!(r#"{{ "name": "{}", "main": "index.js" }}"#, name),
+        );
+        create_test_file(root, &format!("node_modules/{}/index.js", name), "module.exports = 1;");
+    }
+
+    fn issues(result: &CheckResult) -> Vec<String> {
+        result
+            .warnings
+            .iter()
+            .map(|w| {
+                let sites: Vec<String> =
+                    w.sites.iter().map(|s| format!("{}:{}", s.file, s.line)).collect();
+                format!(
+                    "{} {:?} {}{} [{}]",
+                    w.package,
+                    w.kind,
+                    w.dependency,
+                    if w.phantom { " (phantom)" } else { "" },
+                    sites.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dependency_issues() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "package.json",
+            r#"{
+                "name": "app",
+                "dependencies": { "react": "^18", "moment": "^2", "@types/react": "^18" },
+                "devDependencies": { "vitest": "^1" }
+            }"#,
+        );
+        for name in ["react", "moment", "vitest", "lodash", "@scope/util"] {
+            create_package(root, name);
+        }
+        create_test_file(
+            root,
+            "src/app.ts",
+            "import React from 'react';\nimport { x } from 'lodash';\n\
+             import { vi } from 'vitest';\nimport type { Mock } from 'vitest';\n\
+             import fs from 'fs';\nimport path from 'node:path';\nimport './setup';",
+        );
+        create_test_file(root, "src/setup.config.ts", "import { defineConfig } from 'vitest';");
+        create_test_file(
+            root,
+            "packages/ui/package.json",
+            r#"{ "name": "@acme/ui", "peerDependencies": { "react": "*" } }"#,
+        );
+        create_test_file(
+            root,
+            "packages/ui/src/index.ts",
+            "import 'react';\nimport '@scope/util';",
+        );
+        // Monorepo tooling without source files is not checked for unused dependencies
+        create_test_file(
+            root,
+            "tools/package.json",
+            r#"{ "name": "tools", "dependencies": { "eslint": "^9" } }"#,
+        );
+
+        let result = run_dependencies_check(config(root, &[])).unwrap();
+        assert_eq!(result.packages_checked, 2);
+        assert_eq!(
+            issues(&result),
+            vec![
+                ". Undeclared lodash (phantom) [src/app.ts:2]",
+                ". DevInRuntime vitest [src/app.ts:3]",
+                ". Unused moment []",
+                "packages/ui Undeclared @scope/util (phantom) [packages/ui/src/index.ts:2]",
+            ]
+        );
+        assert_eq!(result.count(IssueKind::Undeclared), 2);
+
+        let result = run_dependencies_check(config(root, &["moment", "lodash"])).unwrap();
+        assert_eq!(result.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_dependency_issues_without_node_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "package.json",
+            r#"{ "name": "app", "dependencies": { "react": "^18" } }"#,
+        );
+        create_test_file(
+            root,
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "paths": { "~lib/*": ["./lib/*"] } } }"#,
+        );
+        create_test_file(
+            root,
+            "src/index.ts",
+            "import React from 'react';\nimport { x } from 'lodash';\nimport '~lib/missing';",
+        );
+
+        let result = run_dependencies_check(config(root, &[])).unwrap();
+        assert_eq!(issues(&result), vec![". Undeclared lodash [src/index.ts:2]"]);
+    }
+
+    #[test]
+    fn test_jsx_import_source_is_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "package.json",
+            r#"{ "name": "app", "dependencies": { "react": "^18", "@emotion/react": "^11" } }"#,
+        );
+        create_test_file(
+            root,
+            "packages/lib/package.json",
+            r#"{ "name": "lib", "dependencies": { "react": "^18" } }"#,
+        );
+        for name in ["react", "@emotion/react"] {
+            create_package(root, name);
+        }
+        // The compiler imports react/jsx-runtime, the source never names react
+        create_test_file(root, "src/app.tsx", "export const App = () => <div />;");
+        // Without .jsx/.tsx files nothing uses the JSX runtime
+        create_test_file(root, "packages/lib/src/index.ts", "export const x = 1;");
+
+        let result = run_dependencies_check(config(root, &[])).unwrap();
+        assert_eq!(
+            issues(&result),
+            vec![". Unused @emotion/react []", "packages/lib Unused react []"]
+        );
+
+        let mut cfg = config(root, &[]);
+        cfg.jsx_import_sources = vec!["@emotion/react".to_string()];
+        let result = run_dependencies_check(cfg).unwrap();
+        assert_eq!(issues(&result), vec![". Unused react []", "packages/lib Unused react []"]);
+    }
+
+    #[test]
+    fn test_type_imports_declared_through_types_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(
+            root,
+            "package.json",
+            r#"{
+                "name": "app",
+                "devDependencies": { "@types/json-schema": "1", "@types/babel__core": "7" }
+            }"#,
+        );
+        create_test_file(
+            root,
+            "src/index.ts",
+            "import type { S } from 'json-schema';\nimport type { T } from '@babel/core';\n\
+             import { validate } from 'json-schema';",
+        );
+
+        // A runtime import still needs the package itself
+        let result = run_dependencies_check(config(root, &[])).unwrap();
+        assert_eq!(issues(&result), vec![". Undeclared json-schema [src/index.ts:3]"]);
+    }
+
+    #[test]
+    fn test_types_package() {
+        assert_eq!(types_package("json-schema"), "@types/json-schema");
+        assert_eq!(types_package("@babel/core"), "@types/babel__core");
+    }
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("react"), Some("react"));
+        assert_eq!(package_name("react-dom/client"), Some("react-dom"));
+        assert_eq!(package_name("@mui/material/Button"), Some("@mui/material"));
+        assert_eq!(package_name("@mui"), None);
+        assert_eq!(package_name("@/components/Button"), None);
+        assert_eq!(package_name("#internal/utils"), None);
+        assert_eq!(package_name("./local"), None);
+        assert_eq!(package_name("node:fs"), None);
+        assert_eq!(package_name("fs/promises"), None);
+    }
+}
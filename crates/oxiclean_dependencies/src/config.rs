@@ -0,0 +1,118 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, DEFAULT_TEST_PATTERNS, GlobMatcher, SharedConfig};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Files that may import `devDependencies` besides test files: tooling config,
+/// scripts, stories and mocks
+pub const DEFAULT_DEV_FILES: &[&str] =
+    &["**/*.config.*", "**/scripts/**", "**/*.stories.*", "**/__tests__/**", "**/__mocks__/**"];
+
+/// Packages whose automatic JSX runtime (`<pkg>/jsx-runtime`) the compiler
+/// imports into .jsx/.tsx files
+pub const DEFAULT_JSX_IMPORT_SOURCES: &[&str] = &["react", "preact"];
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "dependencies")]
+#[command(
+    about = "Find undeclared, unused and dev-only package dependencies of each workspace package"
+)]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Glob pattern for files that may import devDependencies, in addition to
+    /// test files and the defaults (config files, scripts, stories). Repeatable
+    #[arg(long = "dev-file")]
+    pub dev_files: Vec<String>,
+
+    /// Package name never reported, e.g. a dependency only used from CSS. Repeatable
+    #[arg(long = "ignore-dependency")]
+    pub ignore_dependencies: Vec<String>,
+
+    /// Package providing the automatic JSX runtime (tsconfig `jsxImportSource`),
+    /// used by every package with .jsx/.tsx files although no file imports it.
+    /// Repeatable (default: react, preact)
+    #[arg(long = "jsx-import-source")]
+    pub jsx_import_sources: Vec<String>,
+
+    #[clap(skip)]
+    pub dev_matcher: Option<GlobMatcher>,
+}
+
+/// The `[dependencies]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct DependenciesSection {
+    dev_files: Vec<String>,
+    ignore_dependencies: Vec<String>,
+    jsx_import_sources: Vec<String>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.dev_matcher.is_some() {
+            return Ok(());
+        }
+        self.shared.initialize()?;
+
+        // Lists from the config file and the command line add up
+        let section: DependenciesSection = self.shared.check_section("dependencies")?;
+        self.dev_files.splice(0..0, section.dev_files);
+        self.ignore_dependencies.splice(0..0, section.ignore_dependencies);
+        self.jsx_import_sources.splice(0..0, section.jsx_import_sources);
+        if self.jsx_import_sources.is_empty() {
+            self.jsx_import_sources =
+                DEFAULT_JSX_IMPORT_SOURCES.iter().map(|s| s.to_string()).collect();
+        }
+
+        let patterns: Vec<&str> = DEFAULT_TEST_PATTERNS
+            .iter()
+            .chain(DEFAULT_DEV_FILES)
+            .copied()
+            .chain(self.dev_files.iter().map(String::as_str))
+            .collect();
+        debug!(
+            "Using {} dev file patterns and {} ignored dependencies",
+            patterns.len(),
+            self.ignore_dependencies.len()
+        );
+        self.dev_matcher = Some(GlobMatcher::new(&patterns)?);
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    /// Whether a file, relative to the root, is development-only code
+    pub fn is_dev_file(&self, rel_path: &Path) -> bool {
+        self.dev_matcher.as_ref().is_some_and(|dev| dev.is_match(rel_path))
+    }
+
+    /// Whether a package should never be reported
+    pub fn is_ignored(&self, dependency: &str) -> bool {
+        self.ignore_dependencies.iter().any(|d| d == dependency)
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
@@ -0,0 +1,53 @@
+//! Package dependency checks for JavaScript/TypeScript projects and monorepos.
+//!
+//! Each project file belongs to the workspace package with the nearest
+//! package.json. This crate checks the packages the files import against that
+//! manifest and reports undeclared imports (including phantom dependencies that
+//! only resolve through hoisting), `devDependencies` imported by runtime code,
+//! and `dependencies` that no file imports.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_dependencies::{Config, run_dependencies_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     dev_files: vec!["tools/**".to_string()],
+//!     ignore_dependencies: vec![],
+//!     jsx_import_sources: vec![],
+//!     dev_matcher: None,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_dependencies_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_dependencies::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_dependencies_check};
+pub use config::{Config, DEFAULT_DEV_FILES};
+pub use reporter::{print_no_dependency_issues_message, print_warnings_tree};
+pub use types::{CheckResult, ImportSite, IssueKind, Warning};
@@ -0,0 +1,112 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{
+    config::Config,
+    types::{CheckResult, IssueKind, Warning},
+};
+
+pub fn print_no_dependency_issues_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No dependency issues detected");
+    writeln!(
+        writer,
+        "{} No dependency issues ({} packages checked).",
+        "✓".green().bold(),
+        result.packages_checked
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints the issues grouped by workspace package, then by kind
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} dependency issues", result.warnings.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Dependency issues detected ({} issues)\n",
+        "⚠".yellow().bold(),
+        result.warnings.len().to_string().yellow()
+    )?;
+
+    let mut rest = &result.warnings[..];
+    while let Some(first) = rest.first() {
+        let count = rest.iter().take_while(|w| w.package == first.package).count();
+        let (package, tail) = rest.split_at(count);
+        rest = tail;
+
+        let title = match &first.package_name {
+            Some(name) => format!("{} ({})", first.package, name),
+            None => first.package.clone(),
+        };
+        writeln!(writer, "{}", title.blue().bold())?;
+
+        for kind in [IssueKind::Undeclared, IssueKind::DevInRuntime, IssueKind::Unused] {
+            let warnings: Vec<&Warning> = package.iter().filter(|w| w.kind == kind).collect();
+            if warnings.is_empty() {
+                continue;
+            }
+            writeln!(writer, "  {}", kind.label().bold())?;
+            for (idx, warning) in warnings.iter().enumerate() {
+                let last = idx == warnings.len() - 1;
+                let prefix = if last { "└──" } else { "├──" };
+                let note = if warning.phantom {
+                    " (phantom: resolves through hoisting)".dimmed().to_string()
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    writer,
+                    "  {} {}{}",
+                    prefix.dimmed(),
+                    warning.dependency.yellow().bold(),
+                    note
+                )?;
+
+                let indent = if last { "    " } else { "│   " };
+                for site in &warning.sites {
+                    writeln!(
+                        writer,
+                        "  {}  {}  {}",
+                        indent.dimmed(),
+                        format!("{}:{}", display(&site.file), site.line).blue(),
+                        format!("import '{}'", site.request).dimmed()
+                    )?;
+                }
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Packages checked: {}", result.packages_checked)?;
+    writeln!(
+        writer,
+        "  Undeclared: {}",
+        result.count(IssueKind::Undeclared).to_string().yellow().bold()
+    )?;
+    writeln!(
+        writer,
+        "  Dev dependencies in runtime code: {}",
+        result.count(IssueKind::DevInRuntime).to_string().yellow().bold()
+    )?;
+    writeln!(writer, "  Unused: {}", result.count(IssueKind::Unused).to_string().yellow().bold())?;
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+/// What is wrong with a dependency of a workspace package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// Imported but not listed in `dependencies`, `peerDependencies`,
+    /// `optionalDependencies` or `devDependencies`
+    Undeclared,
+    /// Only listed in `devDependencies`, but imported by runtime code
+    DevInRuntime,
+    /// Listed in `dependencies`, but no file of the package imports it
+    Unused,
+}
+
+impl IssueKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            IssueKind::Undeclared => "Undeclared",
+            IssueKind::DevInRuntime => "Dev dependency in runtime code",
+            IssueKind::Unused => "Unused",
+        }
+    }
+}
+
+/// An import of a package
+#[derive(Debug, Clone)]
+pub struct ImportSite {
+    /// The importing file, relative to the root
+    pub file: String,
+    /// 1-based line of the import in `file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+}
+
+/// A dependency issue of one workspace package
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// Directory of the package's package.json, relative to the root (`.` for the root)
+    pub package: String,
+    /// The `name` field of the package.json, if present
+    pub package_name: Option<String>,
+    pub kind: IssueKind,
+    /// The dependency's package name
+    pub dependency: String,
+    /// For undeclared packages: resolved from `node_modules` anyway, which only
+    /// works because another package's dependency was hoisted or installed there
+    pub phantom: bool,
+    /// The imports of the dependency, empty for unused dependencies
+    pub sites: Vec<ImportSite>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Issues sorted by package, kind and dependency
+    pub warnings: Vec<Warning>,
+    /// Workspace packages owning at least one analyzed file
+    pub packages_checked: usize,
+    pub files_analyzed: usize,
+}
+
+impl CheckResult {
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.warnings.iter().filter(|w| w.kind == kind).count()
+    }
+}
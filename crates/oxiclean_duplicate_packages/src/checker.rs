@@ -0,0 +1,373 @@
+use anyhow::Result;
+use log::{debug, info, trace};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use oxiclean_core::{ModuleGraph, ModuleId};
+
+use crate::{
+    config::Config,
+    types::{CheckResult, Importer, PackageCopy, Warning},
+};
+
+pub fn run_duplicate_packages_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting duplicate packages check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
+
+/// An installed package directory
+struct Copy {
+    name: String,
+    version: Option<String>,
+    dir: PathBuf,
+}
+
+/// Runs the duplicate packages check as a pass over an already-built module graph.
+///
+/// Every file in `node_modules` belongs to the copy of its package in the
+/// directory after the last `node_modules`, with the name and version from its
+/// package.json. A collapsed package is a copy on its own. A package is
+/// reported when an entry reaches more than one of its copies through runtime
+/// imports.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    let mut copies: Vec<Copy> = Vec::new();
+    let mut copy_by_dir: HashMap<PathBuf, usize> = HashMap::new();
+    let mut copy_of: Vec<Option<usize>> = vec![None; graph.len()];
+    for id in graph.modules() {
+        let Some((dir, path_name)) = graph.package_root(id) else {
+            continue;
+        };
+        let copy = *copy_by_dir.entry(dir.clone()).or_insert_with(|| {
+            let (name, version) = read_manifest(&dir);
+            copies.push(Copy { name: name.unwrap_or(path_name), version, dir });
+            copies.len() - 1
+        });
+        copy_of[id.index()] = Some(copy);
+    }
+
+    let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, copy) in copies.iter().enumerate() {
+        by_name.entry(&copy.name).or_default().push(index);
+    }
+    let packages_found = by_name.len();
+    let candidates: Vec<usize> = by_name
+        .iter()
+        .filter(|(name, copies)| copies.len() > 1 && !cfg.is_ignored(name))
+        .flat_map(|(_, copies)| copies.iter().copied())
+        .collect();
+    debug!("Found {} copies of {} packages", copies.len(), packages_found);
+    if candidates.is_empty() {
+        info!("Duplicate packages check complete. No package has several copies");
+        return CheckResult { warnings: Vec::new(), packages_found, files_analyzed: graph.len() };
+    }
+
+    // Per duplicated package: the copies reached together and the affected entries
+    #[derive(Default)]
+    struct Duplicate {
+        copies: BTreeSet<usize>,
+        entries: Vec<ModuleId>,
+    }
+    let mut duplicates: BTreeMap<&str, Duplicate> = BTreeMap::new();
+    let reached_copies = candidate_closures(graph, &copy_of, &candidates);
+    for &entry in graph.entries() {
+        let mut reached: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+        for copy in reached_copies(entry) {
+            reached.entry(&copies[copy].name).or_default().insert(copy);
+        }
+        for (name, reached_copies) in reached.into_iter().filter(|(_, c)| c.len() > 1) {
+            trace!("{} reaches {} copies of {}", graph.rel_path(entry), reached_copies.len(), name);
+            let duplicate = duplicates.entry(name).or_default();
+            duplicate.copies.extend(reached_copies);
+            duplicate.entries.push(entry);
+        }
+    }
+
+    let mut warnings: Vec<Warning> = duplicates
+        .into_iter()
+        .map(|(name, duplicate)| {
+            let reached = graph.reached_from(&duplicate.entries);
+            let mut package_copies: Vec<PackageCopy> = duplicate
+                .copies
+                .iter()
+                .map(|&copy| PackageCopy {
+                    dir: rel_dir(graph.root(), &copies[copy].dir),
+                    version: copies[copy].version.clone(),
+                    modules: copy_of.iter().filter(|c| **c == Some(copy)).count(),
+                    importers: importers_of(graph, &copy_of, copy, &reached),
+                })
+                .collect();
+            package_copies.sort_by(|a, b| a.dir.cmp(&b.dir));
+            let mut entries: Vec<String> =
+                duplicate.entries.iter().map(|&e| graph.rel_path(e)).collect();
+            entries.sort();
+            Warning { name: name.to_string(), copies: package_copies, entries }
+        })
+        .collect();
+    warnings.sort_by_key(|w| (Reverse(w.entries.len()), w.name.clone()));
+
+    info!("Duplicate packages check complete. Found {} duplicated packages", warnings.len());
+    CheckResult { warnings, packages_found, files_analyzed: graph.len() }
+}
+
+/// The candidate copies reached from each module.
+///
+/// Reached copies are computed once per strongly connected component as
+/// bitsets over the candidates, in dependency order, so every entry is looked
+/// up without walking its imports again.
+fn candidate_closures(
+    graph: &ModuleGraph,
+    copy_of: &[Option<usize>],
+    candidates: &[usize],
+) -> impl Fn(ModuleId) -> Vec<usize> {
+    let bit_of: HashMap<usize, usize> =
+        candidates.iter().enumerate().map(|(bit, &copy)| (copy, bit)).collect();
+    let words = candidates.len().div_ceil(64);
+    let dag = graph.condensation();
+    let mut sets: Vec<Vec<u64>> = Vec::with_capacity(dag.len());
+    for c in 0..dag.len() {
+        let mut bits = vec![0u64; words];
+        for &id in dag.members(c) {
+            if let Some(&bit) = copy_of[id.index()].and_then(|copy| bit_of.get(&copy)) {
+                bits[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        for s in dag.successors(c) {
+            for (word, other) in bits.iter_mut().zip(&sets[s]) {
+                *word |= other;
+            }
+        }
+        sets.push(bits);
+    }
+    debug!(
+        "Computed reached copies for {} components over {} candidates",
+        dag.len(),
+        candidates.len()
+    );
+
+    let candidates = candidates.to_vec();
+    move |id| {
+        let bits = &sets[dag.component_of(id)];
+        (0..candidates.len())
+            .filter(|&bit| bits[bit / 64] & (1 << (bit % 64)) != 0)
+            .map(|bit| candidates[bit])
+            .collect()
+    }
+}
+
+/// Runtime imports of files of `copy` from reached files outside of it
+fn importers_of(
+    graph: &ModuleGraph,
+    copy_of: &[Option<usize>],
+    copy: usize,
+    reached: &[bool],
+) -> Vec<Importer> {
+    let mut from: Vec<ModuleId> = graph
+        .modules()
+        .filter(|id| copy_of[id.index()] == Some(copy))
+        .flat_map(|id| graph.importers(id).iter().copied())
+        .filter(|importer| copy_of[importer.index()] != Some(copy) && reached[importer.index()])
+        .collect();
+    from.sort();
+    from.dedup();
+
+    let mut importers: Vec<Importer> = from
+        .into_iter()
+        .flat_map(|importer| {
+            graph
+                .runtime_imports(importer)
+                .filter(|edge| copy_of[edge.to.index()] == Some(copy))
+                .map(move |edge| Importer {
+                    file: graph.rel_path(importer),
+                    line: edge.line,
+                    request: edge.request.clone(),
+                })
+        })
+        .collect();
+    importers.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    importers
+}
+
+/// The `name` and `version` fields of the package.json in `dir`
+fn read_manifest(dir: &Path) -> (Option<String>, Option<String>) {
+    let Some(manifest) = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|txt| serde_json::from_str::<serde_json::Value>(&txt).ok())
+    else {
+        trace!("No readable package.json in {:?}", dir);
+        return (None, None);
+    };
+    let field = |key: &str| manifest.get(key).and_then(|v| v.as_str()).map(String::from);
+    (field("name"), field("version"))
+}
+
+fn rel_dir(root: &Path, dir: &Path) -> String {
+    dir.strip_prefix(root).unwrap_or(dir).to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, ignore: &[&str]) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            ignore: ignore.iter().map(|p| p.to_string()).collect(),
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_package(root: &Path, dir: &str, name: &str, version: &str, source: &str) {
+        create_test_file(
+            root,
+            &format!("{}/package.json", dir),
+            &format!(r#"{{ "name": "{}", "version": "{}", "main": "index.js" }}"#, name, version),
+        );
+        create_test_file(root, &format!("{}/index.js", dir), source);
+    }
+
+    #[test]
+    fn test_duplicate_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_package(root, "node_modules/react", "react", "18.2.0", "module.exports = 1;");
+        create_package(
+            root,
+            "node_modules/legacy/node_modules/react",
+            "react",
+            "17.0.2",
+            "module.exports = 1;",
+        );
+        create_package(root, "node_modules/legacy", "legacy", "1.0.0", "require('react');");
+        create_package(root, "node_modules/@scope/ui", "@scope/ui", "2.0.0", "require('react');");
+        create_test_file(root, "src/app.ts", "import 'react';\nimport 'legacy';");
+        create_test_file(root, "src/other.ts", "import 'react';\nimport '@scope/ui';");
+
+        let result = run_duplicate_packages_check(config(root, &[])).unwrap();
+        assert_eq!(result.packages_found, 3);
+        assert_eq!(result.warnings.len(), 1);
+        let warning = &result.warnings[0];
+        assert_eq!(warning.name, "react");
+        assert_eq!(warning.entries, vec!["src/app.ts"]);
+        let copies: Vec<(&str, Option<&str>, Vec<String>)> = warning
+            .copies
+            .iter()
+            .map(|c| {
+                let importers = c.importers.iter().map(|i| format!("{}:{}", i.file, i.line));
+                (c.dir.as_str(), c.version.as_deref(), importers.collect())
+            })
+            .collect();
+        assert_eq!(
+            copies,
+            vec![
+                (
+                    "node_modules/legacy/node_modules/react",
+                    Some("17.0.2"),
+                    vec!["node_modules/legacy/index.js:1".to_string()]
+                ),
+                // other.ts doesn't reach two copies, so its import isn't listed
+                ("node_modules/react", Some("18.2.0"), vec!["src/app.ts:1".to_string()]),
+            ]
+        );
+
+        assert!(
+            run_duplicate_packages_check(config(root, &["react"])).unwrap().warnings.is_empty()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_packages_through_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_package(root, "node_modules/react", "react", "18.2.0", "module.exports = 1;");
+        create_package(
+            root,
+            "node_modules/legacy/node_modules/react",
+            "react",
+            "17.0.2",
+            "module.exports = 1;",
+        );
+        create_package(root, "node_modules/legacy", "legacy", "1.0.0", "require('react');");
+        create_test_file(root, "src/a.ts", "import './b';\nimport 'react';");
+        create_test_file(root, "src/b.ts", "import './a';\nimport 'legacy';");
+        create_test_file(root, "src/c.ts", "import 'react';");
+
+        let result = run_duplicate_packages_check(config(root, &[])).unwrap();
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].entries, vec!["src/a.ts", "src/b.ts"]);
+    }
+
+    #[test]
+    fn test_duplicate_collapsed_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_package(root, "node_modules/react", "react", "18.2.0", "module.exports = 1;");
+        create_package(
+            root,
+            "packages/ui/node_modules/react",
+            "react",
+            "17.0.2",
+            "module.exports = 1;",
+        );
+        create_test_file(root, "packages/ui/src/button.ts", "import 'react';");
+        create_test_file(
+            root,
+            "src/app.ts",
+            "import 'react';\nimport '../packages/ui/src/button';",
+        );
+
+        let mut cfg = config(root, &[]);
+        cfg.shared.collapse_packages = Some(true);
+        let result = run_duplicate_packages_check(cfg).unwrap();
+        assert_eq!(result.packages_found, 1);
+        assert_eq!(result.warnings.len(), 1);
+        let warning = &result.warnings[0];
+        assert_eq!(warning.name, "react");
+        assert_eq!(warning.entries, vec!["src/app.ts"]);
+        let copies: Vec<(&str, usize, Vec<String>)> = warning
+            .copies
+            .iter()
+            .map(|c| {
+                let importers = c.importers.iter().map(|i| format!("{}:{}", i.file, i.line));
+                (c.dir.as_str(), c.modules, importers.collect())
+            })
+            .collect();
+        assert_eq!(
+            copies,
+            vec![
+                ("node_modules/react", 1, vec!["src/app.ts:1".to_string()]),
+                (
+                    "packages/ui/node_modules/react",
+                    1,
+                    vec!["packages/ui/src/button.ts:1".to_string()]
+                ),
+            ]
+        );
+    }
+}
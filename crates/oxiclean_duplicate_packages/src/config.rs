@@ -0,0 +1,69 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "duplicate-packages")]
+#[command(about = "Find packages with more than one copy reachable from the same entry")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Package name never reported, e.g. one that is safe to bundle twice. Repeatable
+    #[arg(long)]
+    pub ignore: Vec<String>,
+}
+
+/// The `[duplicate-packages]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct DuplicatePackagesSection {
+    ignore: Vec<String>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+
+        // Names from the config file and the command line add up
+        let section: DuplicatePackagesSection = self.shared.check_section("duplicate-packages")?;
+        for name in section.ignore {
+            if !self.ignore.contains(&name) {
+                self.ignore.push(name);
+            }
+        }
+        debug!("Ignoring {} packages", self.ignore.len());
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    /// Whether a package should never be reported
+    pub fn is_ignored(&self, package: &str) -> bool {
+        self.ignore.iter().any(|p| p == package)
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
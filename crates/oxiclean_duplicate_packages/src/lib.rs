@@ -0,0 +1,50 @@
+//! Duplicate package detection for JavaScript/TypeScript projects.
+//!
+//! When resolution walks through nested `node_modules`, one package can resolve
+//! from several directories, often at different versions. This crate groups
+//! the resolved `node_modules` files by package copy, with the name and version
+//! from each copy's package.json, and reports the packages an entry reaches more
+//! than one copy of, together with the imports that pull in each copy.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_duplicate_packages::{Config, run_duplicate_packages_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     ignore: vec![],
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_duplicate_packages_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_duplicate_packages::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_duplicate_packages_check};
+pub use config::Config;
+pub use reporter::{print_no_duplicates_message, print_warnings_tree};
+pub use types::{CheckResult, Importer, PackageCopy, Warning};
@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::CheckResult};
+
+pub fn print_no_duplicates_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No duplicate packages detected");
+    writeln!(
+        writer,
+        "{} No duplicate packages ({} packages reachable).",
+        "✓".green().bold(),
+        result.packages_found
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints each duplicated package with its copies and the imports pulling in each
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} duplicated packages", result.warnings.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Duplicate packages detected ({} packages)\n",
+        "⚠".yellow().bold(),
+        result.warnings.len().to_string().yellow()
+    )?;
+
+    for warning in &result.warnings {
+        let entries = match warning.entries.as_slice() {
+            [entry] => format!("reached together from {}", display(entry)),
+            entries => format!("reached together from {} entries", entries.len()),
+        };
+        writeln!(
+            writer,
+            "{} {}",
+            warning.name.blue().bold(),
+            format!("({} copies, {})", warning.copies.len(), entries).dimmed()
+        )?;
+
+        for (idx, copy) in warning.copies.iter().enumerate() {
+            let last = idx == warning.copies.len() - 1;
+            let prefix = if last { "└──" } else { "├──" };
+            writeln!(
+                writer,
+                "{} {}  {} {}",
+                prefix.dimmed(),
+                copy.version.as_deref().unwrap_or("unknown version").yellow().bold(),
+                display(&copy.dir),
+                format!("({} modules)", copy.modules).dimmed()
+            )?;
+
+            let indent = if last { "    " } else { "│   " };
+            for importer in &copy.importers {
+                writeln!(
+                    writer,
+                    "{}  {}  {}",
+                    indent.dimmed(),
+                    format!("{}:{}", display(&importer.file), importer.line).blue(),
+                    format!("import '{}'", importer.request).dimmed()
+                )?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    let extra: usize = result.warnings.iter().map(|w| w.copies.len() - 1).sum();
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Packages reachable: {}", result.packages_found)?;
+    writeln!(
+        writer,
+        "  Duplicated packages: {}",
+        result.warnings.len().to_string().yellow().bold()
+    )?;
+    writeln!(writer, "  Extra copies: {}", extra.to_string().yellow())?;
+
+    writer.flush()?;
+    Ok(())
+}
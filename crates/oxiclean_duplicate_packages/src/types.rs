@@ -0,0 +1,44 @@
+/// An import of a file of a package copy
+#[derive(Debug, Clone)]
+pub struct Importer {
+    /// The importing file, relative to the root
+    pub file: String,
+    /// 1-based line of the import in `file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+}
+
+/// One installed copy of a package
+#[derive(Debug, Clone)]
+pub struct PackageCopy {
+    /// The package directory, relative to the root
+    pub dir: String,
+    /// The `version` field of its package.json, if present
+    pub version: Option<String>,
+    /// Files of the copy in the graph
+    pub modules: usize,
+    /// Imports from outside the copy that pull it in, from files reachable from
+    /// an entry that reaches several copies
+    pub importers: Vec<Importer>,
+}
+
+/// A package with several copies reachable from the same entry
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// The package name
+    pub name: String,
+    /// The copies reachable together, sorted by directory
+    pub copies: Vec<PackageCopy>,
+    /// Entries reaching more than one of the copies, relative to the root
+    pub entries: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Duplicated packages, those reached from the most entries first
+    pub warnings: Vec<Warning>,
+    /// Distinct packages in the graph
+    pub packages_found: usize,
+    pub files_analyzed: usize,
+}
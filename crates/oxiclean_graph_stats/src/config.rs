@@ -0,0 +1,132 @@
+use anyhow::{Result, anyhow};
+use clap::{Parser, ValueEnum};
+use log::debug;
+use oxiclean_core::{CommandConfig, GlobMatcher, SharedConfig};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// The metric modules are sorted by, largest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortKey {
+    FanIn,
+    FanOut,
+    Reach,
+    #[default]
+    Dependents,
+    Depth,
+}
+
+impl SortKey {
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::FanIn => "fan-in",
+            SortKey::FanOut => "fan-out",
+            SortKey::Reach => "reach",
+            SortKey::Dependents => "dependents",
+            SortKey::Depth => "depth",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+#[command(name = "graph-stats")]
+#[command(about = "Show fan-in, fan-out, reach, dependents and depth of each module")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Metric to sort by, largest first [default: dependents]
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+
+    /// Number of modules to show, 0 for all [default: 20]
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Glob pattern for the modules to show, `!` to exclude. Repeatable
+    #[arg(long)]
+    pub filter: Vec<String>,
+
+    /// Only show modules whose sort metric is at least this value
+    #[arg(long)]
+    pub min: Option<usize>,
+
+    /// Also show files in node_modules, which are always counted
+    #[arg(long)]
+    pub include_node_modules: bool,
+
+    #[clap(skip)]
+    pub filter_matcher: Option<GlobMatcher>,
+}
+
+/// The `[graph-stats]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct GraphStatsSection {
+    sort: Option<SortKey>,
+    limit: Option<usize>,
+    filter: Vec<String>,
+    include_node_modules: bool,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.filter_matcher.is_some() {
+            return Ok(());
+        }
+        self.shared.initialize()?;
+
+        let section: GraphStatsSection = self.shared.check_section("graph-stats")?;
+        self.sort = self.sort.or(section.sort);
+        self.limit = self.limit.or(section.limit);
+        self.filter.splice(0..0, section.filter);
+        self.include_node_modules |= section.include_node_modules;
+        debug!(
+            "Sorting by {}, showing {} modules matching {:?}",
+            self.sort_key().label(),
+            self.limit(),
+            self.filter
+        );
+        self.filter_matcher = Some(GlobMatcher::new(&self.filter)?);
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    pub fn sort_key(&self) -> SortKey {
+        self.sort.unwrap_or_default()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT)
+    }
+
+    /// Whether a module, relative to the root, passes `--filter`
+    pub fn is_shown(&self, rel_path: &Path) -> bool {
+        self.filter_matcher.as_ref().is_none_or(|filter| filter.is_match(rel_path))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
@@ -0,0 +1,53 @@
+//! Module graph statistics for JavaScript/TypeScript projects.
+//!
+//! Computes, for every module, its fan-in and fan-out (direct importers and
+//! imports), its transitive reach (the modules it loads, as counted by
+//! `oxiclean_import_bloat`), its dependents (the modules that load it) and its
+//! import depth. Sorting by dependents finds the hotspots where one change
+//! ripples through the whole project.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_graph_stats::{Config, SortKey, run_graph_stats};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     sort: Some(SortKey::FanIn),
+//!     limit: Some(50),
+//!     filter: vec!["src/**".to_string()],
+//!     min: None,
+//!     include_node_modules: false,
+//!     filter_matcher: None,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let stats = run_graph_stats(cfg.clone())?;
+//!
+//! // Use buffered output for better performance
+//! let mut stdout = BufWriter::new(std::io::stdout());
+//! oxiclean_graph_stats::print_stats_table(&mut stdout, &stats, &cfg)?;
+//! stdout.flush()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod config;
+mod reporter;
+mod stats;
+mod types;
+
+// Re-export public API
+pub use config::{Config, DEFAULT_LIMIT, SortKey};
+pub use reporter::print_stats_table;
+pub use stats::{compute_stats, run_graph_stats};
+pub use types::{GraphStats, ModuleStats};
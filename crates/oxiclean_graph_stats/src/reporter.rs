@@ -0,0 +1,78 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{
+    config::{Config, SortKey},
+    types::GraphStats,
+};
+
+const COLUMNS: &[(SortKey, &str)] = &[
+    (SortKey::FanIn, "Fan-in"),
+    (SortKey::FanOut, "Fan-out"),
+    (SortKey::Reach, "Reach"),
+    (SortKey::Dependents, "Dependents"),
+    (SortKey::Depth, "Depth"),
+];
+
+/// Prints the metrics as a table, the sort column highlighted, followed by a
+/// summary of the whole graph
+pub fn print_stats_table<W: Write>(
+    writer: &mut W,
+    stats: &GraphStats,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing stats of {} modules", stats.modules.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    let shown = if stats.modules.len() < stats.matched {
+        format!("top {} of {}", stats.modules.len(), stats.matched)
+    } else {
+        format!("{}", stats.matched)
+    };
+    writeln!(
+        writer,
+        "{} {}\n",
+        "Module graph stats".bold(),
+        format!("({} modules, sorted by {})", shown, stats.sort.label()).dimmed()
+    )?;
+
+    if stats.modules.is_empty() {
+        writeln!(writer, "  No modules match the filters.\n")?;
+    } else {
+        let header: Vec<String> = COLUMNS
+            .iter()
+            .map(|(key, title)| {
+                let cell = format!("{:>12}", title);
+                if *key == stats.sort { cell.bold().to_string() } else { cell.dimmed().to_string() }
+            })
+            .collect();
+        writeln!(writer, "{}  {}", header.join(""), "Module".dimmed())?;
+
+        for module in &stats.modules {
+            let cells: Vec<String> = COLUMNS
+                .iter()
+                .map(|(key, _)| {
+                    let cell = format!("{:>12}", module.metric(*key));
+                    if *key == stats.sort { cell.yellow().bold().to_string() } else { cell }
+                })
+                .collect();
+            writeln!(writer, "{}  {}", cells.join(""), display(&module.file).blue())?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Modules: {}", stats.total_modules)?;
+    writeln!(writer, "  Runtime imports: {}", stats.total_imports)?;
+    writeln!(writer, "  Import cycles: {}", stats.cycles)?;
+
+    writer.flush()?;
+    Ok(())
+}
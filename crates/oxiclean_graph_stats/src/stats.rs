@@ -0,0 +1,172 @@
+use anyhow::Result;
+use log::{debug, info};
+use std::{cmp::Reverse, path::Path};
+
+use oxiclean_core::{ModuleGraph, ModuleId};
+
+use crate::{
+    config::Config,
+    types::{GraphStats, ModuleStats},
+};
+
+pub fn run_graph_stats(mut cfg: Config) -> Result<GraphStats> {
+    info!("Starting graph stats");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(compute_stats(&graph, &cfg))
+}
+
+/// Computes the metrics of every module of an already-built module graph, then
+/// filters, sorts and limits them as configured.
+///
+/// Reach and dependents are counted over the graph's condensation, so both take
+/// a single pass however many modules there are.
+///
+/// `cfg` must be initialized.
+pub fn compute_stats(graph: &ModuleGraph, cfg: &Config) -> GraphStats {
+    let mut fan_in = vec![0; graph.len()];
+    let mut fan_out = vec![0; graph.len()];
+    for id in graph.modules() {
+        let mut targets: Vec<ModuleId> = graph.runtime_imports(id).map(|edge| edge.to).collect();
+        targets.sort();
+        targets.dedup();
+        fan_out[id.index()] = targets.len();
+        for to in targets {
+            fan_in[to.index()] += 1;
+        }
+    }
+
+    let all: Vec<ModuleId> = graph.modules().collect();
+    let reach = graph.reachable_counts(&all);
+    let dependents = graph.dependent_counts(&all);
+    let dag = graph.condensation();
+    let depths = dag.depths();
+    let cycles = (0..dag.len()).filter(|&c| dag.members(c).len() > 1).count();
+    debug!("Computed metrics for {} modules in {} components", all.len(), dag.len());
+
+    let sort = cfg.sort_key();
+    let mut modules: Vec<ModuleStats> = all
+        .iter()
+        .filter(|&&id| cfg.include_node_modules || !graph.is_installed(id))
+        .map(|&id| ModuleStats {
+            file: graph.rel_path(id),
+            fan_in: fan_in[id.index()],
+            fan_out: fan_out[id.index()],
+            reach: reach[&id],
+            dependents: dependents[&id] - 1,
+            depth: depths[dag.component_of(id)],
+        })
+        .filter(|stats| cfg.is_shown(Path::new(&stats.file)))
+        .filter(|stats| cfg.min.is_none_or(|min| stats.metric(sort) >= min))
+        .collect();
+    modules.sort_by(|a, b| {
+        Reverse(a.metric(sort)).cmp(&Reverse(b.metric(sort))).then_with(|| a.file.cmp(&b.file))
+    });
+    let matched = modules.len();
+    if cfg.limit() > 0 {
+        modules.truncate(cfg.limit());
+    }
+
+    info!("Graph stats complete. {} of {} modules match", matched, graph.len());
+    GraphStats {
+        modules,
+        matched,
+        sort,
+        total_modules: graph.len(),
+        total_imports: fan_out.iter().sum(),
+        cycles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SortKey;
+    use oxiclean_core::SharedConfig;
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, sort: SortKey, filter: &[&str], min: Option<usize>) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            sort: Some(sort),
+            limit: None,
+            filter: filter.iter().map(|f| f.to_string()).collect(),
+            min,
+            include_node_modules: false,
+            filter_matcher: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        create_test_file(root, "src/pages/home.ts", "import '../lib/format';\nimport '../ui';");
+        create_test_file(root, "src/pages/admin.ts", "import '../ui';");
+        create_test_file(
+            root,
+            "src/ui/index.ts",
+            "import './button';\nimport type { T } from './types';",
+        );
+        create_test_file(root, "src/ui/button.ts", "import '../lib/format';");
+        create_test_file(root, "src/ui/types.ts", "export type T = 1;");
+        create_test_file(root, "src/lib/format.ts", "export const f = 1;");
+    }
+
+    #[test]
+    fn test_module_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let stats = run_graph_stats(config(root, SortKey::Dependents, &[], None)).unwrap();
+        assert_eq!((stats.total_modules, stats.total_imports, stats.cycles), (6, 5, 0));
+        let format = &stats.modules[0];
+        assert_eq!(
+            format,
+            &ModuleStats {
+                file: "src/lib/format.ts".to_string(),
+                fan_in: 2,
+                fan_out: 0,
+                reach: 1,
+                dependents: 4,
+                depth: 0,
+            }
+        );
+        let home = stats.modules.iter().find(|m| m.file == "src/pages/home.ts").unwrap();
+        assert_eq!((home.fan_out, home.reach, home.dependents, home.depth), (2, 4, 0, 3));
+    }
+
+    #[test]
+    fn test_sort_filter_and_min() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let stats = run_graph_stats(config(root, SortKey::Reach, &["src/ui/**"], Some(2))).unwrap();
+        let files: Vec<&str> = stats.modules.iter().map(|m| m.file.as_str()).collect();
+        assert_eq!(files, vec!["src/ui/index.ts", "src/ui/button.ts"]);
+        assert_eq!(stats.matched, 2);
+
+        let stats =
+            run_graph_stats(config(root, SortKey::Depth, &["!src/pages/**"], None)).unwrap();
+        assert_eq!(stats.modules[0].file, "src/ui/index.ts");
+        assert_eq!(stats.matched, 4);
+    }
+}
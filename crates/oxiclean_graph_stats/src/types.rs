@@ -0,0 +1,45 @@
+use crate::config::SortKey;
+
+/// Graph metrics of one module. All but fan-in and fan-out are transitive, and
+/// every metric follows runtime imports only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleStats {
+    /// The module, relative to the root
+    pub file: String,
+    /// Modules importing it directly
+    pub fan_in: usize,
+    /// Modules it imports directly
+    pub fan_out: usize,
+    /// Modules it loads, including itself (the import-bloat count)
+    pub reach: usize,
+    /// Modules that load it, not counting itself: what a change to it can affect
+    pub dependents: usize,
+    /// Longest import chain below it, an import cycle counting as one level
+    pub depth: usize,
+}
+
+impl ModuleStats {
+    pub fn metric(&self, key: SortKey) -> usize {
+        match key {
+            SortKey::FanIn => self.fan_in,
+            SortKey::FanOut => self.fan_out,
+            SortKey::Reach => self.reach,
+            SortKey::Dependents => self.dependents,
+            SortKey::Depth => self.depth,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphStats {
+    /// The modules passing the filters, sorted and limited
+    pub modules: Vec<ModuleStats>,
+    /// Modules passing the filters before the limit was applied
+    pub matched: usize,
+    pub sort: SortKey,
+    /// Modules and runtime imports in the whole graph
+    pub total_modules: usize,
+    pub total_imports: usize,
+    /// Import cycles in the graph
+    pub cycles: usize,
+}
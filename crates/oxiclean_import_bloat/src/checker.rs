@@ -1,14 +1,16 @@
-use anyhow::{Result, anyhow};
-use dashmap::DashMap;
-use log::{debug, info, trace, warn};
+use anyhow::Result;
+use log::{debug, info, trace};
 use rayon::prelude::*;
-use std::{collections::HashSet, path::PathBuf, sync::Arc, thread};
+use std::{path::Path, thread};
 
-use oxiclean_core::{CollectorConfig, Specifier, collect_entries, imports_for, resolve};
+use oxiclean_core::{ModuleGraph, ModuleId, merge_entry_results};
 
 use crate::{
     config::Config,
-    graph::reachable_modules,
+    graph::{
+        exclusive_modules, module_counts, package_breakdown, reachable_modules, reachable_weights,
+    },
+    size::module_sizes,
     types::{CheckResult, Warning},
 };
 
@@ -17,150 +19,141 @@ pub fn run_import_bloat_check(mut cfg: Config) -> Result<CheckResult> {
 
     // Initialize config (resolve root, load tsconfig paths)
     cfg.initialize()?;
-    let root = cfg.root().ok_or_else(|| anyhow!("Config not initialized"))?.clone();
 
-    debug!("Collecting entry files with glob: {:?}", cfg.entry_glob);
-    let collector_cfg = CollectorConfig {
-        root: root.clone(),
-        entry_glob: cfg.entry_glob.clone(),
-        tsconfig_paths: cfg.tsconfig_paths.clone(),
-    };
-
-    let entries = collect_entries(&collector_cfg)?;
-    if entries.is_empty() {
-        warn!("No entry files found under {}", root.display());
-        return Err(anyhow!("No entry files found under {}", root.display()));
-    }
-    info!("Found {} entry files", entries.len());
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
 
-    // Thread-safe caches using DashMap
-    let import_cache: Arc<DashMap<PathBuf, Vec<Specifier>>> = Arc::new(DashMap::new());
-    let resolve_cache: Arc<DashMap<(PathBuf, String), Option<PathBuf>>> = Arc::new(DashMap::new());
-    let reachable_cache: Arc<DashMap<PathBuf, HashSet<PathBuf>>> = Arc::new(DashMap::new());
+/// Runs the import bloat check as a pass over an already-built module graph.
+///
+/// In size mode imports are weighed by the total size of their reachable
+/// modules rather than their number, and thresholds are in KB. Modules in
+/// node_modules are third-party, the rest first-party; each part can have a
+/// threshold of its own.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    check_entries(graph, cfg, graph.entries())
+}
 
-    // Wrap config in Arc for sharing across threads
-    let cfg = Arc::new(cfg);
+/// Runs the import bloat check for some of the graph's entries only, such as
+/// the ones watch mode found changed. Reachable counts and sizes are computed
+/// for the modules these entries reach rather than the whole graph.
+/// Re-exports are not followed, so a barrel file only counts the modules it
+/// imports itself.
+///
+/// `cfg` must be initialized.
+pub fn check_entries(graph: &ModuleGraph, cfg: &Config, entries: &[ModuleId]) -> CheckResult {
+    let graph = &graph.without_re_exports();
+    let counts = module_counts(graph, cfg);
+    let reachable = reachable_modules(graph, entries, &counts);
+    let sizes = cfg.size.map(|metric| module_sizes(graph, metric, &graph.reached_from(entries)));
+    let reachable_bytes = sizes.as_deref().map(|sizes| reachable_weights(graph, entries, sizes));
+
+    let packages: Vec<Option<String>> = graph.modules().map(|id| graph.package_name(id)).collect();
+    let third_party: Vec<u64> = packages.iter().map(|p| u64::from(p.is_some())).collect();
+    let third_party_counts: Vec<u64> =
+        counts.iter().zip(&third_party).map(|(c, t)| c * t).collect();
+    let reachable_third_party = reachable_weights(graph, entries, &third_party_counts);
+    let reachable_third_party_bytes = sizes.as_deref().map(|sizes| {
+        let masked: Vec<u64> = sizes.iter().zip(&third_party).map(|(s, t)| s * t).collect();
+        reachable_weights(graph, entries, &masked)
+    });
+
+    // Size mode weighs modules by bytes, the default by count: the total, then
+    // the third-party part
+    let weight = |id| match &reachable_bytes {
+        Some(bytes) => bytes[&id],
+        None => reachable[&id] as u64,
+    };
+    let third_party_weight = |id| match &reachable_third_party_bytes {
+        Some(bytes) => bytes[&id],
+        None => reachable_third_party[&id],
+    };
+    let first_party_limit = cfg.first_party_limit();
+    let third_party_limit = cfg.third_party_limit();
+    let exceeds = |id, limit| {
+        weight(id) >= limit
+            || first_party_limit.is_some_and(|l| weight(id) - third_party_weight(id) >= l)
+            || third_party_limit.is_some_and(|l| third_party_weight(id) >= l)
+    };
+    let warning =
+        |statement: String, from_file: &str, id, exclusive: Option<(usize, u64)>| Warning {
+            import_statement: statement,
+            from_file: from_file.to_string(),
+            reachable_unique_modules: reachable[&id],
+            reachable_bytes: reachable_bytes.as_ref().map(|bytes| bytes[&id]),
+            exclusive_modules: exclusive.map(|(count, _)| count),
+            exclusive_bytes: exclusive.filter(|_| sizes.is_some()).map(|(_, bytes)| bytes),
+            third_party_modules: reachable_third_party[&id] as usize,
+            third_party_bytes: reachable_third_party_bytes.as_ref().map(|bytes| bytes[&id]),
+            packages: package_breakdown(
+                graph,
+                id,
+                &packages,
+                &counts,
+                sizes.as_deref(),
+                cfg.top_packages(),
+            ),
+            resolved_path: None,
+        };
 
     info!("Processing {} entry files in parallel", entries.len());
 
     // Process entries in parallel using rayon
     let warnings: Vec<Warning> = entries
         .par_iter()
-        .flat_map(|entry| {
+        .flat_map(|&entry| {
             let thread_id = thread::current().id();
-            debug!("Thread {:?} processing: {}", thread_id, entry.display());
-            trace!("Computing reachable modules for entry: {}", entry.display());
-
-            let cfg = Arc::clone(&cfg);
-            let import_cache = Arc::clone(&import_cache);
-            let resolve_cache = Arc::clone(&resolve_cache);
-            let reachable_cache = Arc::clone(&reachable_cache);
-
-            let root = match cfg.root() {
-                Some(r) => r.clone(),
-                None => {
-                    warn!("Config root not initialized");
-                    return vec![];
-                }
-            };
-
-            // Compute reachable modules for this entry
-            let reachable = match reachable_modules(
-                &root,
-                &cfg.tsconfig_paths,
-                entry,
-                &import_cache,
-                &resolve_cache,
-                &reachable_cache,
-            ) {
-                Ok(r) => r,
-                Err(e) => {
-                    warn!("Error computing reachable modules for {}: {}", entry.display(), e);
-                    return vec![];
-                }
-            };
-
-            debug!("Entry {} has {} reachable modules", entry.display(), reachable.len());
+            debug!("Thread {:?} processing: {}", thread_id, graph.path(entry).display());
+            let entry_reachable = reachable[&entry];
+            debug!(
+                "Entry {} has {} reachable modules",
+                graph.path(entry).display(),
+                entry_reachable
+            );
 
             // Get relative path for better display
-            let rel_entry =
-                entry.strip_prefix(&root).unwrap_or(entry).to_string_lossy().to_string();
+            let rel_entry = graph.rel_path(entry);
+            let limit = cfg.limit_for(Path::new(&rel_entry));
 
             let mut entry_warnings = Vec::new();
 
-            // For each direct import from entry, compute its own reachable set and warn per-import
+            // Warn per direct import whose own reachable set exceeds the threshold
             trace!("Analyzing direct imports from entry");
-            let direct_imports = match imports_for(entry, &import_cache) {
-                Ok(imports) => imports,
-                Err(e) => {
-                    warn!("Error parsing imports for {}: {}", entry.display(), e);
-                    return vec![];
-                }
-            };
-
+            let direct_imports: Vec<_> = graph.runtime_imports(entry).collect();
             debug!("Entry has {} direct imports", direct_imports.len());
 
-            for spec in direct_imports {
-                trace!("Checking import: '{}'", spec.request);
-
-                let resolved =
-                    match resolve(&root, &cfg.tsconfig_paths, entry, &spec.request, &resolve_cache)
-                    {
-                        Ok(Some(r)) => r,
-                        Ok(None) => {
-                            trace!("Could not resolve import: '{}'", spec.request);
-                            continue;
-                        }
-                        Err(e) => {
-                            warn!("Error resolving '{}': {}", spec.request, e);
-                            continue;
-                        }
-                    };
-
-                let rset = match reachable_modules(
-                    &root,
-                    &cfg.tsconfig_paths,
-                    &resolved,
-                    &import_cache,
-                    &resolve_cache,
-                    &reachable_cache,
-                ) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        warn!(
-                            "Error computing reachable modules for {}: {}",
-                            resolved.display(),
-                            e
-                        );
-                        continue;
-                    }
-                };
-
-                if rset.len() >= cfg.threshold {
-                    // Get the resolved path relative to root for display
-                    let resolved_rel = resolved
-                        .strip_prefix(&root)
-                        .unwrap_or(&resolved)
-                        .to_string_lossy()
-                        .to_string();
+            // Exclusive counts need the entry's dominator tree, so they are only
+            // computed for entries with a warning
+            let mut exclusive: Option<Vec<(usize, u64)>> = None;
+            for (idx, edge) in direct_imports.iter().enumerate() {
+                trace!("Checking import: '{}'", edge.request);
 
+                if exceeds(edge.to, limit) {
+                    let exclusive = exclusive.get_or_insert_with(|| {
+                        exclusive_modules(graph, entry, &counts, sizes.as_deref())
+                    });
                     entry_warnings.push(Warning {
-                        import_statement: format!("import '{}'", spec.request),
-                        from_file: rel_entry.clone(),
-                        reachable_unique_modules: rset.len(),
-                        resolved_path: Some(resolved_rel),
+                        resolved_path: Some(graph.rel_path(edge.to)),
+                        ..warning(
+                            format!("import '{}'", edge.request),
+                            &rel_entry,
+                            edge.to,
+                            Some(exclusive[idx]),
+                        )
                     });
                 }
             }
 
             // Also consider the whole entry's graph if desired
-            if reachable.len() >= cfg.threshold {
-                entry_warnings.push(Warning {
-                    import_statement: "Entry file (entire graph)".to_string(),
-                    from_file: rel_entry,
-                    reachable_unique_modules: reachable.len(),
-                    resolved_path: None,
-                });
+            if exceeds(entry, limit) {
+                entry_warnings.push(warning(
+                    "Entry file (entire graph)".to_string(),
+                    &rel_entry,
+                    entry,
+                    None,
+                ));
             }
 
             entry_warnings
@@ -168,12 +161,28 @@ pub fn run_import_bloat_check(mut cfg: Config) -> Result<CheckResult> {
         .collect();
 
     info!("Import bloat check complete. Found {} warnings", warnings.len());
-    debug!(
-        "Cache statistics: imports={}, resolutions={}, reachable={}",
-        import_cache.len(),
-        resolve_cache.len(),
-        reachable_cache.len()
-    );
+    debug!("Statistics: modules={}, reachable counts={}", graph.len(), reachable.len());
+
+    CheckResult { warnings, files_analyzed: graph.len() }
+}
 
-    Ok(CheckResult { warnings, files_analyzed: import_cache.len() })
+/// Updates the result of an earlier run for a rebuilt graph, re-checking only
+/// the `changed_entries` and keeping the warnings of every other entry
+///
+/// `cfg` must be initialized.
+pub fn recheck_entries(
+    graph: &ModuleGraph,
+    cfg: &Config,
+    previous: CheckResult,
+    changed_entries: &[ModuleId],
+) -> CheckResult {
+    let rechecked = check_entries(graph, cfg, changed_entries);
+    let warnings = merge_entry_results(
+        graph,
+        changed_entries,
+        previous.warnings,
+        rechecked.warnings,
+        |warning| &warning.from_file,
+    );
+    CheckResult { warnings, files_analyzed: graph.len() }
 }
@@ -1,52 +1,199 @@
 use anyhow::Result;
-use clap::Parser;
-use log::{debug, info};
-use std::{collections::HashMap, path::PathBuf};
+use clap::{Parser, ValueEnum};
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig, ThresholdOverride, ThresholdOverrides};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug, Clone, Parser)]
+/// Threshold used when neither the command line nor the config file sets one
+pub const DEFAULT_THRESHOLD: usize = 200;
+
+/// Threshold in KB used in size mode when neither the command line nor the
+/// config file sets one
+pub const DEFAULT_SIZE_THRESHOLD_KB: usize = 250;
+
+/// Number of packages listed per warning when neither the command line nor the
+/// config file sets it
+pub const DEFAULT_TOP_PACKAGES: usize = 5;
+
+/// How the size of a module is measured in size mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SizeMetric {
+    /// Bytes on disk
+    Raw,
+    /// Estimated bytes after minification: without comments and whitespace
+    Min,
+    /// Bytes after gzip at its default level (6)
+    Gzip,
+    /// Bytes after brotli at its default quality (11)
+    Brotli,
+}
+
+impl SizeMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeMetric::Raw => "raw",
+            SizeMetric::Min => "min",
+            SizeMetric::Gzip => "gzip",
+            SizeMetric::Brotli => "brotli",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Parser)]
 #[command(name = "import-bloat")]
 #[command(about = "Check for import bloat in JavaScript/TypeScript projects")]
 pub struct Config {
-    /// Root directory of the project (defaults to git root)
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Threshold for number of modules, or KB in size mode [default: 200, or 250 KB]
+    #[arg(long)]
+    pub threshold: Option<usize>,
+
+    /// Weigh imports by the total size of the modules they pull in, measured this
+    /// way, instead of by module count. Thresholds are then in KB
+    #[arg(long, value_enum)]
+    pub size: Option<SizeMetric>,
+
+    /// Threshold for first-party modules (outside node_modules), or KB in size mode
     #[arg(long)]
-    pub root: Option<PathBuf>,
+    pub first_party_threshold: Option<usize>,
 
-    /// Threshold for number of modules
-    #[arg(long, default_value = "200")]
-    pub threshold: usize,
+    /// Threshold for third-party modules (in node_modules), or KB in size mode
+    #[arg(long)]
+    pub third_party_threshold: Option<usize>,
+
+    /// Number of packages to list per warning, largest first, 0 for none [default: 5]
+    #[arg(long)]
+    pub top_packages: Option<usize>,
 
-    /// Glob pattern to filter entry files
+    /// Modules each package collapsed by --collapse-packages counts as [default: 1]
     #[arg(long)]
-    pub entry_glob: Option<String>,
+    pub package_weight: Option<usize>,
 
+    /// Per-package weights of collapsed packages, from the config file
     #[clap(skip)]
-    pub tsconfig_paths: HashMap<String, Vec<String>>,
+    pub package_weights: HashMap<String, usize>,
+
+    #[clap(skip)]
+    pub overrides: ThresholdOverrides,
+}
+
+/// The `[import-bloat]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ImportBloatSection {
+    threshold: Option<usize>,
+    overrides: Vec<ThresholdOverride>,
+    size: Option<SizeMetric>,
+    first_party_threshold: Option<usize>,
+    third_party_threshold: Option<usize>,
+    top_packages: Option<usize>,
+    package_weight: Option<usize>,
+    package_weights: HashMap<String, usize>,
 }
 
 impl Config {
-    /// Initialize the config by resolving the root directory and loading tsconfig paths
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
     pub fn initialize(&mut self) -> Result<()> {
-        // Resolve root directory
-        let root = if let Some(r) = self.root.take() {
-            debug!("Using provided root directory: {:?}", r);
-            r.canonicalize().unwrap_or(r)
-        } else {
-            debug!("No root provided, searching for git root");
-            oxiclean_core::find_git_root()?
-        };
-        info!("Using root directory: {}", root.display());
-
-        // Read tsconfig paths
-        debug!("Reading tsconfig paths");
-        self.tsconfig_paths = oxiclean_core::read_tsconfig_paths(&root);
-        debug!("Found {} tsconfig path aliases", self.tsconfig_paths.len());
+        self.shared.initialize()?;
 
-        self.root = Some(root);
+        let section: ImportBloatSection = self.shared.check_section("import-bloat")?;
+        self.size = self.size.or(section.size);
+        let default = match self.size {
+            Some(_) => DEFAULT_SIZE_THRESHOLD_KB,
+            None => DEFAULT_THRESHOLD,
+        };
+        let threshold = self.threshold.or(section.threshold).unwrap_or(default);
+        debug!(
+            "Using threshold {} {} with {} overrides",
+            threshold,
+            self.unit(),
+            section.overrides.len()
+        );
+        self.overrides = ThresholdOverrides::new(&section.overrides)?;
+        self.threshold = Some(threshold);
+        self.first_party_threshold = self.first_party_threshold.or(section.first_party_threshold);
+        self.third_party_threshold = self.third_party_threshold.or(section.third_party_threshold);
+        self.top_packages = self.top_packages.or(section.top_packages);
+        self.package_weight = self.package_weight.or(section.package_weight);
+        self.package_weights.extend(section.package_weights);
+        debug!(
+            "First-party threshold {:?}, third-party threshold {:?}",
+            self.first_party_threshold, self.third_party_threshold
+        );
         Ok(())
     }
 
     /// Get the root directory, returning None if not initialized
     pub fn root(&self) -> Option<&PathBuf> {
-        self.root.as_ref()
+        self.shared.root()
+    }
+
+    /// The default threshold (before per-path overrides)
+    pub fn threshold(&self) -> usize {
+        self.threshold.unwrap_or(DEFAULT_THRESHOLD)
+    }
+
+    /// The threshold for an entry file, relative to the root
+    pub fn threshold_for(&self, rel_path: &Path) -> usize {
+        self.overrides.threshold_for(rel_path, self.threshold())
+    }
+
+    /// The threshold for an entry file in the unit imports are weighed in:
+    /// modules, or bytes in size mode
+    pub fn limit_for(&self, rel_path: &Path) -> u64 {
+        self.in_weight_unit(self.threshold_for(rel_path))
+    }
+
+    /// The first-party threshold in the unit imports are weighed in, if set
+    pub fn first_party_limit(&self) -> Option<u64> {
+        self.first_party_threshold.map(|threshold| self.in_weight_unit(threshold))
+    }
+
+    /// The third-party threshold in the unit imports are weighed in, if set
+    pub fn third_party_limit(&self) -> Option<u64> {
+        self.third_party_threshold.map(|threshold| self.in_weight_unit(threshold))
+    }
+
+    pub fn top_packages(&self) -> usize {
+        self.top_packages.unwrap_or(DEFAULT_TOP_PACKAGES)
+    }
+
+    /// How many modules a collapsed package counts as
+    pub fn package_weight(&self, package: &str) -> usize {
+        self.package_weights.get(package).copied().or(self.package_weight).unwrap_or(1)
+    }
+
+    fn in_weight_unit(&self, threshold: usize) -> u64 {
+        if self.size.is_some() { threshold as u64 * 1024 } else { threshold as u64 }
+    }
+
+    /// The unit thresholds are given in, for display
+    pub fn unit(&self) -> String {
+        match self.size {
+            Some(metric) => format!("KB {}", metric.label()),
+            None => "modules".to_string(),
+        }
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
     }
 }
@@ -1,59 +1,229 @@
-use anyhow::Result;
-use dashmap::DashMap;
 use log::{debug, trace};
-use std::{
-    collections::HashMap,
-    collections::HashSet,
-    path::{Path, PathBuf},
-};
-
-use oxiclean_core::{Specifier, imports_for, resolve};
+use std::collections::HashMap;
+
+use oxiclean_core::{ModuleGraph, ModuleId};
+
+use crate::{config::Config, types::PackageShare};
+
+/// How many modules each module of the graph counts as, indexed by module: one,
+/// or for a collapsed package its configured weight
+pub(crate) fn module_counts(graph: &ModuleGraph, cfg: &Config) -> Vec<u64> {
+    graph
+        .modules()
+        .map(|id| match graph.package_name(id) {
+            Some(name) if graph.is_collapsed(id) => cfg.package_weight(&name) as u64,
+            _ => 1,
+        })
+        .collect()
+}
 
+/// Number of unique modules reachable (including itself) from each of `entries`
+/// and every module they import directly, each counting as given by `counts`
 pub(crate) fn reachable_modules(
-    root: &Path,
-    tsconfig_paths: &HashMap<String, Vec<String>>,
-    start: &PathBuf,
-    import_cache: &DashMap<PathBuf, Vec<Specifier>>,
-    resolve_cache: &DashMap<(PathBuf, String), Option<PathBuf>>,
-    reachable_cache: &DashMap<PathBuf, HashSet<PathBuf>>,
-) -> Result<HashSet<PathBuf>> {
-    if let Some(cached) = reachable_cache.get(start) {
-        trace!("Cache hit for reachable modules: {}", start.display());
-        return Ok(cached.clone());
-    }
-    trace!("Computing reachable modules from: {}", start.display());
-    let mut visited: HashSet<PathBuf> = HashSet::new();
-    let mut stack: Vec<PathBuf> = vec![start.clone()];
+    graph: &ModuleGraph,
+    entries: &[ModuleId],
+    counts: &[u64],
+) -> HashMap<ModuleId, usize> {
+    let targets = bloat_targets(graph, entries);
+    debug!("Computing reachable modules for {} modules", targets.len());
+    graph
+        .reachable_weights(&targets, |id| counts[id.index()])
+        .into_iter()
+        .map(|(id, count)| (id, count as usize))
+        .collect()
+}
+
+/// Total weight of the modules reachable (including itself) from each of
+/// `entries` and every module they import directly, given each module's
+/// weight, such as its size in bytes
+pub(crate) fn reachable_weights(
+    graph: &ModuleGraph,
+    entries: &[ModuleId],
+    weights: &[u64],
+) -> HashMap<ModuleId, u64> {
+    let targets = bloat_targets(graph, entries);
+    debug!("Computing reachable weights for {} modules", targets.len());
+    graph.reachable_weights(&targets, |id| weights[id.index()])
+}
 
-    while let Some(cur) = stack.pop() {
-        if visited.contains(&cur) {
-            continue;
+/// The `top` packages with the most modules reachable from `start`, or the
+/// most bytes when `sizes` gives the size of every module, largest first.
+/// `packages` holds the package of every module, None for first-party ones.
+pub(crate) fn package_breakdown(
+    graph: &ModuleGraph,
+    start: ModuleId,
+    packages: &[Option<String>],
+    counts: &[u64],
+    sizes: Option<&[u64]>,
+    top: usize,
+) -> Vec<PackageShare> {
+    if top == 0 {
+        return Vec::new();
+    }
+    let mut by_name: HashMap<&str, (usize, u64)> = HashMap::new();
+    for id in graph.reachable(start) {
+        if let Some(name) = &packages[id.index()] {
+            let share = by_name.entry(name).or_default();
+            share.0 += counts[id.index()] as usize;
+            share.1 += sizes.map_or(0, |sizes| sizes[id.index()]);
         }
-        visited.insert(cur.clone());
-        trace!("Visiting module: {}", cur.display());
-
-        let specs = imports_for(&cur, import_cache).unwrap_or_default();
-        trace!("Module has {} imports", specs.len());
-
-        for s in specs {
-            if let Some(next) = resolve(root, tsconfig_paths, &cur, &s.request, resolve_cache)?
-                && !visited.contains(&next)
-            {
-                trace!("Adding to stack: {}", next.display());
-                stack.push(next);
+    }
+    let mut shares: Vec<PackageShare> = by_name
+        .into_iter()
+        .map(|(name, (modules, bytes))| PackageShare {
+            name: name.to_string(),
+            modules,
+            bytes: sizes.map(|_| bytes),
+        })
+        .collect();
+    shares.sort_by(|a, b| {
+        let weight = |s: &PackageShare| s.bytes.unwrap_or(s.modules as u64);
+        weight(b).cmp(&weight(a)).then_with(|| a.name.cmp(&b.name))
+    });
+    shares.truncate(top);
+    shares
+}
+
+/// The given entries and every module they import directly
+fn bloat_targets(graph: &ModuleGraph, entries: &[ModuleId]) -> Vec<ModuleId> {
+    let mut targets: Vec<ModuleId> = entries.to_vec();
+    for &entry in entries {
+        targets.extend(graph.runtime_imports(entry).map(|edge| edge.to));
+    }
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+/// For each runtime import of `entry`, in source order, the number of modules
+/// that would no longer be reachable from the entry if only that import were
+/// removed, its exclusive share of the entry's graph, each counting as given by
+/// `counts`, and their total size when `sizes` gives the size of every module
+/// (0 otherwise).
+///
+/// Each import becomes a node of its own between the entry and the imported
+/// module, and its exclusive modules are those it dominates: every route from
+/// the entry to them passes through it. Modules shared with another import are
+/// not counted for either, nor is a module imported twice by the entry.
+/// Dominators are computed with the iterative algorithm of Cooper, Harvey and
+/// Kennedy over the entry's reachable subgraph.
+pub(crate) fn exclusive_modules(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    counts: &[u64],
+    sizes: Option<&[u64]>,
+) -> Vec<(usize, u64)> {
+    let imports: Vec<ModuleId> = graph.runtime_imports(entry).map(|edge| edge.to).collect();
+
+    // Node 0 is the entry, 1..=imports.len() the imports, then the other modules
+    let mut modules: Vec<ModuleId> =
+        graph.reachable(entry).into_iter().filter(|&id| id != entry).collect();
+    modules.sort();
+    let first_module = imports.len() + 1;
+    let mut node_of: HashMap<ModuleId, usize> =
+        modules.iter().enumerate().map(|(i, &id)| (id, first_module + i)).collect();
+    node_of.insert(entry, 0);
+
+    let n = first_module + modules.len();
+    let mut succ: Vec<Vec<usize>> = vec![Vec::new(); n];
+    succ[0] = (1..first_module).collect();
+    for (i, to) in imports.iter().enumerate() {
+        succ[i + 1].push(node_of[to]);
+    }
+    for (i, &id) in modules.iter().enumerate() {
+        succ[first_module + i] = graph.runtime_imports(id).map(|edge| node_of[&edge.to]).collect();
+    }
+
+    // Reverse postorder of an iterative depth-first search from the entry
+    let mut order = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+    let mut stack = vec![(0, 0)];
+    visited[0] = true;
+    while let Some((node, child)) = stack.pop() {
+        if let Some(&next) = succ[node].get(child) {
+            stack.push((node, child + 1));
+            if !visited[next] {
+                visited[next] = true;
+                stack.push((next, 0));
             }
+        } else {
+            order.push(node);
+        }
+    }
+    order.reverse();
+    let mut rank = vec![0; n];
+    for (r, &node) in order.iter().enumerate() {
+        rank[node] = r;
+    }
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (node, targets) in succ.iter().enumerate() {
+        for &to in targets {
+            preds[to].push(node);
         }
     }
 
-    debug!("Computed {} reachable modules from {}", visited.len(), start.display());
-    reachable_cache.insert(start.clone(), visited.clone());
-    Ok(visited)
+    const UNDEFINED: usize = usize::MAX;
+    let mut idom = vec![UNDEFINED; n];
+    idom[0] = 0;
+    let mut changed = true;
+    let mut rounds = 0;
+    while changed {
+        changed = false;
+        rounds += 1;
+        for &node in &order[1..] {
+            let mut new_idom = UNDEFINED;
+            for &pred in &preds[node] {
+                if idom[pred] == UNDEFINED {
+                    continue;
+                }
+                new_idom = if new_idom == UNDEFINED {
+                    pred
+                } else {
+                    // Walk both up the dominator tree to their common ancestor
+                    let (mut a, mut b) = (pred, new_idom);
+                    while a != b {
+                        while rank[a] > rank[b] {
+                            a = idom[a];
+                        }
+                        while rank[b] > rank[a] {
+                            b = idom[b];
+                        }
+                    }
+                    a
+                };
+            }
+            if idom[node] != new_idom {
+                idom[node] = new_idom;
+                changed = true;
+            }
+        }
+    }
+    trace!("Dominators of {} nodes converged after {} rounds", n, rounds);
+
+    // Dominator subtree sizes, counting modules only, children before parents
+    let mut dominated: Vec<(usize, u64)> = vec![(0, 0); n];
+    for &node in order[1..].iter().rev() {
+        if node >= first_module {
+            let id = modules[node - first_module];
+            dominated[node].0 += counts[id.index()] as usize;
+            dominated[node].1 += sizes.map_or(0, |sizes| sizes[id.index()]);
+        }
+        let (count, size) = dominated[node];
+        dominated[idom[node]].0 += count;
+        dominated[idom[node]].1 += size;
+    }
+    dominated[1..first_module].to_vec()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use clap::Parser;
+    use oxiclean_core::{PackageBoundaries, ResolveOptions};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
     use tempfile::TempDir;
 
     fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
@@ -65,38 +235,29 @@ mod tests {
         file_path
     }
 
+    fn build_graph(root: &Path, entry: &Path) -> ModuleGraph {
+        ModuleGraph::build(root, &ResolveOptions::default(), &[entry.to_path_buf()]).unwrap()
+    }
+
+    /// Every module counting as one
+    fn ones(graph: &ModuleGraph) -> Vec<u64> {
+        vec![1; graph.len()]
+    }
+
     #[test]
     fn test_reachable_modules_simple() {
         let temp_dir = TempDir::new().unwrap();
         let root = temp_dir.path();
 
         let entry = create_test_file(root, "src/index.js", "import './a'; import './b';");
-        let a = create_test_file(root, "src/a.js", "// a");
-        let b = create_test_file(root, "src/b.js", "// b");
+        create_test_file(root, "src/a.js", "// a");
+        create_test_file(root, "src/b.js", "// b");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+        let graph = build_graph(root, &entry);
 
-        let reachable = reachable_modules(
-            root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
-
-        assert_eq!(reachable.len(), 3); // entry, a, b
-        // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
-        let reachable_canonical: std::collections::HashSet<_> =
-            reachable.iter().map(|p| p.canonicalize().unwrap_or_else(|_| p.clone())).collect();
-        assert!(
-            reachable_canonical.contains(&entry.canonicalize().unwrap_or_else(|_| entry.clone()))
-        );
-        assert!(reachable_canonical.contains(&a.canonicalize().unwrap_or_else(|_| a.clone())));
-        assert!(reachable_canonical.contains(&b.canonicalize().unwrap_or_else(|_| b.clone())));
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 3); // entry, a, b
     }
 
     #[test]
@@ -105,34 +266,66 @@ mod tests {
         let root = temp_dir.path();
 
         let entry = create_test_file(root, "src/index.js", "import './a';");
-        let a = create_test_file(root, "src/a.js", "import './b';");
-        let b = create_test_file(root, "src/b.js", "import './c';");
-        let c = create_test_file(root, "src/c.js", "// c");
+        create_test_file(root, "src/a.js", "import './b';");
+        create_test_file(root, "src/b.js", "import './c';");
+        create_test_file(root, "src/c.js", "// c");
+
+        let graph = build_graph(root, &entry);
+
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 4); // entry, a, b, c
+
+        let a = graph.imports(graph.entries()[0])[0].to;
+        assert_eq!(reachable_modules(&graph, graph.entries(), &ones(&graph))[&a], 3); // a, b, c
+    }
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+    #[test]
+    fn test_reachable_modules_skip_reexports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
 
-        let reachable = reachable_modules(
+        let entry = create_test_file(root, "src/index.ts", "import { a } from './ui';");
+        create_test_file(
             root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
-
-        assert_eq!(reachable.len(), 4); // entry, a, b, c
-        // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
-        let reachable_canonical: std::collections::HashSet<_> =
-            reachable.iter().map(|p| p.canonicalize().unwrap_or_else(|_| p.clone())).collect();
-        assert!(
-            reachable_canonical.contains(&entry.canonicalize().unwrap_or_else(|_| entry.clone()))
+            "src/ui/index.ts",
+            "export * from './a';\nexport { b } from './b';\nexport type { T } from './types';",
         );
-        assert!(reachable_canonical.contains(&a.canonicalize().unwrap_or_else(|_| a.clone())));
-        assert!(reachable_canonical.contains(&b.canonicalize().unwrap_or_else(|_| b.clone())));
-        assert!(reachable_canonical.contains(&c.canonicalize().unwrap_or_else(|_| c.clone())));
+        create_test_file(root, "src/ui/a.ts", "export const a = 1;");
+        create_test_file(root, "src/ui/b.ts", "export const b = 1;");
+        create_test_file(root, "src/ui/types.ts", "export type T = number;");
+
+        // As in check_entries
+        let graph = build_graph(root, &entry).without_re_exports();
+
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 2); // entry, ui
+    }
+
+    #[test]
+    fn test_reachable_modules_of_some_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let first = create_test_file(root, "src/first.js", "import './a';");
+        let second = create_test_file(root, "src/second.js", "import './b';");
+        create_test_file(root, "src/a.js", "import './c';");
+        create_test_file(root, "src/b.js", "// b");
+        create_test_file(root, "src/c.js", "// c");
+
+        let graph = ModuleGraph::build(root, &ResolveOptions::default(), &[first, second]).unwrap();
+        let second = graph.entries()[1];
+
+        let reached = graph.reached_from(&[second]);
+        let reached: Vec<String> =
+            graph.modules().filter(|id| reached[id.index()]).map(|id| graph.rel_path(id)).collect();
+        assert_eq!(reached, vec!["src/second.js", "src/b.js"]);
+
+        // Only the entry and its imports are counted
+        let reachable = reachable_modules(&graph, &[second], &ones(&graph));
+        assert_eq!(reachable.len(), 2);
+        assert_eq!(reachable[&second], 2);
     }
 
     #[test]
@@ -141,71 +334,31 @@ mod tests {
         let root = temp_dir.path();
 
         let entry = create_test_file(root, "src/index.js", "import './a';");
-        let a = create_test_file(root, "src/a.js", "import './b';");
-        let b = create_test_file(root, "src/b.js", "import './a';"); // circular
-
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+        create_test_file(root, "src/a.js", "import './b';");
+        create_test_file(root, "src/b.js", "import './a';"); // circular
 
-        let reachable = reachable_modules(
-            root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
+        let graph = build_graph(root, &entry);
 
         // Should handle circular dependencies without infinite loop
-        assert_eq!(reachable.len(), 3); // entry, a, b
-        // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
-        let reachable_canonical: std::collections::HashSet<_> =
-            reachable.iter().map(|p| p.canonicalize().unwrap_or_else(|_| p.clone())).collect();
-        assert!(
-            reachable_canonical.contains(&entry.canonicalize().unwrap_or_else(|_| entry.clone()))
-        );
-        assert!(reachable_canonical.contains(&a.canonicalize().unwrap_or_else(|_| a.clone())));
-        assert!(reachable_canonical.contains(&b.canonicalize().unwrap_or_else(|_| b.clone())));
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 3); // entry, a, b
     }
 
     #[test]
-    fn test_reachable_modules_cache() {
+    fn test_reachable_modules_covers_entries_and_direct_imports() {
         let temp_dir = TempDir::new().unwrap();
         let root = temp_dir.path();
 
         let entry = create_test_file(root, "src/index.js", "import './a';");
-        let _a = create_test_file(root, "src/a.js", "// a");
+        create_test_file(root, "src/a.js", "import './b';");
+        create_test_file(root, "src/b.js", "// b");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+        let graph = build_graph(root, &entry);
+        let reachable = reachable_modules(&graph, graph.entries(), &ones(&graph));
 
-        // First call
-        let reachable1 = reachable_modules(
-            root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
-
-        // Second call should use cache
-        let reachable2 = reachable_modules(
-            root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
-
-        assert_eq!(reachable1.len(), reachable2.len());
-        assert_eq!(reachable_cache.len(), 1);
+        // Only the entry and a are needed; b is never reported on its own
+        assert_eq!(reachable.len(), 2);
     }
 
     #[test]
@@ -215,22 +368,11 @@ mod tests {
 
         let entry = create_test_file(root, "src/index.js", "// no imports");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+        let graph = build_graph(root, &entry);
 
-        let reachable = reachable_modules(
-            root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
-
-        assert_eq!(reachable.len(), 1); // only the entry itself
-        assert!(reachable.contains(&entry));
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 1); // only the entry itself
     }
 
     #[test]
@@ -240,34 +382,146 @@ mod tests {
 
         // Entry imports a and b, both import c
         let entry = create_test_file(root, "src/index.js", "import './a'; import './b';");
-        let a = create_test_file(root, "src/a.js", "import './c';");
-        let b = create_test_file(root, "src/b.js", "import './c';");
-        let c = create_test_file(root, "src/c.js", "// c");
+        create_test_file(root, "src/a.js", "import './c';");
+        create_test_file(root, "src/b.js", "import './c';");
+        create_test_file(root, "src/c.js", "// c");
+
+        let graph = build_graph(root, &entry);
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let reachable_cache = DashMap::new();
+        // Should only count c once (no duplicates)
+        let reachable =
+            reachable_modules(&graph, graph.entries(), &ones(&graph))[&graph.entries()[0]];
+        assert_eq!(reachable, 4); // entry, a, b, c
+    }
+
+    #[test]
+    fn test_exclusive_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
 
-        let reachable = reachable_modules(
+        // a and b share c; d is a's alone, and e is imported twice by the entry
+        let entry = create_test_file(
             root,
-            &HashMap::new(),
-            &entry,
-            &import_cache,
-            &resolve_cache,
-            &reachable_cache,
-        )
-        .unwrap();
+            "src/index.js",
+            "import './a'; import './b'; import './e'; import('./e');",
+        );
+        create_test_file(root, "src/a.js", "import './c'; import './d';");
+        create_test_file(root, "src/b.js", "import './c';");
+        create_test_file(root, "src/c.js", "import './f';");
+        create_test_file(root, "src/d.js", "// d");
+        create_test_file(root, "src/e.js", "// e");
+        create_test_file(root, "src/f.js", "// f");
+
+        let graph = build_graph(root, &entry);
+        let entry = graph.entries()[0];
+
+        let counts: Vec<usize> = exclusive_modules(&graph, entry, &ones(&graph), None)
+            .into_iter()
+            .map(|(count, _)| count)
+            .collect();
+        assert_eq!(counts, vec![2, 1, 0, 0]); // a+d, b, e twice
+
+        // Sizes of a and d, b, nothing, nothing
+        let sizes: Vec<u64> = graph.modules().map(|id| 1 << id.index()).collect();
+        let a = graph.imports(entry)[0].to;
+        let d = graph.resolved(a, "./d").unwrap();
+        let b = graph.imports(entry)[1].to;
+        let exclusive = exclusive_modules(&graph, entry, &ones(&graph), Some(&sizes));
+        assert_eq!(exclusive[0], (2, sizes[a.index()] + sizes[d.index()]));
+        assert_eq!(exclusive[1], (1, sizes[b.index()]));
+        assert_eq!(exclusive[2], (0, 0));
+
+        let reachable = reachable_weights(&graph, graph.entries(), &sizes);
+        assert_eq!(reachable[&entry], sizes.iter().sum::<u64>());
+
+        assert_eq!(reachable_modules(&graph, graph.entries(), &ones(&graph))[&a], 4); // a, c, d, f
+    }
 
-        // Should only count c once (no duplicates)
-        assert_eq!(reachable.len(), 4); // entry, a, b, c
-        // Normalize paths for comparison (canonicalize can add /private prefix on macOS)
-        let reachable_canonical: std::collections::HashSet<_> =
-            reachable.iter().map(|p| p.canonicalize().unwrap_or_else(|_| p.clone())).collect();
-        assert!(
-            reachable_canonical.contains(&entry.canonicalize().unwrap_or_else(|_| entry.clone()))
+    #[test]
+    fn test_exclusive_modules_circular() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // b is only reached through a, even though it imports a and the entry back
+        let entry = create_test_file(root, "src/index.js", "import './a'; import './c';");
+        create_test_file(root, "src/a.js", "import './b';");
+        create_test_file(root, "src/b.js", "import './a'; import './index';");
+        create_test_file(root, "src/c.js", "import './b';");
+
+        let graph = build_graph(root, &entry);
+        let exclusive = exclusive_modules(&graph, graph.entries()[0], &ones(&graph), None);
+        assert_eq!(exclusive, vec![(0, 0), (1, 0)]); // -, c
+    }
+
+    #[test]
+    fn test_package_breakdown() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(
+            root,
+            "src/index.js",
+            "import './a'; import 'lodash'; import '@scope/ui';",
+        );
+        create_test_file(root, "src/a.js", "import 'lodash';");
+        create_test_file(
+            root,
+            "node_modules/lodash/package.json",
+            r#"{ "name": "lodash", "main": "index.js" }"#,
+        );
+        create_test_file(root, "node_modules/lodash/index.js", "require('./map');");
+        create_test_file(root, "node_modules/lodash/map.js", "module.exports = 1;");
+        create_test_file(
+            root,
+            "node_modules/@scope/ui/package.json",
+            r#"{ "name": "@scope/ui", "main": "index.js" }"#,
         );
-        assert!(reachable_canonical.contains(&a.canonicalize().unwrap_or_else(|_| a.clone())));
-        assert!(reachable_canonical.contains(&b.canonicalize().unwrap_or_else(|_| b.clone())));
-        assert!(reachable_canonical.contains(&c.canonicalize().unwrap_or_else(|_| c.clone())));
+        create_test_file(root, "node_modules/@scope/ui/index.js", "module.exports = 1;");
+
+        let graph = build_graph(root, &entry);
+        let entry = graph.entries()[0];
+        let packages: Vec<Option<String>> =
+            graph.modules().map(|id| graph.package_name(id)).collect();
+
+        let third_party: Vec<u64> = packages.iter().map(|p| p.is_some() as u64).collect();
+        let counts = reachable_weights(&graph, graph.entries(), &third_party);
+        assert_eq!(counts[&entry], 3);
+        assert_eq!(counts[&graph.imports(entry)[0].to], 2);
+
+        let shares = package_breakdown(&graph, entry, &packages, &ones(&graph), None, 5);
+        let shares: Vec<(&str, usize)> =
+            shares.iter().map(|s| (s.name.as_str(), s.modules)).collect();
+        assert_eq!(shares, vec![("lodash", 2), ("@scope/ui", 1)]);
+        assert_eq!(package_breakdown(&graph, entry, &packages, &ones(&graph), None, 1).len(), 1);
+        assert!(package_breakdown(&graph, entry, &packages, &ones(&graph), None, 0).is_empty());
+    }
+
+    #[test]
+    fn test_module_counts_collapsed_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let entry = create_test_file(root, "src/index.js", "import 'lodash'; import 'react';");
+        for name in ["lodash", "react"] {
+            create_test_file(
+                root,
+                &format!("node_modules/{}/package.json", name),
+                &format!(r#"{{ "name": "{}", "main": "index.js" }}"#, name),
+            );
+            create_test_file(root, &format!("node_modules/{}/index.js", name), "require('./x');");
+            create_test_file(root, &format!("node_modules/{}/x.js", name), "");
+        }
+
+        let opts = ResolveOptions {
+            packages: PackageBoundaries { collapse_all: true, ..Default::default() },
+            ..Default::default()
+        };
+        let graph = ModuleGraph::build(root, &opts, &[entry]).unwrap();
+        let mut cfg = Config::try_parse_from(["import-bloat", "--package-weight", "3"]).unwrap();
+        cfg.package_weights.insert("react".to_string(), 40);
+
+        // The entry, lodash as 3 modules and react as 40
+        let counts = module_counts(&graph, &cfg);
+        assert_eq!(reachable_modules(&graph, graph.entries(), &counts)[&graph.entries()[0]], 44);
     }
 }
@@ -1,7 +1,9 @@
 //! Import bloat detection for JavaScript/TypeScript projects.
 //!
 //! This crate analyzes import statements in JS/TS codebases to identify files
-//! that import too many modules, which can lead to large bundle sizes.
+//! that import too many modules, which can lead to large bundle sizes. In size
+//! mode imports are weighed by the raw, estimated minified, gzip or brotli size of the modules they
+//! pull in instead.
 //!
 //! # Examples
 //!
@@ -9,16 +11,27 @@
 //!
 //! ```no_run
 //! use oxiclean_import_bloat::{Config, run_import_bloat_check};
+//! use oxiclean_core::SharedConfig;
 //! use std::io::{BufWriter, Write};
 //!
 //! # fn main() -> anyhow::Result<()> {
-//! let cfg = Config {
-//!     root: Some(std::path::PathBuf::from("/path/to/project")),
-//!     threshold: 200,
-//!     entry_glob: None,
-//!     tsconfig_paths: Default::default(),
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     threshold: Some(200),
+//!     size: None,
+//!     first_party_threshold: None,
+//!     third_party_threshold: Some(50),
+//!     top_packages: None,
+//!     package_weight: None,
+//!     package_weights: Default::default(),
+//!     overrides: Default::default(),
 //! };
 //!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
 //! let result = run_import_bloat_check(cfg.clone())?;
 //!
 //! if !result.warnings.is_empty() {
@@ -28,7 +41,7 @@
 //!         &mut stdout,
 //!         &result.warnings,
 //!         &cfg,
-//!         cfg.threshold,
+//!         cfg.threshold(),
 //!     )?;
 //!     stdout.flush()?;
 //! }
@@ -40,10 +53,11 @@ mod checker;
 mod config;
 mod graph;
 mod reporter;
+mod size;
 mod types;
 
 // Re-export public API
-pub use checker::run_import_bloat_check;
-pub use config::Config;
+pub use checker::{check_entries, check_graph, recheck_entries, run_import_bloat_check};
+pub use config::{Config, SizeMetric};
 pub use reporter::{print_no_bloat_message, print_warnings_tree};
-pub use types::{CheckResult, Warning};
+pub use types::{CheckResult, PackageShare, Warning};
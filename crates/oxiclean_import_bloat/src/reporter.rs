@@ -1,112 +1,13 @@
 use std::{
     collections::HashMap,
-    env,
     io::{self, Write},
-    path::{Path, PathBuf},
 };
 
 use colored::Colorize;
 use log::{debug, trace};
+use oxiclean_core::relativize_to_cwd;
 
-use crate::{config::Config, types::Warning};
-
-/// Relativize a path to the current working directory for clickable links
-fn relativize_to_cwd(root: &Path, relative_to_root: &str) -> String {
-    let cwd = match env::current_dir() {
-        Ok(cwd) => cwd,
-        Err(_) => {
-            debug!("Failed to get current directory");
-            return relative_to_root.to_string();
-        }
-    };
-    trace!("Relativizing '{}' from root {:?} to cwd {:?}", relative_to_root, root, cwd);
-
-    // Reconstruct the absolute path
-    let abs_path = root.join(relative_to_root);
-
-    // Make it relative to cwd
-    match make_relative(&abs_path, &cwd) {
-        Some(rel_path) => {
-            let result = rel_path.to_string_lossy().to_string();
-            trace!("Relativized '{}' to '{}'", relative_to_root, result);
-            result
-        }
-        None => {
-            trace!("Could not relativize '{}', using original", relative_to_root);
-            relative_to_root.to_string()
-        }
-    }
-}
-
-/// Create a relative path from `base` to `target`
-fn make_relative(target: &Path, base: &Path) -> Option<PathBuf> {
-    use std::path::Component;
-
-    let mut target_components = target.components();
-    let mut base_components = base.components();
-
-    let mut common_prefix_len = 0;
-    let mut target_parts = Vec::new();
-    let mut base_parts = Vec::new();
-
-    // Find common prefix
-    loop {
-        match (target_components.next(), base_components.next()) {
-            (Some(t), Some(b)) if t == b => {
-                common_prefix_len += 1;
-            }
-            (Some(t), Some(b)) => {
-                target_parts.push(t);
-                base_parts.push(b);
-                break;
-            }
-            (Some(t), None) => {
-                target_parts.push(t);
-                break;
-            }
-            (None, Some(_)) => {
-                // target is a prefix of base, need to go up
-                return Some(PathBuf::from("."));
-            }
-            (None, None) => {
-                // They are the same
-                return Some(PathBuf::from("."));
-            }
-        }
-    }
-
-    // Collect remaining components
-    target_parts.extend(target_components);
-    base_parts.extend(base_components);
-
-    // If there's no common prefix, we can't make a relative path
-    if common_prefix_len == 0 {
-        // Check if they at least share a root
-        let target_root = target.components().next();
-        let base_root = base.components().next();
-
-        if target_root != base_root {
-            return None;
-        }
-    }
-
-    // Build the relative path: "../" for each remaining base component,
-    // then append all remaining target components
-    let mut result = PathBuf::new();
-    for _ in &base_parts {
-        result.push("..");
-    }
-    for component in target_parts {
-        match component {
-            Component::Normal(p) => result.push(p),
-            Component::CurDir => {}
-            Component::ParentDir => result.push(".."),
-            Component::RootDir | Component::Prefix(_) => {}
-        }
-    }
-
-    if result.as_os_str().is_empty() { Some(PathBuf::from(".")) } else { Some(result) }
-}
+use crate::{config::Config, size::format_size, types::Warning};
 
 pub fn print_no_bloat_message<W: Write>(writer: &mut W, threshold: usize) -> io::Result<()> {
     debug!("No bloat detected");
@@ -129,20 +30,27 @@ pub fn print_warnings_tree<W: Write>(
     }
     debug!("Grouped warnings into {} files", by_file.len());
 
+    let mut split_thresholds = String::new();
+    if let Some(first_party) = cfg.first_party_threshold {
+        split_thresholds.push_str(&format!(", {} first-party", first_party.to_string().yellow()));
+    }
+    if let Some(third_party) = cfg.third_party_threshold {
+        split_thresholds.push_str(&format!(", {} third-party", third_party.to_string().yellow()));
+    }
     writeln!(
         writer,
-        "{} Import bloat detected (threshold: {} modules)\n",
+        "{} Import bloat detected (threshold: {} {}{})\n",
         "⚠".yellow().bold(),
-        threshold.to_string().yellow()
+        threshold.to_string().yellow(),
+        cfg.unit(),
+        split_thresholds
     )?;
 
     // Sort files by their worst warning
     let mut files: Vec<_> = by_file.keys().collect();
     files.sort_by(|a, b| {
-        let max_a =
-            by_file.get(*a).unwrap().iter().map(|w| w.reachable_unique_modules).max().unwrap_or(0);
-        let max_b =
-            by_file.get(*b).unwrap().iter().map(|w| w.reachable_unique_modules).max().unwrap_or(0);
+        let max_a = by_file.get(*a).unwrap().iter().map(|w| w.weight()).max().unwrap_or(0);
+        let max_b = by_file.get(*b).unwrap().iter().map(|w| w.weight()).max().unwrap_or(0);
         max_b.cmp(&max_a)
     });
 
@@ -151,7 +59,7 @@ pub fn print_warnings_tree<W: Write>(
         trace!("Processing file: {} with {} warnings", file, file_warnings.len());
 
         // Relativize the file path to cwd for clickable links
-        let display_path = if let Some(root) = &cfg.root {
+        let display_path = if let Some(root) = cfg.root() {
             relativize_to_cwd(root, file)
         } else {
             file.to_string()
@@ -164,10 +72,15 @@ pub fn print_warnings_tree<W: Write>(
         if let Some(entry) = entry_warning {
             writeln!(
                 writer,
-                "{} ({} modules)",
+                "{} ({} modules{}{})",
                 display_path.blue(),
-                entry.reachable_unique_modules.to_string().red().bold()
+                entry.reachable_unique_modules.to_string().red().bold(),
+                size_suffix(entry, cfg),
+                split_suffix(entry)
             )?;
+            if let Some(packages) = packages_line(entry) {
+                writeln!(writer, "     {}", packages)?;
+            }
         } else {
             writeln!(writer, "{}", display_path.bright_white().bold())?;
         }
@@ -175,8 +88,7 @@ pub fn print_warnings_tree<W: Write>(
         // Sort warnings within this file by module count (descending)
         let mut sorted_file_warnings: Vec<_> =
             file_warnings.iter().filter(|w| !w.import_statement.contains("Entry file")).collect();
-        sorted_file_warnings
-            .sort_by(|a, b| b.reachable_unique_modules.cmp(&a.reachable_unique_modules));
+        sorted_file_warnings.sort_by_key(|w| std::cmp::Reverse(w.weight()));
 
         for (idx, warning) in sorted_file_warnings.iter().enumerate() {
             let is_last = idx == sorted_file_warnings.len() - 1;
@@ -193,11 +105,18 @@ pub fn print_warnings_tree<W: Write>(
 
             writeln!(
                 writer,
-                "{}  {} ({} modules)",
+                "{}  {} ({} modules{}{}{})",
                 prefix.dimmed(),
                 display_import,
-                warning.reachable_unique_modules.to_string().red()
+                warning.reachable_unique_modules.to_string().red(),
+                size_suffix(warning, cfg),
+                split_suffix(warning),
+                exclusive_suffix(warning)
             )?;
+            if let Some(packages) = packages_line(warning) {
+                let continuation = if is_last { " " } else { "│" };
+                writeln!(writer, "{}      {}", continuation.dimmed(), packages)?;
+            }
         }
 
         writeln!(writer)?;
@@ -220,17 +139,31 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
     }
 
     let total_violations = violations.len();
-    let max_bloat = violations.iter().map(|w| w.reachable_unique_modules).max().unwrap_or(0);
+    let max_bloat = violations.iter().max_by_key(|w| w.weight()).copied();
 
     // Get top 5 offenders (sorted by module count, descending)
     let mut top_offenders: Vec<_> = violations.iter().collect();
-    top_offenders.sort_by(|a, b| b.reachable_unique_modules.cmp(&a.reachable_unique_modules));
+    top_offenders.sort_by_key(|w| std::cmp::Reverse(w.weight()));
     top_offenders.truncate(5);
 
     writeln!(writer, "{}", "─".repeat(60).dimmed())?;
     writeln!(writer, "{}", "Summary".bold())?;
     writeln!(writer, "  Total violations: {}", total_violations.to_string().yellow().bold())?;
-    writeln!(writer, "  Maximum bloat: {} modules", max_bloat.to_string().red().bold())?;
+    if let Some(max_bloat) = max_bloat {
+        match max_bloat.reachable_bytes {
+            Some(bytes) => writeln!(
+                writer,
+                "  Maximum bloat: {} {}",
+                format_size(bytes).red().bold(),
+                size_label(cfg)
+            )?,
+            None => writeln!(
+                writer,
+                "  Maximum bloat: {} modules",
+                max_bloat.reachable_unique_modules.to_string().red().bold()
+            )?,
+        }
+    }
 
     if !top_offenders.is_empty() {
         writeln!(writer, "  Top {} offenders:", top_offenders.len().min(5))?;
@@ -243,7 +176,7 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
                 .collect::<Vec<_>>()
                 .join(" ");
 
-            let file_path = if let Some(root) = &cfg.root {
+            let file_path = if let Some(root) = cfg.root() {
                 relativize_to_cwd(root, &warning.from_file)
             } else {
                 warning.from_file.clone()
@@ -251,10 +184,12 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
 
             writeln!(
                 writer,
-                "    {}. {} ({} modules) - {}",
+                "    {}. {} ({} modules{}{}) - {}",
                 idx + 1,
                 display_import,
                 warning.reachable_unique_modules.to_string().red(),
+                size_suffix(warning, cfg),
+                exclusive_suffix(warning),
                 file_path.blue()
             )?;
         }
@@ -263,55 +198,60 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_make_relative_same_dir() {
-        let target = Path::new("/project/src/file.ts");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("file.ts")));
-    }
-
-    #[test]
-    fn test_make_relative_child_dir() {
-        let target = Path::new("/project/src/components/Button.tsx");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("components/Button.tsx")));
+/// ", N exclusive" after the module count of an import, the modules removing
+/// only that import would drop, with their size in size mode
+fn exclusive_suffix(warning: &Warning) -> String {
+    match (warning.exclusive_modules, warning.exclusive_bytes) {
+        (Some(exclusive), Some(bytes)) => format!(
+            ", {} exclusive ({})",
+            exclusive.to_string().yellow(),
+            format_size(bytes).yellow()
+        ),
+        (Some(exclusive), None) => format!(", {} exclusive", exclusive.to_string().yellow()),
+        (None, _) => String::new(),
     }
+}
 
-    #[test]
-    fn test_make_relative_parent_dir() {
-        let target = Path::new("/project/src/file.ts");
-        let base = Path::new("/project/src/components");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../file.ts")));
+/// ", N first-party, M third-party" after a module count, with sizes in size mode
+fn split_suffix(warning: &Warning) -> String {
+    let first_party = warning.first_party_modules().to_string();
+    let third_party = warning.third_party_modules.to_string();
+    match (warning.reachable_bytes, warning.third_party_bytes) {
+        (Some(total), Some(third_party_bytes)) => format!(
+            ", {} first-party ({}), {} third-party ({})",
+            first_party,
+            format_size(total - third_party_bytes),
+            third_party,
+            format_size(third_party_bytes)
+        ),
+        _ => format!(", {} first-party, {} third-party", first_party, third_party),
     }
+}
 
-    #[test]
-    fn test_make_relative_sibling_dir() {
-        let target = Path::new("/project/apps/web/index.ts");
-        let base = Path::new("/project/apps/api");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../web/index.ts")));
+/// The largest packages a warning reaches, with their module counts and sizes
+fn packages_line(warning: &Warning) -> Option<String> {
+    if warning.packages.is_empty() {
+        return None;
     }
+    let packages: Vec<String> = warning
+        .packages
+        .iter()
+        .map(|package| match package.bytes {
+            Some(bytes) => format!("{} {} ({})", package.name, package.modules, format_size(bytes)),
+            None => format!("{} {}", package.name, package.modules),
+        })
+        .collect();
+    Some(format!("{} {}", "packages:".dimmed(), packages.join(", ").dimmed()))
+}
 
-    #[test]
-    fn test_make_relative_same_path() {
-        let target = Path::new("/project/src");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from(".")));
+/// ", 1.20 MB gzip" after a module count, in size mode
+fn size_suffix(warning: &Warning, cfg: &Config) -> String {
+    match warning.reachable_bytes {
+        Some(bytes) => format!(", {} {}", format_size(bytes).red(), size_label(cfg)),
+        None => String::new(),
     }
+}
 
-    #[test]
-    fn test_make_relative_multiple_levels_up() {
-        let target = Path::new("/project/file.ts");
-        let base = Path::new("/project/apps/web/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../../../file.ts")));
-    }
+fn size_label(cfg: &Config) -> &'static str {
+    cfg.size.map_or("", |metric| metric.label())
 }
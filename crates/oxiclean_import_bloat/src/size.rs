@@ -0,0 +1,184 @@
+use brotli::enc::BrotliEncoderParams;
+use flate2::{Compression, write::GzEncoder};
+use log::{debug, trace};
+use rayon::prelude::*;
+use std::{fs, io::Write, path::Path};
+
+use oxiclean_core::{ModuleGraph, RESOLVE_EXTENSIONS, minified_size};
+
+use crate::config::SizeMetric;
+
+/// Size in bytes of every module of the graph for which `measure` is set,
+/// indexed by module, measured with `metric`. Other modules, and files that
+/// can't be read, weigh nothing. A collapsed package weighs as much as all of
+/// its JS/TS files, as which of them its importers load is unknown.
+///
+/// Each file is compressed on its own, so the compressed sizes overestimate a
+/// bundle, which compresses across files, but rank imports the same way.
+pub(crate) fn module_sizes(graph: &ModuleGraph, metric: SizeMetric, measure: &[bool]) -> Vec<u64> {
+    debug!(
+        "Measuring {} sizes of {} modules",
+        metric.label(),
+        measure.iter().filter(|&&m| m).count()
+    );
+    graph
+        .modules()
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|&id| match measure[id.index()] {
+            false => 0,
+            true if graph.is_collapsed(id) => package_size(graph.path(id), metric),
+            true => file_size(graph.path(id), metric),
+        })
+        .collect()
+}
+
+/// Total size of the JS/TS files in a package directory, leaving out type
+/// declarations and the packages installed inside it
+fn package_size(dir: &Path, metric: SizeMetric) -> u64 {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            debug!("Failed to read package directory {:?}", dir);
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if name != "node_modules" {
+                    dirs.push(path);
+                }
+            } else if is_runtime_source(&name) {
+                files.push(path);
+            }
+        }
+    }
+    let size = files.par_iter().map(|file| file_size(file, metric)).sum();
+    trace!("Package {:?} weighs {} bytes {} in {} files", dir, size, metric.label(), files.len());
+    size
+}
+
+/// Whether a file name has a JS/TS extension and is not a type declaration
+fn is_runtime_source(name: &str) -> bool {
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return false;
+    };
+    RESOLVE_EXTENSIONS.contains(&ext) && !stem.ends_with(".d")
+}
+
+fn file_size(path: &Path, metric: SizeMetric) -> u64 {
+    let source = match fs::read(path) {
+        Ok(source) => source,
+        Err(e) => {
+            debug!("Failed to read {:?} for its size: {}", path, e);
+            return 0;
+        }
+    };
+    let size = match metric {
+        SizeMetric::Raw => source.len(),
+        // Other files, such as JSON, aren't minified
+        SizeMetric::Min => match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) if is_runtime_source(name) => {
+                minified_size(path, &String::from_utf8_lossy(&source))
+            }
+            _ => source.len(),
+        },
+        SizeMetric::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            match encoder.write_all(&source).and_then(|_| encoder.finish()) {
+                Ok(compressed) => compressed.len(),
+                Err(_) => source.len(),
+            }
+        }
+        SizeMetric::Brotli => {
+            let mut compressed = Vec::new();
+            let params = BrotliEncoderParams::default();
+            match brotli::BrotliCompress(&mut source.as_slice(), &mut compressed, &params) {
+                Ok(len) => len,
+                Err(_) => source.len(),
+            }
+        }
+    };
+    trace!("{:?} weighs {} bytes {}", path, size, metric.label());
+    size as u64
+}
+
+/// A byte count for display, in B, KB or MB
+pub(crate) fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.2} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::{PackageBoundaries, ResolveOptions};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.js");
+        fs::write(&path, "export const x = 'aaaa';\n".repeat(200)).unwrap();
+
+        let raw = file_size(&path, SizeMetric::Raw);
+        assert_eq!(raw, 5000);
+        assert_eq!(file_size(&path, SizeMetric::Min), 4400);
+        let gzip = file_size(&path, SizeMetric::Gzip);
+        let brotli = file_size(&path, SizeMetric::Brotli);
+        assert!(gzip > 0 && gzip < raw / 10, "gzip {}", gzip);
+        assert!(brotli > 0 && brotli < raw / 10, "brotli {}", brotli);
+        assert_eq!(file_size(&temp_dir.path().join("missing.js"), SizeMetric::Raw), 0);
+    }
+
+    #[test]
+    fn test_collapsed_package_sizes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        };
+
+        let entry = write("src/index.js", "import 'lib'; import './a';");
+        write("src/a.js", "// a");
+        write("node_modules/lib/package.json", r#"{"name": "lib", "main": "index.js"}"#);
+        write("node_modules/lib/index.js", &"x".repeat(100));
+        write("node_modules/lib/esm/index.mjs", &"x".repeat(50));
+        write("node_modules/lib/index.d.ts", &"x".repeat(1000));
+        write("node_modules/lib/README.md", &"x".repeat(1000));
+        write("node_modules/lib/node_modules/dep/index.js", &"x".repeat(1000));
+
+        let opts = ResolveOptions {
+            packages: PackageBoundaries { collapse_all: true, ..Default::default() },
+            ..Default::default()
+        };
+        let graph = ModuleGraph::build(root, &opts, &[entry]).unwrap();
+        let lib = graph.imports(graph.entries()[0])[0].to;
+        assert!(graph.is_collapsed(lib));
+
+        let sizes = module_sizes(&graph, SizeMetric::Raw, &vec![true; graph.len()]);
+        assert_eq!(sizes[lib.index()], 150);
+        let gzip = module_sizes(&graph, SizeMetric::Gzip, &vec![true; graph.len()]);
+        assert!(gzip[lib.index()] > 0);
+
+        // Modules left out are not measured
+        let mut measure = vec![true; graph.len()];
+        measure[lib.index()] = false;
+        assert_eq!(module_sizes(&graph, SizeMetric::Raw, &measure)[lib.index()], 0);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.00 MB");
+    }
+}
@@ -3,10 +3,46 @@ pub struct Warning {
     pub import_statement: String,
     pub from_file: String,
     pub reachable_unique_modules: usize,
+    /// Total size in bytes of the reachable modules, in size mode
+    pub reachable_bytes: Option<u64>,
+    /// Modules that would leave the entry's graph if only this import were removed,
+    /// None for the entry warning
+    pub exclusive_modules: Option<usize>,
+    /// Total size in bytes of the exclusive modules, in size mode
+    pub exclusive_bytes: Option<u64>,
+    /// Reachable modules in node_modules; the rest are first-party
+    pub third_party_modules: usize,
+    /// Total size in bytes of the reachable modules in node_modules, in size mode
+    pub third_party_bytes: Option<u64>,
+    /// The packages with the most reachable modules (or bytes, in size mode),
+    /// largest first
+    pub packages: Vec<PackageShare>,
     /// The resolved file path (with extension) for relative imports, None for non-relative imports
     pub resolved_path: Option<String>,
 }
 
+impl Warning {
+    /// What the warning is measured by: bytes in size mode, modules otherwise
+    pub fn weight(&self) -> u64 {
+        self.reachable_bytes.unwrap_or(self.reachable_unique_modules as u64)
+    }
+
+    /// Reachable modules outside node_modules
+    pub fn first_party_modules(&self) -> usize {
+        self.reachable_unique_modules - self.third_party_modules
+    }
+}
+
+/// One package's share of the modules a warning reaches, counting every
+/// installed copy of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageShare {
+    pub name: String,
+    pub modules: usize,
+    /// Total size in bytes, in size mode
+    pub bytes: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CheckResult {
     pub warnings: Vec<Warning>,
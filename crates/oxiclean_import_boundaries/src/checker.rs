@@ -0,0 +1,184 @@
+use anyhow::Result;
+use log::{debug, info, trace, warn};
+use std::{collections::HashMap, path::Path};
+
+use oxiclean_core::ModuleGraph;
+
+use crate::{
+    config::Config,
+    rules::Targets,
+    types::{CheckResult, Warning},
+};
+
+pub fn run_import_boundaries_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting import boundaries check");
+
+    // Initialize config (resolve root, load tsconfig paths, compile rules)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    check_graph(&graph, &cfg)
+}
+
+/// Runs the import boundaries check as a pass over an already-built module graph,
+/// reporting every import of a project file that breaks one of the rules.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> Result<CheckResult> {
+    if cfg.rules.is_empty() {
+        warn!("No import boundary rules configured; add them to the [import-boundaries] section");
+    }
+
+    // Rules whose patterns use captures are compiled once per distinct capture
+    let mut targets: HashMap<(usize, Vec<String>), Targets> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for from in graph.modules() {
+        if graph.is_installed(from) {
+            continue;
+        }
+        let from_file = graph.rel_path(from);
+
+        for (index, rule) in cfg.rules.iter().enumerate() {
+            if !rule.applies_to(Path::new(&from_file)) {
+                continue;
+            }
+            let captures =
+                if rule.uses_captures() { rule.captures(Path::new(&from_file)) } else { vec![] };
+            let key = (index, captures);
+            if !targets.contains_key(&key) {
+                trace!("Compiling rule {} for captures {:?}", rule.label, key.1);
+                let compiled = rule.targets(&key.1)?;
+                targets.insert(key.clone(), compiled);
+            }
+            let rule_targets = &targets[&key];
+
+            for edge in graph.imports(from) {
+                if cfg.skip_type_imports && !edge.kind.is_runtime() {
+                    continue;
+                }
+                let to_file = graph.rel_path(edge.to);
+                if rule_targets.forbids(Path::new(&to_file)) {
+                    trace!("{}:{} breaks {}", from_file, edge.line, rule.label);
+                    warnings.push(Warning {
+                        rule: index,
+                        from_file: from_file.clone(),
+                        line: edge.line,
+                        request: edge.request.clone(),
+                        to_file,
+                    });
+                }
+            }
+        }
+    }
+    warnings.sort_by(|a, b| {
+        a.rule.cmp(&b.rule).then_with(|| a.from_file.cmp(&b.from_file)).then(a.line.cmp(&b.line))
+    });
+    debug!("Compiled targets for {} rule and capture combinations", targets.len());
+
+    info!("Import boundaries check complete. Found {} violations", warnings.len());
+    Ok(CheckResult { warnings, rules: cfg.rules.len(), files_analyzed: graph.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, skip_type_imports: bool, only_rules: &[&str]) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                include: vec!["**".to_string()],
+                ..Default::default()
+            },
+            skip_type_imports,
+            only_rules: only_rules.iter().map(|r| r.to_string()).collect(),
+            rules: Vec::new(),
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        create_test_file(
+            root,
+            "oxiclean.toml",
+            r#"
+[[import-boundaries.rules]]
+name = "features"
+from = ["src/features/*/**"]
+deny = ["src/features/**"]
+allow = ["src/features/$1/**"]
+message = "Features may not import from another feature"
+
+[[import-boundaries.rules]]
+name = "server"
+from = ["**", "!server/**"]
+to = ["server/**"]
+"#,
+        );
+        create_test_file(
+            root,
+            "src/features/cart/index.ts",
+            "import './list';\nimport '../checkout/api';\nimport type { Db } from '../../../server/db';",
+        );
+        create_test_file(root, "src/features/cart/list.ts", "import '../../lib/format';");
+        create_test_file(root, "src/features/checkout/api.ts", "import '../cart';");
+        create_test_file(root, "src/lib/format.ts", "export const f = 1;");
+        create_test_file(root, "server/db.ts", "import '../src/lib/format';\nexport type Db = {};");
+    }
+
+    fn violations(result: &CheckResult) -> Vec<String> {
+        result
+            .warnings
+            .iter()
+            .map(|w| format!("{} {}:{} -> {}", w.rule, w.from_file, w.line, w.to_file))
+            .collect()
+    }
+
+    #[test]
+    fn test_boundary_violations() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let result = run_import_boundaries_check(config(root, false, &[])).unwrap();
+        assert_eq!(result.rules, 2);
+        assert_eq!(
+            violations(&result),
+            vec![
+                "0 src/features/cart/index.ts:2 -> src/features/checkout/api.ts",
+                "0 src/features/checkout/api.ts:1 -> src/features/cart/index.ts",
+                "1 src/features/cart/index.ts:3 -> server/db.ts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_type_imports_and_rule_selection() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let result = run_import_boundaries_check(config(root, true, &[])).unwrap();
+        assert_eq!(result.warnings.len(), 2);
+
+        let cfg = config(root, false, &["server"]);
+        assert_eq!(cfg.rules.len(), 1);
+        let result = run_import_boundaries_check(cfg).unwrap();
+        assert_eq!(violations(&result), vec!["0 src/features/cart/index.ts:3 -> server/db.ts"]);
+    }
+}
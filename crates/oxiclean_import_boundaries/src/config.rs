@@ -0,0 +1,91 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::rules::{Rule, RuleSpec};
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "import-boundaries")]
+#[command(about = "Check imports against architectural boundary rules from the config file")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Ignore type-only imports, which are erased at runtime
+    #[arg(long)]
+    pub skip_type_imports: bool,
+
+    /// Only check the rules with these names. Repeatable
+    #[arg(long = "rule")]
+    pub only_rules: Vec<String>,
+
+    /// The rules from the config file, set by `initialize`
+    #[clap(skip)]
+    pub rules: Vec<Rule>,
+}
+
+/// The `[import-boundaries]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct BoundariesSection {
+    skip_type_imports: bool,
+    rules: Vec<RuleSpec>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths, and compiling the boundary rules
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+
+        let section: BoundariesSection = self.shared.check_section("import-boundaries")?;
+        self.skip_type_imports |= section.skip_type_imports;
+        let unknown = self
+            .only_rules
+            .iter()
+            .find(|name| !section.rules.iter().any(|r| r.name.as_ref() == Some(*name)));
+        if let Some(name) = unknown {
+            return Err(anyhow!("No import boundary rule named '{}' in the config file", name));
+        }
+        self.rules = section
+            .rules
+            .into_iter()
+            .enumerate()
+            .filter(|(_, spec)| {
+                self.only_rules.is_empty()
+                    || spec.name.as_ref().is_some_and(|name| self.only_rules.contains(name))
+            })
+            .map(|(index, spec)| Rule::new(index, spec))
+            .collect::<Result<_>>()?;
+        debug!(
+            "Using {} boundary rules, type imports {}",
+            self.rules.len(),
+            if self.skip_type_imports { "ignored" } else { "included" }
+        );
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
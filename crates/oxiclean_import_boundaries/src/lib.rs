@@ -0,0 +1,53 @@
+//! Architectural boundary checks for JavaScript/TypeScript projects.
+//!
+//! This crate checks every import of a project against rules from the
+//! `[import-boundaries]` section of the config file, such as "a feature may not
+//! import another feature" or "nothing outside `server/` imports `server/**`",
+//! and reports each import that breaks one with its location.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_import_boundaries::{Config, run_import_boundaries_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     skip_type_imports: false,
+//!     only_rules: vec![],
+//!     rules: vec![],
+//! };
+//!
+//! // Resolve the root and load the rules from oxiclean.json / oxiclean.toml
+//! cfg.initialize()?;
+//! let result = run_import_boundaries_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_import_boundaries::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod rules;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_import_boundaries_check};
+pub use config::Config;
+pub use reporter::{print_no_violations_message, print_warnings_tree};
+pub use rules::{Rule, RuleSpec, Targets};
+pub use types::{CheckResult, Warning};
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::CheckResult};
+
+pub fn print_no_violations_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No import boundary violations detected");
+    if result.rules == 0 {
+        writeln!(
+            writer,
+            "{} No import boundary rules configured. Add rules to the [import-boundaries] section of the config file.",
+            "✓".green().bold()
+        )?;
+    } else {
+        writeln!(
+            writer,
+            "{} No import boundary violations ({} rules checked).",
+            "✓".green().bold(),
+            result.rules
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints the violations grouped by rule, in config file order
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} import boundary violations", result.warnings.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Import boundary violations detected ({} imports)\n",
+        "⚠".yellow().bold(),
+        result.warnings.len().to_string().yellow()
+    )?;
+
+    let mut broken = 0;
+    for (index, rule) in cfg.rules.iter().enumerate() {
+        let violations: Vec<_> = result.warnings.iter().filter(|w| w.rule == index).collect();
+        if violations.is_empty() {
+            continue;
+        }
+        broken += 1;
+
+        writeln!(
+            writer,
+            "{} {}",
+            rule.label.bold(),
+            format!("({} imports)", violations.len()).red()
+        )?;
+        writeln!(writer, "  {}", rule.spec.describe().dimmed())?;
+        for (idx, warning) in violations.iter().enumerate() {
+            let prefix = if idx == violations.len() - 1 { "└──" } else { "├──" };
+            writeln!(
+                writer,
+                "  {} {}  {}  {} {}",
+                prefix.dimmed(),
+                format!("{}:{}", display(&warning.from_file), warning.line).blue(),
+                format!("import '{}'", warning.request).dimmed(),
+                "→".dimmed(),
+                display(&warning.to_file)
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Violations: {}", result.warnings.len().to_string().yellow().bold())?;
+    writeln!(writer, "  Rules broken: {} of {}", broken.to_string().yellow(), result.rules)?;
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use anyhow::{Result, bail};
+use oxiclean_core::GlobMatcher;
+use serde::Deserialize;
+use std::path::Path;
+
+/// A boundary rule as written in the `[import-boundaries]` section of the config file.
+///
+/// An import from a file matching `from` breaks the rule when the imported file
+/// matches `deny` and doesn't match `allow`. Without `deny`, every project file
+/// outside `node_modules` is denied, which turns `allow` into an allowlist.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuleSpec {
+    /// Short name shown in the report
+    pub name: Option<String>,
+    /// Importing files the rule applies to; `!` negates, and empty means every file
+    pub from: Vec<String>,
+    /// Imported files the rule forbids
+    #[serde(alias = "to")]
+    pub deny: Vec<String>,
+    /// Imported files the rule still allows
+    pub allow: Vec<String>,
+    /// Explanation shown with each violation
+    pub message: Option<String>,
+}
+
+/// A rule with its `from` patterns compiled.
+///
+/// `deny` and `allow` patterns may refer to the path segments matched by `*`
+/// segments of the `from` pattern as `$1`, `$2`, ..., so `from = "src/features/*/**"`
+/// with `allow = "src/features/$1/**"` lets a feature import only from itself.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub spec: RuleSpec,
+    /// The rule's name, or its position in the config file
+    pub label: String,
+    from: GlobMatcher,
+    /// Each positive `from` pattern on its own, to find the one that captured
+    from_patterns: Vec<(String, GlobMatcher)>,
+}
+
+/// The compiled `deny` and `allow` patterns of a rule, for one set of captures
+#[derive(Debug, Clone)]
+pub struct Targets {
+    deny: Option<GlobMatcher>,
+    allow: GlobMatcher,
+}
+
+impl Rule {
+    /// Compiles the rule at `index` in the config file
+    pub fn new(index: usize, spec: RuleSpec) -> Result<Self> {
+        let label = spec.name.clone().unwrap_or_else(|| format!("rule {}", index + 1));
+        if spec.deny.is_empty() && spec.allow.is_empty() {
+            bail!("Import boundary rule '{}' needs `deny` or `allow` patterns", label);
+        }
+        let from = GlobMatcher::new(&spec.from)?;
+        let from_patterns = spec
+            .from
+            .iter()
+            .filter(|p| !p.starts_with('!'))
+            .map(|p| Ok((p.trim_start_matches("./").to_string(), GlobMatcher::new(&[p])?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { spec, label, from, from_patterns })
+    }
+
+    /// Whether the rule applies to imports from a file, relative to the root
+    pub fn applies_to(&self, rel_path: &Path) -> bool {
+        self.from.is_match(rel_path)
+    }
+
+    /// The path segments matched by `*` segments of the first `from` pattern that
+    /// matches, up to its first `**`
+    pub fn captures(&self, rel_path: &Path) -> Vec<String> {
+        let path = rel_path.to_string_lossy();
+        let Some((pattern, _)) = self.from_patterns.iter().find(|(_, m)| m.is_included(rel_path))
+        else {
+            return Vec::new();
+        };
+        pattern
+            .split('/')
+            .zip(path.split('/'))
+            .take_while(|(p, _)| *p != "**")
+            .filter(|(p, _)| *p == "*")
+            .map(|(_, segment)| segment.to_string())
+            .collect()
+    }
+
+    /// Whether the `deny` or `allow` patterns refer to captures
+    pub fn uses_captures(&self) -> bool {
+        self.spec.deny.iter().chain(&self.spec.allow).any(|p| p.contains('$'))
+    }
+
+    /// Compiles the `deny` and `allow` patterns with `$N` replaced by `captures`
+    pub fn targets(&self, captures: &[String]) -> Result<Targets> {
+        let expand = |patterns: &[String]| -> Vec<String> {
+            patterns.iter().map(|p| substitute(p, captures)).collect()
+        };
+        let deny = if self.spec.deny.is_empty() {
+            None
+        } else {
+            Some(GlobMatcher::new(&expand(&self.spec.deny))?)
+        };
+        Ok(Targets { deny, allow: GlobMatcher::new(&expand(&self.spec.allow))? })
+    }
+}
+
+impl Targets {
+    /// Whether importing a file, relative to the root, breaks the rule
+    pub fn forbids(&self, rel_path: &Path) -> bool {
+        let denied = match &self.deny {
+            Some(deny) => deny.is_match(rel_path),
+            None => !rel_path.components().any(|c| c.as_os_str() == "node_modules"),
+        };
+        denied && !(self.allow.has_includes() && self.allow.is_match(rel_path))
+    }
+}
+
+impl RuleSpec {
+    /// The rule's message, or a description of its patterns
+    pub fn describe(&self) -> String {
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+        let from = if self.from.is_empty() { "any file".to_string() } else { self.from.join(", ") };
+        match (self.deny.is_empty(), self.allow.is_empty()) {
+            (false, true) => format!("{} may not import {}", from, self.deny.join(", ")),
+            (true, _) => format!("{} may only import {}", from, self.allow.join(", ")),
+            (false, false) => format!(
+                "{} may not import {} except {}",
+                from,
+                self.deny.join(", "),
+                self.allow.join(", ")
+            ),
+        }
+    }
+}
+
+/// Replaces `$1`..`$9` in a pattern with the corresponding capture
+fn substitute(pattern: &str, captures: &[String]) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$'
+            && let Some(index) = chars.peek().and_then(|d| d.to_digit(10))
+            && let Some(capture) = captures.get((index as usize).wrapping_sub(1))
+        {
+            chars.next();
+            out.push_str(capture);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &[&str], deny: &[&str], allow: &[&str]) -> Rule {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Rule::new(
+            0,
+            RuleSpec {
+                from: strings(from),
+                deny: strings(deny),
+                allow: strings(allow),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_captures() {
+        let rule = rule(&["src/features/*/**"], &["src/features/**"], &["src/features/$1/**"]);
+        let from = Path::new("src/features/cart/ui/List.tsx");
+        assert!(rule.applies_to(from));
+        assert_eq!(rule.captures(from), vec!["cart"]);
+        assert!(rule.uses_captures());
+
+        let targets = rule.targets(&rule.captures(from)).unwrap();
+        assert!(!targets.forbids(Path::new("src/features/cart/api.ts")));
+        assert!(targets.forbids(Path::new("src/features/checkout/api.ts")));
+        assert!(!targets.forbids(Path::new("src/lib/format.ts")));
+    }
+
+    #[test]
+    fn test_allowlist_and_negated_from() {
+        let only = rule(&["packages/ui/**"], &[], &["packages/ui/**", "packages/shared/**"]);
+        let targets = only.targets(&[]).unwrap();
+        assert!(targets.forbids(Path::new("packages/app/index.ts")));
+        assert!(!targets.forbids(Path::new("packages/shared/colors.ts")));
+        assert!(!targets.forbids(Path::new("node_modules/react/index.js")));
+
+        let server = rule(&["**", "!server/**"], &["server/**"], &[]);
+        assert!(server.applies_to(Path::new("src/index.ts")));
+        assert!(!server.applies_to(Path::new("server/db.ts")));
+        assert!(server.targets(&[]).unwrap().forbids(Path::new("server/db.ts")));
+    }
+
+    #[test]
+    fn test_rule_needs_targets() {
+        assert!(
+            Rule::new(0, RuleSpec { from: vec!["src/**".to_string()], ..Default::default() })
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,23 @@
+/// An import that breaks a boundary rule
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// Index of the broken rule in the configured rules
+    pub rule: usize,
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// The imported file, relative to the root
+    pub to_file: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Violations, sorted by rule, file and line
+    pub warnings: Vec<Warning>,
+    /// Number of rules checked
+    pub rules: usize,
+    pub files_analyzed: usize,
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use log::{debug, info, trace};
+use std::path::Path;
+
+use oxiclean_core::{Edge, ModuleGraph};
+
+use crate::{
+    config::Config,
+    cycles::{is_cycle, shortest_cycle},
+    types::{CheckResult, CycleHop, Warning},
+};
+
+pub fn run_import_cycles_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting import cycles check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
+
+/// Runs the import cycles check as a pass over an already-built module graph.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    let follows = |edge: &Edge| cfg.include_type_imports || edge.kind.is_runtime();
+    let dag = graph.condensation_with(follows);
+
+    let mut warnings = Vec::new();
+    let mut allowed = 0;
+    let mut too_long = 0;
+
+    for component in 0..dag.len() {
+        if !is_cycle(graph, &dag, component, follows) {
+            continue;
+        }
+        let members = dag.members(component);
+        trace!("Found cycle of {} modules", members.len());
+
+        let mut modules: Vec<(String, _)> =
+            members.iter().map(|&m| (graph.rel_path(m), m)).collect();
+        modules.sort();
+        if modules.iter().all(|(path, _)| cfg.is_allowed(Path::new(path))) {
+            debug!("Skipping allowlisted cycle through {}", modules[0].0);
+            allowed += 1;
+            continue;
+        }
+
+        // Search from modules in path order so the reported loop is stable
+        let by_path: Vec<_> = modules.iter().map(|&(_, m)| m).collect();
+        let hops = shortest_cycle(graph, &dag, &by_path, follows);
+        if cfg.max_length.is_some_and(|max| hops.len() > max) {
+            debug!("Skipping cycle whose shortest loop has {} imports", hops.len());
+            too_long += 1;
+            continue;
+        }
+        let hops = hops
+            .into_iter()
+            .map(|(from, edge)| CycleHop {
+                from_file: graph.rel_path(from),
+                line: edge.line,
+                request: edge.request.clone(),
+                to_file: graph.rel_path(edge.to),
+            })
+            .collect();
+
+        warnings
+            .push(Warning { modules: modules.into_iter().map(|(path, _)| path).collect(), hops });
+    }
+    warnings.sort_by(|a, b| a.modules.cmp(&b.modules));
+
+    info!(
+        "Import cycles check complete. Found {} cycles ({} allowed, {} over max length)",
+        warnings.len(),
+        allowed,
+        too_long
+    );
+
+    CheckResult { warnings, allowed, too_long, files_analyzed: graph.len() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            max_length: None,
+            include_type_imports: false,
+            allow: Vec::new(),
+            allowed: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        // A two-module cycle, a three-module cycle and a type-only cycle
+        create_test_file(root, "src/a.ts", "import './b';");
+        create_test_file(root, "src/b.ts", "import './a';");
+        create_test_file(root, "src/legacy/x.ts", "import './y';");
+        create_test_file(root, "src/legacy/y.ts", "import './z';");
+        create_test_file(root, "src/legacy/z.ts", "import './x';");
+        create_test_file(root, "src/types/p.ts", "import type { Q } from './q';");
+        create_test_file(root, "src/types/q.ts", "import type { P } from './p';");
+    }
+
+    #[test]
+    fn test_reports_cycles_with_import_sites() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let cfg = config(root);
+        let graph = ModuleGraph::from_config(&cfg.shared).unwrap();
+        let result = check_graph(&graph, &cfg);
+
+        assert_eq!(result.warnings.len(), 2);
+        let first = &result.warnings[0];
+        assert_eq!(first.modules, vec!["src/a.ts", "src/b.ts"]);
+        assert_eq!(first.hops.len(), 2);
+        assert_eq!(first.hops[0].from_file, "src/a.ts");
+        assert_eq!(first.hops[0].request, "./b");
+        assert_eq!(first.hops[0].line, 1);
+        assert_eq!(first.hops[1].to_file, "src/a.ts");
+    }
+
+    #[test]
+    fn test_max_length_measures_the_shortest_loop() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        // Five modules in one cycle, with a two-module loop between d and e
+        create_test_file(root, "src/a.ts", "import './b';");
+        create_test_file(root, "src/b.ts", "import './c';");
+        create_test_file(root, "src/c.ts", "import './d';");
+        create_test_file(root, "src/d.ts", "import './e';");
+        create_test_file(root, "src/e.ts", "import './a';\nimport './d';");
+
+        let mut cfg = config(root);
+        cfg.max_length = Some(2);
+        let graph = ModuleGraph::from_config(&cfg.shared).unwrap();
+        let result = check_graph(&graph, &cfg);
+        assert_eq!(result.too_long, 0);
+        assert_eq!(result.warnings.len(), 1);
+        let warning = &result.warnings[0];
+        assert_eq!(warning.modules.len(), 5);
+        let loop_files: Vec<&str> = warning.hops.iter().map(|h| h.from_file.as_str()).collect();
+        assert_eq!(loop_files, vec!["src/d.ts", "src/e.ts"]);
+
+        cfg.max_length = Some(1);
+        let result = check_graph(&graph, &cfg);
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.too_long, 1);
+    }
+
+    #[test]
+    fn test_max_length_allowlist_and_type_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let mut cfg = config(root);
+        cfg.max_length = Some(2);
+        let graph = ModuleGraph::from_config(&cfg.shared).unwrap();
+        let result = check_graph(&graph, &cfg);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.too_long, 1);
+
+        let mut cfg = config(root);
+        cfg.allow = vec!["src/legacy/**".to_string()];
+        cfg.include_type_imports = true;
+        cfg.initialize().unwrap();
+        let result = check_graph(&graph, &cfg);
+        assert_eq!(result.allowed, 1);
+        let cycles: Vec<_> = result.warnings.iter().map(|w| w.modules[0].as_str()).collect();
+        assert_eq!(cycles, vec!["src/a.ts", "src/types/p.ts"]);
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, GlobMatcher, SharedConfig};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "import-cycles")]
+#[command(about = "Check for circular imports in JavaScript/TypeScript projects")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Only report cycles whose shortest loop has at most this many imports
+    /// [default: no limit]
+    #[arg(long)]
+    pub max_length: Option<usize>,
+
+    /// Also follow type-only imports, which are erased at runtime and ignored by default
+    #[arg(long)]
+    pub include_type_imports: bool,
+
+    /// Glob pattern for modules allowed to form cycles; a cycle is ignored when
+    /// all of its modules match. Repeatable
+    #[arg(long = "allow")]
+    pub allow: Vec<String>,
+
+    #[clap(skip)]
+    pub allowed: Option<GlobMatcher>,
+}
+
+/// The `[import-cycles]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CyclesSection {
+    max_length: Option<usize>,
+    include_type_imports: bool,
+    allow: Vec<String>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+
+        let section: CyclesSection = self.shared.check_section("import-cycles")?;
+        self.max_length = self.max_length.or(section.max_length);
+        self.include_type_imports |= section.include_type_imports;
+        // Allowlists from the config file and the command line add up
+        let patterns: Vec<&String> = section.allow.iter().chain(&self.allow).collect();
+        debug!(
+            "Using max length {:?}, {} allow patterns, type imports {}",
+            self.max_length,
+            patterns.len(),
+            if self.include_type_imports { "included" } else { "ignored" }
+        );
+        self.allowed = Some(GlobMatcher::new(&patterns)?);
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    /// Whether a module, relative to the root, is allowed to be part of a cycle
+    pub fn is_allowed(&self, rel_path: &Path) -> bool {
+        self.allowed.as_ref().is_some_and(|allowed| allowed.is_included(rel_path))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
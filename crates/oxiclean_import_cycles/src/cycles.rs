@@ -0,0 +1,189 @@
+use log::trace;
+use std::collections::{HashMap, VecDeque};
+
+use oxiclean_core::{Condensation, Edge, ModuleGraph, ModuleId};
+
+/// Whether a component of the condensation is an import cycle: several
+/// modules, or a single module that imports itself
+pub(crate) fn is_cycle(
+    graph: &ModuleGraph,
+    dag: &Condensation,
+    component: usize,
+    follows: impl Fn(&Edge) -> bool,
+) -> bool {
+    match dag.members(component) {
+        [module] => graph.imports(*module).iter().any(|edge| follows(edge) && edge.to == *module),
+        members => members.len() > 1,
+    }
+}
+
+/// The shortest loop of imports among `members`, which must make up a
+/// component of the condensation. Of equally short loops, the one through the
+/// earliest module of `members` is returned, starting at that module. Each hop
+/// is the importing module and the import.
+pub(crate) fn shortest_cycle<'g>(
+    graph: &'g ModuleGraph,
+    dag: &Condensation,
+    members: &[ModuleId],
+    follows: impl Fn(&Edge) -> bool,
+) -> Vec<(ModuleId, &'g Edge)> {
+    let mut best: Vec<(ModuleId, &'g Edge)> = Vec::new();
+    for &start in members {
+        // Only a strictly shorter loop replaces the best one so far
+        let limit = if best.is_empty() { usize::MAX } else { best.len() - 1 };
+        if let Some(hops) = cycle_through(graph, dag, start, limit, &follows) {
+            best = hops;
+            if best.len() == 1 {
+                break;
+            }
+        }
+    }
+    if best.is_empty() {
+        // Unreachable for components that are cycles
+        trace!("No import cycle among {} modules", members.len());
+    }
+    best
+}
+
+/// The shortest loop of imports from `start` back to itself, staying inside
+/// `start`'s component, if it has at most `limit` hops
+fn cycle_through<'g>(
+    graph: &'g ModuleGraph,
+    dag: &Condensation,
+    start: ModuleId,
+    limit: usize,
+    follows: impl Fn(&Edge) -> bool,
+) -> Option<Vec<(ModuleId, &'g Edge)>> {
+    let component = dag.component_of(start);
+    let mut parent: HashMap<ModuleId, (ModuleId, &'g Edge)> = HashMap::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    // Breadth-first, so a module at `depth` closes a loop of `depth + 1` hops
+    while let Some((module, depth)) = queue.pop_front() {
+        if depth + 1 > limit {
+            break;
+        }
+        for edge in graph.imports(module).iter().filter(|edge| follows(edge)) {
+            if edge.to == start {
+                let mut hops = vec![(module, edge)];
+                let mut at = module;
+                while at != start {
+                    let hop = parent[&at];
+                    at = hop.0;
+                    hops.push(hop);
+                }
+                hops.reverse();
+                return Some(hops);
+            }
+            if dag.component_of(edge.to) == component && !parent.contains_key(&edge.to) {
+                parent.insert(edge.to, (module, edge));
+                queue.push_back((edge.to, depth + 1));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::ResolveOptions;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn build_graph(root: &Path, entry: &Path) -> ModuleGraph {
+        ModuleGraph::build(root, &ResolveOptions::default(), &[entry.to_path_buf()]).unwrap()
+    }
+
+    fn runtime(edge: &Edge) -> bool {
+        edge.kind.is_runtime()
+    }
+
+    #[test]
+    fn test_shortest_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // a -> b -> c -> a, and the shorter a -> b -> a
+        let a = create_test_file(root, "src/a.js", "import './b';");
+        let _b = create_test_file(root, "src/b.js", "import './c';\nimport './a';");
+        let _c = create_test_file(root, "src/c.js", "import './a';");
+
+        let graph = build_graph(root, &a);
+        let dag = graph.condensation();
+        let start = graph.entries()[0];
+        assert!(is_cycle(&graph, &dag, dag.component_of(start), runtime));
+
+        let members = dag.members(dag.component_of(start));
+        let hops = shortest_cycle(&graph, &dag, members, runtime);
+        let sites: Vec<_> =
+            hops.iter().map(|(from, edge)| (graph.rel_path(*from), edge.line)).collect();
+        assert_eq!(sites, vec![("src/a.js".to_string(), 1), ("src/b.js".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_shortest_cycle_away_from_first_module() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // a -> b -> c -> d -> a, with the shorter c -> d -> c
+        let a = create_test_file(root, "src/a.js", "import './b';");
+        let _b = create_test_file(root, "src/b.js", "import './c';");
+        let _c = create_test_file(root, "src/c.js", "import './d';");
+        let _d = create_test_file(root, "src/d.js", "import './a';\nimport './c';");
+
+        let graph = build_graph(root, &a);
+        let dag = graph.condensation();
+        let members = dag.members(dag.component_of(graph.entries()[0]));
+        assert_eq!(members.len(), 4);
+
+        let hops = shortest_cycle(&graph, &dag, members, runtime);
+        let sites: Vec<_> =
+            hops.iter().map(|(from, edge)| (graph.rel_path(*from), edge.line)).collect();
+        assert_eq!(sites, vec![("src/c.js".to_string(), 1), ("src/d.js".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_self_import_is_a_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let a = create_test_file(root, "src/a.js", "import './a';");
+
+        let graph = build_graph(root, &a);
+        let dag = graph.condensation();
+        let start = graph.entries()[0];
+        assert!(is_cycle(&graph, &dag, dag.component_of(start), runtime));
+        assert_eq!(shortest_cycle(&graph, &dag, &[start], runtime).len(), 1);
+    }
+
+    #[test]
+    fn test_type_only_imports_break_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let a = create_test_file(root, "src/a.ts", "import { b } from './b';");
+        let _b = create_test_file(root, "src/b.ts", "import type { A } from './a';");
+
+        let graph = build_graph(root, &a);
+        let start = graph.entries()[0];
+
+        let dag = graph.condensation();
+        assert!(!is_cycle(&graph, &dag, dag.component_of(start), runtime));
+
+        let dag = graph.condensation_with(|_| true);
+        assert!(is_cycle(&graph, &dag, dag.component_of(start), |_| true));
+    }
+}
@@ -0,0 +1,53 @@
+//! Import cycle detection for JavaScript/TypeScript projects.
+//!
+//! This crate finds modules that import each other, directly or through other
+//! modules. Circular imports make evaluation order depend on which module is
+//! loaded first, and can crash at runtime when a binding is used before the
+//! module defining it has finished evaluating (the temporal dead zone).
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_import_cycles::{Config, run_import_cycles_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     max_length: None,
+//!     include_type_imports: false,
+//!     allow: vec!["src/legacy/**".to_string()],
+//!     allowed: None,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_import_cycles_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_import_cycles::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod cycles;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_import_cycles_check};
+pub use config::Config;
+pub use reporter::{print_no_cycles_message, print_warnings_tree};
+pub use types::{CheckResult, CycleHop, Warning};
@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{
+    config::Config,
+    types::{CheckResult, Warning},
+};
+
+pub fn print_no_cycles_message<W: Write>(writer: &mut W, result: &CheckResult) -> io::Result<()> {
+    debug!("No import cycles detected");
+    writeln!(writer, "{} No import cycles detected.{}", "✓".green().bold(), skipped_note(result))?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    let warnings = &result.warnings;
+    debug!("Printing {} import cycles", warnings.len());
+
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Import cycles detected ({} cycles)\n",
+        "⚠".yellow().bold(),
+        warnings.len().to_string().yellow()
+    )?;
+
+    // Largest cycles first; within a size, by path
+    let mut sorted: Vec<&Warning> = warnings.iter().collect();
+    sorted.sort_by_key(|w| std::cmp::Reverse(w.modules.len()));
+
+    for warning in sorted {
+        let loop_note = if warning.hops.len() < warning.modules.len() {
+            format!(", shortest loop: {} imports", warning.hops.len())
+        } else {
+            String::new()
+        };
+        writeln!(
+            writer,
+            "{} ({} modules{})",
+            "cycle".bold(),
+            warning.modules.len().to_string().red().bold(),
+            loop_note
+        )?;
+
+        for (idx, hop) in warning.hops.iter().enumerate() {
+            let arrow = if idx == 0 { " " } else { "→" };
+            writeln!(
+                writer,
+                "  {} {}  {}",
+                arrow.dimmed(),
+                format!("{}:{}", display(&hop.from_file), hop.line).blue(),
+                format!("import '{}'", hop.request).dimmed()
+            )?;
+        }
+        if let Some(last) = warning.hops.last() {
+            writeln!(writer, "  {} {}", "→".dimmed(), display(&last.to_file).blue())?;
+        }
+
+        // Modules of the cycle that the shortest loop doesn't pass through
+        let others: Vec<&String> = warning
+            .modules
+            .iter()
+            .filter(|m| !warning.hops.iter().any(|hop| &hop.from_file == *m))
+            .collect();
+        if !others.is_empty() {
+            writeln!(
+                writer,
+                "  {}",
+                format!("also in this cycle: {} more modules", others.len()).dimmed()
+            )?;
+            for (idx, module) in others.iter().enumerate() {
+                let prefix = if idx == others.len() - 1 { "└──" } else { "├──" };
+                writeln!(writer, "  {}  {}", prefix.dimmed(), display(module))?;
+            }
+        }
+
+        writeln!(writer)?;
+    }
+
+    print_summary(writer, result)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn print_summary<W: Write>(writer: &mut W, result: &CheckResult) -> io::Result<()> {
+    let largest = result.warnings.iter().map(|w| w.modules.len()).max().unwrap_or(0);
+    let modules: usize = result.warnings.iter().map(|w| w.modules.len()).sum();
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Total cycles: {}", result.warnings.len().to_string().yellow().bold())?;
+    writeln!(writer, "  Modules in cycles: {}", modules.to_string().yellow())?;
+    writeln!(
+        writer,
+        "  Largest cycle: {} modules{}",
+        largest.to_string().red().bold(),
+        skipped_note(result)
+    )?;
+    Ok(())
+}
+
+/// Notes cycles that were found but not reported
+fn skipped_note(result: &CheckResult) -> String {
+    let mut skipped = Vec::new();
+    if result.allowed > 0 {
+        skipped.push(format!("{} allowlisted", result.allowed));
+    }
+    if result.too_long > 0 {
+        skipped.push(format!("{} over the max length", result.too_long));
+    }
+    if skipped.is_empty() {
+        String::new()
+    } else {
+        format!(" ({} cycles not reported)", skipped.join(", ")).dimmed().to_string()
+    }
+}
@@ -0,0 +1,32 @@
+/// A set of modules that import each other, directly or indirectly
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// Every module in the cycle, relative to the root and sorted
+    pub modules: Vec<String>,
+    /// The shortest loop of imports in the cycle, starting from the first
+    /// module by path that lies on such a loop
+    pub hops: Vec<CycleHop>,
+}
+
+/// One import along a cycle
+#[derive(Debug, Clone)]
+pub struct CycleHop {
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// The imported file, relative to the root
+    pub to_file: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub warnings: Vec<Warning>,
+    /// Cycles skipped because every module in them is allowlisted
+    pub allowed: usize,
+    /// Cycles skipped because their shortest loop is longer than the max length
+    pub too_long: usize,
+    pub files_analyzed: usize,
+}
@@ -1,15 +1,14 @@
-use anyhow::{Result, anyhow};
-use dashmap::DashMap;
-use log::{debug, info, trace, warn};
+use anyhow::Result;
+use log::{debug, info, trace};
 use rayon::prelude::*;
-use std::{path::PathBuf, sync::Arc, thread};
+use std::{path::Path, thread};
 
-use oxiclean_core::{CollectorConfig, collect_entries};
+use oxiclean_core::{Edge, ModuleGraph, ModuleId, merge_entry_results};
 
 use crate::{
     config::Config,
-    depth::compute_import_depths,
-    types::{CheckResult, Warning},
+    depth::{Hop, ModuleDepths, compute_import_depths},
+    types::{ChainHop, CheckResult, Cycle, Warning},
 };
 
 pub fn run_import_depth_check(mut cfg: Config) -> Result<CheckResult> {
@@ -17,94 +16,59 @@ pub fn run_import_depth_check(mut cfg: Config) -> Result<CheckResult> {
 
     // Initialize config (resolve root, load tsconfig paths)
     cfg.initialize()?;
-    let root = cfg.root()?.clone();
 
-    debug!("Collecting entry files with glob: {:?}", cfg.entry_glob);
-    let collector_cfg = CollectorConfig {
-        root: root.clone(),
-        entry_glob: cfg.entry_glob.clone(),
-        tsconfig_paths: cfg.tsconfig_paths.clone(),
-    };
-
-    let entries = collect_entries(&collector_cfg)?;
-    if entries.is_empty() {
-        warn!("No entry files found under {}", root.display());
-        return Err(anyhow!("No entry files found under {}", root.display()));
-    }
-    info!("Found {} entry files", entries.len());
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    Ok(check_graph(&graph, &cfg))
+}
 
-    // Thread-safe caches using DashMap
-    let import_cache: Arc<DashMap<PathBuf, Vec<oxiclean_core::Specifier>>> =
-        Arc::new(DashMap::new());
-    let resolve_cache: Arc<DashMap<(PathBuf, String), Option<PathBuf>>> = Arc::new(DashMap::new());
-    let depth_cache: Arc<DashMap<PathBuf, usize>> = Arc::new(DashMap::new());
+/// Runs the import depth check as a pass over an already-built module graph.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> CheckResult {
+    check_entries(graph, cfg, graph.entries())
+}
 
-    // Wrap config in Arc for sharing across threads
-    let cfg = Arc::new(cfg);
+/// Runs the import depth check for some of the graph's entries only, such as
+/// the ones watch mode found changed. Depths are computed for the modules these
+/// entries reach rather than the whole graph; cycles are always reported for
+/// the whole graph. Re-exports are not followed.
+///
+/// `cfg` must be initialized.
+pub fn check_entries(graph: &ModuleGraph, cfg: &Config, entries: &[ModuleId]) -> CheckResult {
+    let graph = &graph.without_re_exports();
+    let depths = ModuleDepths::new(graph, entries, cfg.chains());
 
     info!("Processing {} entry files in parallel", entries.len());
 
     // Process entries in parallel using rayon
     let warnings: Vec<Warning> = entries
         .par_iter()
-        .flat_map(|entry| {
+        .flat_map(|&entry| {
             let thread_id = thread::current().id();
-            debug!("Thread {:?} processing: {}", thread_id, entry.display());
-            trace!("Computing import depths for entry: {}", entry.display());
-
-            let cfg = Arc::clone(&cfg);
-            let import_cache = Arc::clone(&import_cache);
-            let resolve_cache = Arc::clone(&resolve_cache);
-            let depth_cache = Arc::clone(&depth_cache);
-
-            let root = match cfg.root() {
-                Ok(r) => r.clone(),
-                Err(e) => {
-                    warn!("Error getting root: {}", e);
-                    return vec![];
-                }
-            };
+            debug!("Thread {:?} processing: {}", thread_id, graph.path(entry).display());
+            trace!("Computing import depths for entry: {}", graph.path(entry).display());
 
             // Get relative path for better display
-            let rel_entry =
-                entry.strip_prefix(&root).unwrap_or(entry).to_string_lossy().to_string();
+            let rel_entry = graph.rel_path(entry);
+            let threshold = cfg.threshold_for(Path::new(&rel_entry));
 
             let mut entry_warnings = Vec::new();
 
             // Compute depths for each direct import from this entry
             trace!("Analyzing direct imports from entry");
-            let import_depths = match compute_import_depths(
-                &root,
-                &cfg.tsconfig_paths,
-                entry,
-                &import_cache,
-                &resolve_cache,
-                &depth_cache,
-            ) {
-                Ok(depths) => depths,
-                Err(e) => {
-                    warn!("Error computing import depths for {}: {}", entry.display(), e);
-                    return vec![];
-                }
-            };
-
+            let import_depths = compute_import_depths(graph, entry, &depths);
             debug!("Entry has {} direct imports", import_depths.len());
 
-            for (import_request, resolved_path, depth) in import_depths {
-                trace!("Import '{}' has depth {}", import_request, depth);
-
-                if depth >= cfg.threshold {
-                    // Get the resolved path relative to root for display
-                    let resolved_rel = resolved_path
-                        .as_ref()
-                        .and_then(|p| p.strip_prefix(&root).ok())
-                        .map(|p| p.to_string_lossy().to_string());
+            for (edge, depth) in import_depths {
+                trace!("Import '{}' has depth {}", edge.request, depth);
 
+                if depth >= threshold {
                     entry_warnings.push(Warning {
-                        import_statement: format!("import '{}'", import_request),
+                        import_statement: format!("import '{}'", edge.request),
                         from_file: rel_entry.clone(),
                         depth,
-                        resolved_path: resolved_rel,
+                        resolved_path: graph.rel_path(edge.to),
+                        chains: chains_through(graph, &depths, entry, edge),
                     });
                 }
             }
@@ -113,13 +77,63 @@ pub fn run_import_depth_check(mut cfg: Config) -> Result<CheckResult> {
         })
         .collect();
 
-    info!("Import depth check complete. Found {} warnings", warnings.len());
-    debug!(
-        "Cache statistics: imports={}, resolutions={}, depths={}",
-        import_cache.len(),
-        resolve_cache.len(),
-        depth_cache.len()
+    let mut cycles: Vec<Cycle> = depths
+        .cycles()
+        .map(|members| {
+            let mut modules: Vec<String> = members.iter().map(|&m| graph.rel_path(m)).collect();
+            modules.sort();
+            Cycle { modules }
+        })
+        .collect();
+    cycles.sort_by(|a, b| a.modules.cmp(&b.modules));
+
+    info!(
+        "Import depth check complete. Found {} warnings and {} cycles",
+        warnings.len(),
+        cycles.len()
+    );
+
+    CheckResult { warnings, cycles, files_analyzed: graph.len() }
+}
+
+/// Updates the result of an earlier run for a rebuilt graph, re-checking only
+/// the `changed_entries` and keeping the warnings of every other entry
+///
+/// `cfg` must be initialized.
+pub fn recheck_entries(
+    graph: &ModuleGraph,
+    cfg: &Config,
+    previous: CheckResult,
+    changed_entries: &[ModuleId],
+) -> CheckResult {
+    let rechecked = check_entries(graph, cfg, changed_entries);
+    let warnings = merge_entry_results(
+        graph,
+        changed_entries,
+        previous.warnings,
+        rechecked.warnings,
+        |warning| &warning.from_file,
     );
+    CheckResult { warnings, ..rechecked }
+}
 
-    Ok(CheckResult { warnings, files_analyzed: import_cache.len() })
+/// The longest chains starting with the import `edge` from `entry`
+fn chains_through(
+    graph: &ModuleGraph,
+    depths: &ModuleDepths,
+    entry: ModuleId,
+    edge: &Edge,
+) -> Vec<Vec<ChainHop>> {
+    let to_hop = |&(from, edge): &Hop| ChainHop {
+        from_file: graph.rel_path(from),
+        line: edge.line,
+        request: edge.request.clone(),
+        to_file: graph.rel_path(edge.to),
+    };
+    let first = to_hop(&(entry, edge));
+    depths
+        .chains(graph, edge.to)
+        .iter()
+        .map(|chain| std::iter::once(first.clone()).chain(chain.iter().map(to_hop)).collect())
+        .collect()
 }
@@ -1,54 +1,93 @@
 use anyhow::{Result, anyhow};
 use clap::Parser;
-use log::{debug, info};
-use std::{collections::HashMap, path::PathBuf};
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig, ThresholdOverride, ThresholdOverrides};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Parser)]
+/// Threshold used when neither the command line nor the config file sets one
+pub const DEFAULT_THRESHOLD: usize = 10;
+
+/// Number of chains shown per warning when neither the command line nor the
+/// config file sets one
+pub const DEFAULT_CHAINS: usize = 1;
+
+#[derive(Debug, Clone, Default, Parser)]
 #[command(name = "import-depth")]
 #[command(about = "Check for excessive import depth in JavaScript/TypeScript projects")]
 pub struct Config {
-    /// Root directory of the project (defaults to git root)
-    #[arg(long)]
-    pub root: Option<PathBuf>,
+    #[command(flatten)]
+    pub shared: SharedConfig,
 
-    /// Threshold for maximum import depth
-    #[arg(long, default_value = "10")]
-    pub threshold: usize,
+    /// Threshold for maximum import depth [default: 10]
+    #[arg(long)]
+    pub threshold: Option<usize>,
 
-    /// Glob pattern to filter entry files
+    /// Number of distinct longest import chains to show per warning [default: 1]
     #[arg(long)]
-    pub entry_glob: Option<String>,
+    pub chains: Option<usize>,
 
     #[clap(skip)]
-    pub tsconfig_paths: HashMap<String, Vec<String>>,
+    pub overrides: ThresholdOverrides,
+}
+
+/// The `[import-depth]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ImportDepthSection {
+    threshold: Option<usize>,
+    overrides: Vec<ThresholdOverride>,
+    chains: Option<usize>,
 }
 
 impl Config {
-    /// Initialize the config by resolving the root directory and loading tsconfig paths
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
     pub fn initialize(&mut self) -> Result<()> {
-        // Resolve root directory
-        let root = if let Some(r) = self.root.take() {
-            debug!("Using provided root directory: {:?}", r);
-            r.canonicalize().unwrap_or(r)
-        } else {
-            debug!("No root provided, searching for git root");
-            oxiclean_core::find_git_root()?
-        };
-        info!("Using root directory: {}", root.display());
-
-        // Read tsconfig paths
-        debug!("Reading tsconfig paths");
-        self.tsconfig_paths = oxiclean_core::read_tsconfig_paths(&root);
-        debug!("Found {} tsconfig path aliases", self.tsconfig_paths.len());
-
-        self.root = Some(root);
+        self.shared.initialize()?;
+
+        let section: ImportDepthSection = self.shared.check_section("import-depth")?;
+        let threshold = self.threshold.or(section.threshold).unwrap_or(DEFAULT_THRESHOLD);
+        debug!("Using threshold {} with {} overrides", threshold, section.overrides.len());
+        self.overrides = ThresholdOverrides::new(&section.overrides)?;
+        self.threshold = Some(threshold);
+        self.chains = Some(self.chains.or(section.chains).unwrap_or(DEFAULT_CHAINS));
         Ok(())
     }
 
     /// Get the root directory, returning an error if not initialized
     pub fn root(&self) -> Result<&PathBuf> {
-        self.root
-            .as_ref()
+        self.shared
+            .root()
             .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
     }
+
+    /// The default threshold (before per-path overrides)
+    pub fn threshold(&self) -> usize {
+        self.threshold.unwrap_or(DEFAULT_THRESHOLD)
+    }
+
+    /// Number of distinct longest chains to show per warning
+    pub fn chains(&self) -> usize {
+        self.chains.unwrap_or(DEFAULT_CHAINS)
+    }
+
+    /// The threshold for an entry file, relative to the root
+    pub fn threshold_for(&self, rel_path: &Path) -> usize {
+        self.overrides.threshold_for(rel_path, self.threshold())
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
 }
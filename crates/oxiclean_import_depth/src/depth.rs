@@ -1,210 +1,202 @@
-use anyhow::Result;
-use dashmap::DashMap;
-use log::{debug, trace, warn};
-use std::{
-    collections::{HashMap, HashSet},
-    path::{Path, PathBuf},
-};
+use log::{debug, trace};
+use std::collections::{HashMap, VecDeque};
 
-use oxiclean_core::{imports_for, resolve};
+use oxiclean_core::{Condensation, Edge, ModuleGraph, ModuleId};
 
-/// Computes the maximum depth of the import tree starting from a given file.
-///
-/// This function performs a depth-first search through the import graph,
-/// tracking the maximum depth encountered. It uses memoization to avoid
-/// recomputing depths for files that have already been analyzed.
-///
-/// # Arguments
-/// * `root` - The root directory of the project
-/// * `tsconfig_paths` - TypeScript path mappings from tsconfig.json
-/// * `start` - The file to start the depth analysis from
-/// * `import_cache` - Cache of parsed imports for each file
-/// * `resolve_cache` - Cache of resolved import paths
-/// * `depth_cache` - Cache of computed depths for each file
-///
-/// # Returns
-/// The maximum depth of imports from the starting file
-pub fn compute_depth(
-    root: &Path,
-    tsconfig_paths: &HashMap<String, Vec<String>>,
-    start: &Path,
-    import_cache: &DashMap<PathBuf, Vec<oxiclean_core::Specifier>>,
-    resolve_cache: &DashMap<(PathBuf, String), Option<PathBuf>>,
-    depth_cache: &DashMap<PathBuf, usize>,
-) -> Result<usize> {
-    let mut visiting = HashSet::new();
-    compute_depth_internal(
-        root,
-        tsconfig_paths,
-        start,
-        import_cache,
-        resolve_cache,
-        depth_cache,
-        &mut visiting,
-    )
+/// One of the longest chains below a component: its length in components and
+/// the successor component and rank it continues through
+#[derive(Debug, Clone, Copy)]
+struct Ranked {
+    len: usize,
+    next: Option<(u32, u32)>,
 }
 
-/// Internal depth computation with cycle detection
-fn compute_depth_internal(
-    root: &Path,
-    tsconfig_paths: &HashMap<String, Vec<String>>,
-    start: &Path,
-    import_cache: &DashMap<PathBuf, Vec<oxiclean_core::Specifier>>,
-    resolve_cache: &DashMap<(PathBuf, String), Option<PathBuf>>,
-    depth_cache: &DashMap<PathBuf, usize>,
-    visiting: &mut HashSet<PathBuf>,
-) -> Result<usize> {
-    if let Some(cached) = depth_cache.get(start) {
-        trace!("Cache hit for depth: {}", start.display());
-        return Ok(*cached);
-    }
-
-    // Detect cycles - if we're already visiting this file, return 0 to break the cycle
-    if visiting.contains(start) {
-        trace!("Cycle detected at: {}", start.display());
-        return Ok(0);
-    }
+/// One hop of an import chain: the importing module and the import it follows
+pub type Hop<'g> = (ModuleId, &'g Edge);
 
-    trace!("Computing depth from: {}", start.display());
+/// Import depths for the modules of a graph reachable from some start modules,
+/// measured over its condensation.
+///
+/// Each import cycle is collapsed into a single component and counts as one
+/// level, so a module's depth is the longest chain of components below it.
+/// Modules in the same cycle share a depth, and the result doesn't depend on
+/// the order in which entries are processed.
+///
+/// For each component the `chains` longest distinct chains are kept, so the
+/// imports that make up the depth can be reported.
+#[derive(Debug)]
+pub struct ModuleDepths {
+    dag: Condensation,
+    ranked: Vec<Vec<Ranked>>,
+}
 
-    // Mark this file as being visited
-    visiting.insert(start.to_path_buf());
+impl ModuleDepths {
+    /// Computes the longest chains below each component reachable from
+    /// `starts`, the only modules whose depth and chains may be asked for,
+    /// visiting dependencies before their importers
+    pub fn new(graph: &ModuleGraph, starts: &[ModuleId], chains: usize) -> Self {
+        let dag = graph.condensation();
+        let mut wanted = vec![false; dag.len()];
+        for &start in starts {
+            wanted[dag.component_of(start)] = true;
+        }
+        // Importers come after their dependencies, so one pass down marks them all
+        for c in (0..dag.len()).rev() {
+            if wanted[c] {
+                for s in dag.successors(c) {
+                    wanted[s] = true;
+                }
+            }
+        }
 
-    // Get imports for this file
-    let specs = match imports_for(start, import_cache) {
-        Ok(specs) => specs,
-        Err(e) => {
-            warn!("Error parsing imports for {}: {}", start.display(), e);
-            visiting.remove(start);
-            depth_cache.insert(start.to_path_buf(), 0);
-            return Ok(0);
+        let chains = chains.max(1);
+        let mut ranked: Vec<Vec<Ranked>> = Vec::with_capacity(dag.len());
+        for (c, &wanted) in wanted.iter().enumerate() {
+            if !wanted {
+                ranked.push(Vec::new());
+                continue;
+            }
+            let mut candidates: Vec<Ranked> = dag
+                .successors(c)
+                .flat_map(|s| {
+                    ranked[s].iter().enumerate().map(move |(rank, r)| Ranked {
+                        len: r.len + 1,
+                        next: Some((s as u32, rank as u32)),
+                    })
+                })
+                .collect();
+            if candidates.is_empty() {
+                candidates.push(Ranked { len: 0, next: None });
+            }
+            // Longest first; ties broken by successor so the chosen chains are stable
+            candidates.sort_by(|a, b| b.len.cmp(&a.len).then(a.next.cmp(&b.next)));
+            candidates.truncate(chains);
+            ranked.push(candidates);
         }
-    };
+        debug!(
+            "Computed depths for {} of {} components, maximum {}",
+            ranked.iter().filter(|r| !r.is_empty()).count(),
+            dag.len(),
+            ranked.iter().filter_map(|r| r.first()).map(|r| r.len).max().unwrap_or(0)
+        );
+        Self { dag, ranked }
+    }
 
-    if specs.is_empty() {
-        trace!("No imports found in {}", start.display());
-        visiting.remove(start);
-        depth_cache.insert(start.to_path_buf(), 0);
-        return Ok(0);
+    /// The maximum depth of the import tree below a module
+    pub fn depth(&self, id: ModuleId) -> usize {
+        self.ranked[self.dag.component_of(id)][0].len
     }
 
-    let mut max_depth = 0;
+    /// Every import cycle in the graph, each as its members sorted by id
+    pub fn cycles(&self) -> impl Iterator<Item = &[ModuleId]> {
+        (0..self.dag.len()).map(|c| self.dag.members(c)).filter(|m| m.len() > 1)
+    }
 
-    for spec in specs {
-        trace!("Checking import: '{}'", spec.request);
+    /// The longest distinct import chains starting at a module, longest first.
+    ///
+    /// A chain that passes through a cycle includes the imports needed to get
+    /// from the module it entered the cycle at to the one it leaves from, so
+    /// chains can have more hops than their depth.
+    pub fn chains<'g>(&self, graph: &'g ModuleGraph, start: ModuleId) -> Vec<Vec<Hop<'g>>> {
+        let first = self.dag.component_of(start);
+        (0..self.ranked[first].len())
+            .map(|rank| {
+                let mut hops = Vec::new();
+                let (mut module, mut component, mut rank) = (start, first, rank);
+                while let Some((next, next_rank)) = self.ranked[component][rank].next {
+                    let next = next as usize;
+                    hops.extend(self.hops_between(graph, module, next));
+                    module = hops.last().map(|(_, edge)| edge.to).unwrap_or(module);
+                    component = next;
+                    rank = next_rank as usize;
+                }
+                hops
+            })
+            .collect()
+    }
 
-        let resolved = match resolve(root, tsconfig_paths, start, &spec.request, resolve_cache) {
-            Ok(Some(r)) => r,
-            Ok(None) => {
-                trace!("Could not resolve import: '{}'", spec.request);
-                continue;
+    /// The shortest run of imports from `start` to any module in component
+    /// `target`, staying inside `start`'s component until the last hop
+    fn hops_between<'g>(
+        &self,
+        graph: &'g ModuleGraph,
+        start: ModuleId,
+        target: usize,
+    ) -> Vec<Hop<'g>> {
+        let own = self.dag.component_of(start);
+        let mut parent: HashMap<ModuleId, Hop<'g>> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(module) = queue.pop_front() {
+            for edge in graph.runtime_imports(module) {
+                let component = self.dag.component_of(edge.to);
+                if component == target {
+                    let mut hops = vec![(module, edge)];
+                    let mut at = module;
+                    while at != start {
+                        let hop = parent[&at];
+                        at = hop.0;
+                        hops.push(hop);
+                    }
+                    hops.reverse();
+                    return hops;
+                }
+                if component == own && edge.to != start && !parent.contains_key(&edge.to) {
+                    parent.insert(edge.to, (module, edge));
+                    queue.push_back(edge.to);
+                }
             }
-            Err(e) => {
-                warn!("Error resolving '{}': {}", spec.request, e);
-                continue;
-            }
-        };
-
-        // Recursively compute depth for the resolved import
-        let child_depth = compute_depth_internal(
-            root,
-            tsconfig_paths,
-            &resolved,
-            import_cache,
-            resolve_cache,
-            depth_cache,
-            visiting,
-        )?;
-
-        // The depth through this import is 1 + the child's depth
-        let depth_through_import = 1 + child_depth;
-        if depth_through_import > max_depth {
-            max_depth = depth_through_import;
         }
-    }
-
-    // Remove from visiting set before returning
-    visiting.remove(start);
 
-    debug!("Computed depth {} from {}", max_depth, start.display());
-    depth_cache.insert(start.to_path_buf(), max_depth);
-    Ok(max_depth)
+        // Unreachable: the successor was recorded from an import of this component
+        trace!("No import from {} into component {}", graph.path(start).display(), target);
+        Vec::new()
+    }
 }
 
-/// Computes the depth for each direct import from a file.
+/// Computes the depth for each direct import from a module.
 ///
-/// This function returns a map of import specifiers to their depths,
-/// allowing the caller to identify which specific imports have excessive depth.
+/// This allows the caller to identify which specific imports have excessive depth.
 ///
 /// # Returns
-/// A vector of tuples containing (import_request, resolved_path, depth)
-pub fn compute_import_depths(
-    root: &Path,
-    tsconfig_paths: &HashMap<String, Vec<String>>,
-    from_file: &Path,
-    import_cache: &DashMap<PathBuf, Vec<oxiclean_core::Specifier>>,
-    resolve_cache: &DashMap<(PathBuf, String), Option<PathBuf>>,
-    depth_cache: &DashMap<PathBuf, usize>,
-) -> Result<Vec<(String, Option<PathBuf>, usize)>> {
-    trace!("Computing import depths from: {}", from_file.display());
-
-    let specs = match imports_for(from_file, import_cache) {
-        Ok(specs) => specs,
-        Err(e) => {
-            warn!("Error parsing imports for {}: {}", from_file.display(), e);
-            return Ok(vec![]);
-        }
-    };
+/// A vector of tuples containing (import, depth)
+pub fn compute_import_depths<'g>(
+    graph: &'g ModuleGraph,
+    from: ModuleId,
+    depths: &ModuleDepths,
+) -> Vec<(&'g Edge, usize)> {
+    trace!("Computing import depths from: {}", graph.path(from).display());
 
     let mut results = Vec::new();
 
-    for spec in specs {
-        trace!("Analyzing import: '{}'", spec.request);
-
-        let resolved = match resolve(root, tsconfig_paths, from_file, &spec.request, resolve_cache)
-        {
-            Ok(Some(r)) => r,
-            Ok(None) => {
-                trace!("Could not resolve import: '{}'", spec.request);
-                continue;
-            }
-            Err(e) => {
-                warn!("Error resolving '{}': {}", spec.request, e);
-                continue;
-            }
-        };
-
-        // Compute depth for this resolved import (uses cycle detection internally)
-        let depth = compute_depth(
-            root,
-            tsconfig_paths,
-            &resolved,
-            import_cache,
-            resolve_cache,
-            depth_cache,
-        )?;
+    for edge in graph.runtime_imports(from) {
+        trace!("Analyzing import: '{}'", edge.request);
 
         // The depth of importing this module is 1 + its internal depth
-        let import_depth = 1 + depth;
+        let import_depth = 1 + depths.depth(edge.to);
 
         trace!(
             "Import '{}' resolved to {} has depth {}",
-            spec.request,
-            resolved.display(),
+            edge.request,
+            graph.path(edge.to).display(),
             import_depth
         );
 
-        results.push((spec.request.clone(), Some(resolved), import_depth));
+        results.push((edge, import_depth));
     }
 
-    debug!("Computed {} import depths from {}", results.len(), from_file.display());
-    Ok(results)
+    debug!("Computed {} import depths from {}", results.len(), graph.path(from).display());
+    results
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use oxiclean_core::{PackageBoundaries, ResolveOptions};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
     use tempfile::TempDir;
 
     fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
@@ -216,6 +208,10 @@ mod tests {
         file_path
     }
 
+    fn build_graph(root: &Path, entry: &Path) -> ModuleGraph {
+        ModuleGraph::build(root, &ResolveOptions::default(), &[entry.to_path_buf()]).unwrap()
+    }
+
     #[test]
     fn test_compute_depth_no_imports() {
         let temp_dir = TempDir::new().unwrap();
@@ -223,19 +219,10 @@ mod tests {
 
         let file = create_test_file(root, "src/file.js", "// no imports");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depth = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depth = depths.depth(graph.entries()[0]);
 
         assert_eq!(depth, 0);
     }
@@ -248,23 +235,38 @@ mod tests {
         let file = create_test_file(root, "src/file.js", "import './a';");
         let _a = create_test_file(root, "src/a.js", "// a");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depth = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depth = depths.depth(graph.entries()[0]);
 
         assert_eq!(depth, 1); // file -> a
     }
 
+    #[test]
+    fn test_compute_depth_skips_reexports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let file = create_test_file(root, "src/file.ts", "import { c } from './ui';");
+        let _ui = create_test_file(
+            root,
+            "src/ui/index.ts",
+            "export * from './a';\nexport type { T } from './types';",
+        );
+        let _a = create_test_file(root, "src/ui/a.ts", "export { c } from './c';");
+        let _c = create_test_file(root, "src/ui/c.ts", "export const c = 1;");
+        let _types = create_test_file(root, "src/ui/types.ts", "import './a';\nexport type T = 1;");
+
+        // As in check_entries
+        let graph = build_graph(root, &file).without_re_exports();
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
+
+        let depth = depths.depth(graph.entries()[0]);
+
+        assert_eq!(depth, 1); // file -> ui
+    }
+
     #[test]
     fn test_compute_depth_nested() {
         let temp_dir = TempDir::new().unwrap();
@@ -275,23 +277,42 @@ mod tests {
         let _b = create_test_file(root, "src/b.js", "import './c';");
         let _c = create_test_file(root, "src/c.js", "// c");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depth = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depth = depths.depth(graph.entries()[0]);
 
         assert_eq!(depth, 3); // file -> a -> b -> c
     }
 
+    #[test]
+    fn test_compute_depth_collapsed_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let file = create_test_file(root, "src/file.js", "import './a';");
+        create_test_file(root, "src/a.js", "import 'chart';");
+        create_test_file(
+            root,
+            "node_modules/chart/package.json",
+            r#"{ "name": "chart", "main": "index.js" }"#,
+        );
+        create_test_file(root, "node_modules/chart/index.js", "require('./core');");
+        create_test_file(root, "node_modules/chart/core.js", "require('./util');");
+        create_test_file(root, "node_modules/chart/util.js", "");
+
+        let graph = build_graph(root, &file);
+        assert_eq!(ModuleDepths::new(&graph, graph.entries(), 1).depth(graph.entries()[0]), 4);
+
+        // The package is a single leaf
+        let opts = ResolveOptions {
+            packages: PackageBoundaries { collapse_all: true, ..Default::default() },
+            ..Default::default()
+        };
+        let graph = ModuleGraph::build(root, &opts, &[file]).unwrap();
+        assert_eq!(ModuleDepths::new(&graph, graph.entries(), 1).depth(graph.entries()[0]), 2); // file -> a -> chart
+    }
+
     #[test]
     fn test_compute_depth_circular() {
         let temp_dir = TempDir::new().unwrap();
@@ -301,60 +322,67 @@ mod tests {
         let _a = create_test_file(root, "src/a.js", "import './b';");
         let _b = create_test_file(root, "src/b.js", "import './a';"); // circular
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depth = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
-
-        // Should handle circular dependencies - depth should be finite
-        assert!(depth < 10); // Should not be infinite
+        let depth = depths.depth(graph.entries()[0]);
+
+        // The a <-> b cycle counts as a single level: file -> {a, b}
+        assert_eq!(depth, 1);
+        let cycles: Vec<_> = depths.cycles().collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 2);
     }
 
     #[test]
-    fn test_compute_depth_cache() {
+    fn test_compute_depth_cycle_members_share_depth() {
         let temp_dir = TempDir::new().unwrap();
         let root = temp_dir.path();
 
-        let file = create_test_file(root, "src/file.js", "import './a';");
-        let _a = create_test_file(root, "src/a.js", "// a");
+        // Entering the a -> b -> c -> a cycle at different points must not change
+        // the depth of its members
+        let file = create_test_file(root, "src/file.js", "import './a'; import './c';");
+        let a = create_test_file(root, "src/a.js", "import './b';");
+        let b = create_test_file(root, "src/b.js", "import './c';");
+        let c = create_test_file(root, "src/c.js", "import './a'; import './d';");
+        let _d = create_test_file(root, "src/d.js", "import './e';");
+        let _e = create_test_file(root, "src/e.js", "// e");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        // First call
-        let depth1 = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
-
-        // Second call should use cache
-        let depth2 = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
-
-        assert_eq!(depth1, depth2);
-        assert_eq!(depth_cache.len(), 2); // file and a
+        for member in [&a, &b, &c] {
+            assert_eq!(depths.depth(graph.id_of(member).unwrap()), 2); // {a, b, c} -> d -> e
+        }
+        assert_eq!(depths.depth(graph.entries()[0]), 3);
+    }
+
+    #[test]
+    fn test_depths_of_some_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let first = create_test_file(root, "src/first.js", "import './a';");
+        let second = create_test_file(root, "src/second.js", "import './b';");
+        create_test_file(root, "src/a.js", "import './c';");
+        create_test_file(root, "src/b.js", "import './c';");
+        create_test_file(root, "src/c.js", "import './d';");
+        create_test_file(root, "src/d.js", "// d");
+
+        let graph =
+            ModuleGraph::build(root, &ResolveOptions::default(), &[first.clone(), second]).unwrap();
+        let all = ModuleDepths::new(&graph, graph.entries(), 1);
+        let some = ModuleDepths::new(&graph, &graph.entries()[1..], 1);
+
+        let second = graph.entries()[1];
+        assert_eq!(some.depth(second), 3); // second -> b -> c -> d
+        assert_eq!(some.depth(second), all.depth(second));
+        let targets = |depths: &ModuleDepths| -> Vec<Vec<ModuleId>> {
+            let chains = depths.chains(&graph, second);
+            chains.iter().map(|chain| chain.iter().map(|(_, edge)| edge.to).collect()).collect()
+        };
+        assert_eq!(targets(&some), targets(&all));
+        assert_eq!(all.depth(graph.id_of(&first).unwrap()), 3);
     }
 
     #[test]
@@ -368,19 +396,10 @@ mod tests {
         let _b = create_test_file(root, "src/b.js", "import './c';");
         let _c = create_test_file(root, "src/c.js", "// c");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depth = compute_depth(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depth = depths.depth(graph.entries()[0]);
 
         // Should return max depth (through b -> c = 2)
         assert_eq!(depth, 2);
@@ -396,25 +415,16 @@ mod tests {
         let _b = create_test_file(root, "src/b.js", "import './c';");
         let _c = create_test_file(root, "src/c.js", "// c");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depths = compute_import_depths(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depths = compute_import_depths(&graph, graph.entries()[0], &depths);
 
         assert_eq!(depths.len(), 2);
 
         // Find depths for each import
-        let a_depth = depths.iter().find(|(req, _, _)| req == "./a").map(|(_, _, d)| *d);
-        let b_depth = depths.iter().find(|(req, _, _)| req == "./b").map(|(_, _, d)| *d);
+        let a_depth = depths.iter().find(|(e, _)| e.request == "./a").map(|(_, d)| *d);
+        let b_depth = depths.iter().find(|(e, _)| e.request == "./b").map(|(_, d)| *d);
 
         assert_eq!(a_depth, Some(1)); // a has no imports
         assert_eq!(b_depth, Some(2)); // b -> c
@@ -427,20 +437,79 @@ mod tests {
 
         let file = create_test_file(root, "src/file.js", "// no imports");
 
-        let import_cache = DashMap::new();
-        let resolve_cache = DashMap::new();
-        let depth_cache = DashMap::new();
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
 
-        let depths = compute_import_depths(
-            root,
-            &HashMap::new(),
-            &file,
-            &import_cache,
-            &resolve_cache,
-            &depth_cache,
-        )
-        .unwrap();
+        let depths = compute_import_depths(&graph, graph.entries()[0], &depths);
 
         assert_eq!(depths.len(), 0);
     }
+
+    #[test]
+    fn test_chains_follow_longest_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let file = create_test_file(root, "src/file.js", "import './a';\nimport './b';");
+        let _a = create_test_file(root, "src/a.js", "// a");
+        let _b = create_test_file(root, "src/b.js", "\nimport './c';");
+        let _c = create_test_file(root, "src/c.js", "// c");
+
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
+
+        let chains = depths.chains(&graph, graph.entries()[0]);
+        assert_eq!(chains.len(), 1);
+        let hops: Vec<_> = chains[0]
+            .iter()
+            .map(|(from, edge)| (graph.rel_path(*from), edge.line, edge.request.as_str()))
+            .collect();
+        assert_eq!(
+            hops,
+            vec![("src/file.js".to_string(), 2, "./b"), ("src/b.js".to_string(), 2, "./c")]
+        );
+    }
+
+    #[test]
+    fn test_chains_top_n_are_distinct() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // Three maximal chains of lengths 3, 2 and 1
+        let file =
+            create_test_file(root, "src/file.js", "import './a'; import './b'; import './c';");
+        let _a = create_test_file(root, "src/a.js", "import './d';");
+        let _d = create_test_file(root, "src/d.js", "import './e';");
+        let _e = create_test_file(root, "src/e.js", "// e");
+        let _b = create_test_file(root, "src/b.js", "import './e';");
+        let _c = create_test_file(root, "src/c.js", "// c");
+
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 5);
+
+        let chains = depths.chains(&graph, graph.entries()[0]);
+        let lens: Vec<_> = chains.iter().map(|c| c.len()).collect();
+        assert_eq!(lens, vec![3, 2, 1]);
+        assert_eq!(depths.depth(graph.entries()[0]), 3);
+    }
+
+    #[test]
+    fn test_chains_walk_through_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // Entering the a <-> b cycle at a, but only b leads on to c
+        let file = create_test_file(root, "src/file.js", "import './a';");
+        let _a = create_test_file(root, "src/a.js", "import './b';");
+        let _b = create_test_file(root, "src/b.js", "import './a'; import './c';");
+        let _c = create_test_file(root, "src/c.js", "// c");
+
+        let graph = build_graph(root, &file);
+        let depths = ModuleDepths::new(&graph, graph.entries(), 1);
+
+        let chains = depths.chains(&graph, graph.entries()[0]);
+        let requests: Vec<_> = chains[0].iter().map(|(_, e)| e.request.as_str()).collect();
+        assert_eq!(requests, vec!["./a", "./b", "./c"]);
+        assert_eq!(depths.depth(graph.entries()[0]), 2);
+    }
 }
@@ -10,16 +10,22 @@
 //!
 //! ```no_run
 //! use oxiclean_import_depth::{Config, run_import_depth_check};
+//! use oxiclean_core::SharedConfig;
 //! use std::io::{BufWriter, Write};
 //!
 //! # fn main() -> anyhow::Result<()> {
-//! let cfg = Config {
-//!     root: Some(std::path::PathBuf::from("/path/to/project")),
-//!     threshold: 10,
-//!     entry_glob: None,
-//!     tsconfig_paths: Default::default(),
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     threshold: Some(10),
+//!     chains: None,
+//!     overrides: Default::default(),
 //! };
 //!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
 //! let result = run_import_depth_check(cfg.clone())?;
 //!
 //! if !result.warnings.is_empty() {
@@ -29,7 +35,7 @@
 //!         &mut stdout,
 //!         &result.warnings,
 //!         &cfg,
-//!         cfg.threshold,
+//!         cfg.threshold(),
 //!     )?;
 //!     stdout.flush()?;
 //! }
@@ -44,7 +50,7 @@ mod reporter;
 mod types;
 
 // Re-export public API
-pub use checker::run_import_depth_check;
+pub use checker::{check_entries, check_graph, recheck_entries, run_import_depth_check};
 pub use config::Config;
-pub use reporter::{print_no_depth_issues_message, print_warnings_tree};
-pub use types::{CheckResult, Warning};
+pub use reporter::{print_cycles, print_no_depth_issues_message, print_warnings_tree};
+pub use types::{ChainHop, CheckResult, Cycle, Warning};
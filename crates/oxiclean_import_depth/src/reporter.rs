@@ -1,112 +1,16 @@
 use std::{
     collections::HashMap,
-    env,
     io::{self, Write},
-    path::{Path, PathBuf},
 };
 
 use colored::Colorize;
 use log::{debug, trace};
+use oxiclean_core::relativize_to_cwd;
 
-use crate::{config::Config, types::Warning};
-
-/// Relativize a path to the current working directory for clickable links
-fn relativize_to_cwd(root: &Path, relative_to_root: &str) -> String {
-    let cwd = match env::current_dir() {
-        Ok(cwd) => cwd,
-        Err(_) => {
-            debug!("Failed to get current directory");
-            return relative_to_root.to_string();
-        }
-    };
-    trace!("Relativizing '{}' from root {:?} to cwd {:?}", relative_to_root, root, cwd);
-
-    // Reconstruct the absolute path
-    let abs_path = root.join(relative_to_root);
-
-    // Make it relative to cwd
-    match make_relative(&abs_path, &cwd) {
-        Some(rel_path) => {
-            let result = rel_path.to_string_lossy().to_string();
-            trace!("Relativized '{}' to '{}'", relative_to_root, result);
-            result
-        }
-        None => {
-            trace!("Could not relativize '{}', using original", relative_to_root);
-            relative_to_root.to_string()
-        }
-    }
-}
-
-/// Create a relative path from `base` to `target`
-fn make_relative(target: &Path, base: &Path) -> Option<PathBuf> {
-    use std::path::Component;
-
-    let mut target_components = target.components();
-    let mut base_components = base.components();
-
-    let mut common_prefix_len = 0;
-    let mut target_parts = Vec::new();
-    let mut base_parts = Vec::new();
-
-    // Find common prefix
-    loop {
-        match (target_components.next(), base_components.next()) {
-            (Some(t), Some(b)) if t == b => {
-                common_prefix_len += 1;
-            }
-            (Some(t), Some(b)) => {
-                target_parts.push(t);
-                base_parts.push(b);
-                break;
-            }
-            (Some(t), None) => {
-                target_parts.push(t);
-                break;
-            }
-            (None, Some(_)) => {
-                // target is a prefix of base, need to go up
-                return Some(PathBuf::from("."));
-            }
-            (None, None) => {
-                // They are the same
-                return Some(PathBuf::from("."));
-            }
-        }
-    }
-
-    // Collect remaining components
-    target_parts.extend(target_components);
-    base_parts.extend(base_components);
-
-    // If there's no common prefix, we can't make a relative path
-    if common_prefix_len == 0 {
-        // Check if they at least share a root
-        let target_root = target.components().next();
-        let base_root = base.components().next();
-
-        if target_root != base_root {
-            return None;
-        }
-    }
-
-    // Build the relative path: "../" for each remaining base component,
-    // then append all remaining target components
-    let mut result = PathBuf::new();
-    for _ in &base_parts {
-        result.push("..");
-    }
-    for component in target_parts {
-        match component {
-            Component::Normal(p) => result.push(p),
-            Component::CurDir => {}
-            Component::ParentDir => result.push(".."),
-            Component::RootDir | Component::Prefix(_) => {}
-        }
-    }
-
-    if result.as_os_str().is_empty() { Some(PathBuf::from(".")) } else { Some(result) }
-}
+use crate::{
+    config::Config,
+    types::{ChainHop, Cycle, Warning},
+};
 
 pub fn print_no_depth_issues_message<W: Write>(writer: &mut W, threshold: usize) -> io::Result<()> {
     debug!("No depth issues detected");
@@ -146,7 +50,7 @@ pub fn print_warnings_tree<W: Write>(
     files.sort_by(|a, b| {
         let max_a = by_file.get(*a).unwrap().iter().map(|w| w.depth).max().unwrap_or(0);
         let max_b = by_file.get(*b).unwrap().iter().map(|w| w.depth).max().unwrap_or(0);
-        max_b.cmp(&max_a)
+        max_b.cmp(&max_a).then_with(|| a.cmp(b))
     });
 
     for file in files {
@@ -164,7 +68,7 @@ pub fn print_warnings_tree<W: Write>(
 
         // Sort warnings within this file by depth (descending)
         let mut sorted_file_warnings: Vec<_> = file_warnings.iter().collect();
-        sorted_file_warnings.sort_by(|a, b| b.depth.cmp(&a.depth));
+        sorted_file_warnings.sort_by_key(|w| std::cmp::Reverse(w.depth));
 
         for (idx, warning) in sorted_file_warnings.iter().enumerate() {
             let is_last = idx == sorted_file_warnings.len() - 1;
@@ -186,6 +90,19 @@ pub fn print_warnings_tree<W: Write>(
                 display_import,
                 warning.depth.to_string().red().bold()
             )?;
+
+            let indent = if is_last { "     " } else { "│    " };
+            for (chain_idx, chain) in warning.chains.iter().enumerate() {
+                if warning.chains.len() > 1 {
+                    writeln!(
+                        writer,
+                        "{}{}",
+                        indent.dimmed(),
+                        format!("chain {} ({} imports)", chain_idx + 1, chain.len()).dimmed()
+                    )?;
+                }
+                print_chain(writer, chain, cfg, indent)?;
+            }
         }
 
         writeln!(writer)?;
@@ -198,6 +115,70 @@ pub fn print_warnings_tree<W: Write>(
     Ok(())
 }
 
+/// Prints a chain one import per line, as `file:line  import` followed by the
+/// file it leads to
+fn print_chain<W: Write>(
+    writer: &mut W,
+    chain: &[ChainHop],
+    cfg: &Config,
+    indent: &str,
+) -> io::Result<()> {
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    for (idx, hop) in chain.iter().enumerate() {
+        let arrow = if idx == 0 { " " } else { "→" };
+        writeln!(
+            writer,
+            "{}{} {}  {}",
+            indent.dimmed(),
+            arrow.dimmed(),
+            format!("{}:{}", display(&hop.from_file), hop.line).blue(),
+            format!("import '{}'", hop.request).dimmed()
+        )?;
+    }
+    if let Some(last) = chain.last() {
+        writeln!(writer, "{}{} {}", indent.dimmed(), "→".dimmed(), display(&last.to_file).blue())?;
+    }
+    Ok(())
+}
+
+/// Prints the import cycles found in the graph. Each cycle is measured as a
+/// single depth level, so long cycles can hide depth from the main report.
+pub fn print_cycles<W: Write>(writer: &mut W, cycles: &[Cycle], cfg: &Config) -> io::Result<()> {
+    if cycles.is_empty() {
+        return Ok(());
+    }
+    debug!("Printing {} import cycles", cycles.len());
+
+    writeln!(
+        writer,
+        "{} {} import cycles (each counted as one depth level)\n",
+        "↻".yellow().bold(),
+        cycles.len().to_string().yellow()
+    )?;
+    for (idx, cycle) in cycles.iter().enumerate() {
+        if idx > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{} ({} modules)", "cycle".bold(), cycle.modules.len())?;
+        for (idx, module) in cycle.modules.iter().enumerate() {
+            let prefix = if idx == cycle.modules.len() - 1 { "└──" } else { "├──" };
+            let display_path = if let Ok(root) = cfg.root() {
+                relativize_to_cwd(root, module)
+            } else {
+                module.clone()
+            };
+            writeln!(writer, "{}  {}", prefix.dimmed(), display_path.blue())?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
 fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -> io::Result<()> {
     if warnings.is_empty() {
         return Ok(());
@@ -208,7 +189,7 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
 
     // Get top 5 offenders (sorted by depth, descending)
     let mut top_offenders: Vec<_> = warnings.iter().collect();
-    top_offenders.sort_by(|a, b| b.depth.cmp(&a.depth));
+    top_offenders.sort_by_key(|w| std::cmp::Reverse(w.depth));
     top_offenders.truncate(5);
 
     writeln!(writer, "{}", "─".repeat(60).dimmed())?;
@@ -246,56 +227,3 @@ fn print_summary<W: Write>(writer: &mut W, warnings: &[Warning], cfg: &Config) -
 
     Ok(())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_make_relative_same_dir() {
-        let target = Path::new("/project/src/file.ts");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("file.ts")));
-    }
-
-    #[test]
-    fn test_make_relative_child_dir() {
-        let target = Path::new("/project/src/components/Button.tsx");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("components/Button.tsx")));
-    }
-
-    #[test]
-    fn test_make_relative_parent_dir() {
-        let target = Path::new("/project/src/file.ts");
-        let base = Path::new("/project/src/components");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../file.ts")));
-    }
-
-    #[test]
-    fn test_make_relative_sibling_dir() {
-        let target = Path::new("/project/apps/web/index.ts");
-        let base = Path::new("/project/apps/api");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../web/index.ts")));
-    }
-
-    #[test]
-    fn test_make_relative_same_path() {
-        let target = Path::new("/project/src");
-        let base = Path::new("/project/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from(".")));
-    }
-
-    #[test]
-    fn test_make_relative_multiple_levels_up() {
-        let target = Path::new("/project/file.ts");
-        let base = Path::new("/project/apps/web/src");
-        let result = make_relative(target, base);
-        assert_eq!(result, Some(PathBuf::from("../../../file.ts")));
-    }
-}
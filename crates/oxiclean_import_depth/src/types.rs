@@ -3,12 +3,35 @@ pub struct Warning {
     pub import_statement: String,
     pub from_file: String,
     pub depth: usize,
-    /// The resolved file path (with extension) for relative imports, None for non-relative imports
-    pub resolved_path: Option<String>,
+    /// The imported file (or collapsed package directory), relative to the root
+    pub resolved_path: String,
+    /// The longest import chains through this import, longest first
+    pub chains: Vec<Vec<ChainHop>>,
+}
+
+/// One import along a chain
+#[derive(Debug, Clone)]
+pub struct ChainHop {
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// The imported file, relative to the root
+    pub to_file: String,
+}
+
+/// A set of modules that import each other, counted as a single depth level
+#[derive(Debug, Clone)]
+pub struct Cycle {
+    /// Paths of the modules in the cycle, relative to the root and sorted
+    pub modules: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CheckResult {
     pub warnings: Vec<Warning>,
+    pub cycles: Vec<Cycle>,
     pub files_analyzed: usize,
 }
@@ -0,0 +1,281 @@
+use anyhow::{Result, anyhow};
+use log::{debug, info, trace};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use oxiclean_core::{Edge, ModuleGraph, ModuleId, resolve_file_arg};
+
+use crate::{
+    config::Config,
+    types::{Chain, Hop, PathResult},
+};
+
+/// A chain as graph positions: each importing module with the index of the
+/// import followed out of it
+type RawChain = Vec<(ModuleId, usize)>;
+
+pub fn run_path(mut cfg: Config) -> Result<PathResult> {
+    info!("Starting path query from {} to {}", cfg.from, cfg.to);
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    query_graph(&graph, &cfg)
+}
+
+/// Answers the query over an already-built module graph: up to `--count` of the
+/// shortest import chains from `from` to the target.
+///
+/// The target is a file when `to` names one and a package otherwise, in which
+/// case a chain ends at the first file of the package it reaches. The shortest
+/// chain is found by a breadth-first search; further ones with Yen's algorithm,
+/// which searches for detours around the chains found so far. No chain visits a
+/// file twice.
+///
+/// `cfg` must be initialized.
+pub fn query_graph(graph: &ModuleGraph, cfg: &Config) -> Result<PathResult> {
+    let root = cfg.root()?;
+    let from_path =
+        resolve_file_arg(root, &cfg.from).ok_or_else(|| anyhow!("No file named '{}'", cfg.from))?;
+    let from = graph.id_of(&from_path).ok_or_else(|| {
+        anyhow!(
+            "'{}' is not in the module graph; check --include, --exclude and --include-tests",
+            cfg.from
+        )
+    })?;
+
+    let (targets, to_files) = graph.resolve_target(&cfg.to)?;
+    debug!("Target {} matched {} modules", cfg.to, targets.len());
+
+    let search = Search {
+        graph,
+        targets: targets.into_iter().collect(),
+        include_type_imports: cfg.include_type_imports,
+    };
+    let chains: Vec<Chain> = search
+        .k_shortest(from, cfg.count)
+        .into_iter()
+        .map(|raw| Chain {
+            hops: raw
+                .into_iter()
+                .map(|(id, idx)| {
+                    let edge = &graph.imports(id)[idx];
+                    Hop {
+                        from_file: graph.rel_path(id),
+                        line: edge.line,
+                        request: edge.request.clone(),
+                        to_file: graph.rel_path(edge.to),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    info!("Path query complete. Found {} chains", chains.len());
+    Ok(PathResult { from_file: graph.rel_path(from), to: cfg.to.clone(), to_files, chains })
+}
+
+struct Search<'a> {
+    graph: &'a ModuleGraph,
+    targets: HashSet<ModuleId>,
+    include_type_imports: bool,
+}
+
+impl Search<'_> {
+    fn follows(&self, edge: &Edge) -> bool {
+        self.include_type_imports || edge.kind.is_runtime()
+    }
+
+    /// Yen's algorithm: the `k` shortest chains from `start`, shortest first and
+    /// in a stable order among chains of equal length
+    fn k_shortest(&self, start: ModuleId, k: usize) -> Vec<RawChain> {
+        let Some(first) = self.shortest(start, &HashSet::new(), &HashSet::new()) else {
+            return Vec::new();
+        };
+        let mut accepted = vec![first];
+        let mut candidates: BTreeSet<(usize, RawChain)> = BTreeSet::new();
+
+        while accepted.len() < k {
+            let last = accepted[accepted.len() - 1].clone();
+            for (i, &(spur, _)) in last.iter().enumerate() {
+                let prefix = &last[..i];
+                // Leave the shared prefix through an import no accepted chain takes
+                // from here, without returning to the files already on it
+                let banned_edges: HashSet<(ModuleId, ModuleId)> = accepted
+                    .iter()
+                    .filter(|chain| chain.len() > i && chain[..i] == *prefix)
+                    .map(|chain| {
+                        let (id, idx) = chain[i];
+                        (id, self.graph.imports(id)[idx].to)
+                    })
+                    .collect();
+                let banned_nodes: HashSet<ModuleId> = prefix.iter().map(|&(id, _)| id).collect();
+
+                if let Some(detour) = self.shortest(spur, &banned_nodes, &banned_edges) {
+                    let chain: RawChain = prefix.iter().copied().chain(detour).collect();
+                    if !accepted.contains(&chain) {
+                        trace!("Candidate chain of {} hops", chain.len());
+                        candidates.insert((chain.len(), chain));
+                    }
+                }
+            }
+            match candidates.pop_first() {
+                Some((_, chain)) => accepted.push(chain),
+                None => break,
+            }
+        }
+        accepted
+    }
+
+    /// Breadth-first search for the shortest chain from `start` to a target,
+    /// avoiding the banned modules and imports (as importer, imported pairs)
+    fn shortest(
+        &self,
+        start: ModuleId,
+        banned_nodes: &HashSet<ModuleId>,
+        banned_edges: &HashSet<(ModuleId, ModuleId)>,
+    ) -> Option<RawChain> {
+        if self.targets.contains(&start) {
+            return Some(Vec::new());
+        }
+        let mut prev: HashMap<ModuleId, (ModuleId, usize)> = HashMap::new();
+        let mut visited: HashSet<ModuleId> = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cur) = queue.pop_front() {
+            for (idx, edge) in self.graph.imports(cur).iter().enumerate() {
+                if !self.follows(edge)
+                    || banned_nodes.contains(&edge.to)
+                    || banned_edges.contains(&(cur, edge.to))
+                    || !visited.insert(edge.to)
+                {
+                    continue;
+                }
+                prev.insert(edge.to, (cur, idx));
+                if self.targets.contains(&edge.to) {
+                    let mut chain = Vec::new();
+                    let mut node = edge.to;
+                    while let Some(&(from, idx)) = prev.get(&node) {
+                        chain.push((from, idx));
+                        node = from;
+                    }
+                    chain.reverse();
+                    return Some(chain);
+                }
+                queue.push_back(edge.to);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, from: &str, to: &str, count: usize) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            from: from.to_string(),
+            to: to.to_string(),
+            count,
+            include_type_imports: false,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        create_test_file(
+            root,
+            "src/app.ts",
+            "import './routes';\nimport './widgets';\nimport type { D } from './lib/date';",
+        );
+        create_test_file(root, "src/routes.ts", "import './widgets';\nimport './lib/date';");
+        create_test_file(root, "src/widgets.ts", "import './lib/date';\nimport './routes';");
+        create_test_file(root, "src/lib/date.ts", "import 'moment';\nexport type D = 1;");
+        create_test_file(
+            root,
+            "node_modules/moment/package.json",
+            r#"{ "name": "moment", "main": "moment.js" }"#,
+        );
+        create_test_file(root, "node_modules/moment/moment.js", "module.exports = 1;");
+    }
+
+    fn chains(result: &PathResult) -> Vec<String> {
+        result
+            .chains
+            .iter()
+            .map(|chain| {
+                let hops: Vec<String> =
+                    chain.hops.iter().map(|h| format!("{}:{}", h.from_file, h.line)).collect();
+                hops.join(" -> ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_shortest_chain() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let result = run_path(config(root, "src/app.ts", "src/lib/date.ts", 1)).unwrap();
+        assert_eq!(result.from_file, "src/app.ts");
+        assert_eq!(chains(&result), vec!["src/app.ts:1 -> src/routes.ts:2"]);
+        assert_eq!(result.chains[0].hops[1].request, "./lib/date");
+        assert_eq!(result.chains[0].hops[1].to_file, "src/lib/date.ts");
+
+        let result = run_path(config(root, "src/app.ts", "moment", 1)).unwrap();
+        assert_eq!(result.to_files, vec!["node_modules/moment/moment.js"]);
+        assert_eq!(chains(&result), vec!["src/app.ts:1 -> src/routes.ts:2 -> src/lib/date.ts:1"]);
+
+        let result = run_path(config(root, "src/lib/date.ts", "src/app.ts", 1)).unwrap();
+        assert!(result.chains.is_empty());
+
+        assert!(run_path(config(root, "src/app.ts", "left-pad", 1)).is_err());
+        assert!(run_path(config(root, "src/missing.ts", "moment", 1)).is_err());
+    }
+
+    #[test]
+    fn test_k_shortest_chains() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        // Every simple chain, shortest first
+        let result = run_path(config(root, "src/app.ts", "src/lib/date.ts", 10)).unwrap();
+        assert_eq!(
+            chains(&result),
+            vec![
+                "src/app.ts:1 -> src/routes.ts:2",
+                "src/app.ts:2 -> src/widgets.ts:1",
+                "src/app.ts:1 -> src/routes.ts:1 -> src/widgets.ts:1",
+                "src/app.ts:2 -> src/widgets.ts:2 -> src/routes.ts:2",
+            ]
+        );
+
+        let mut cfg = config(root, "src/app.ts", "src/lib/date.ts", 1);
+        cfg.include_type_imports = true;
+        let result = run_path(cfg).unwrap();
+        assert_eq!(chains(&result), vec!["src/app.ts:3"]);
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, SharedConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Parser)]
+#[command(name = "path")]
+#[command(about = "Show the shortest import chains from one file to another file or a package")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// The importing file, relative to the current directory or the root
+    #[arg(value_name = "FROM")]
+    pub from: String,
+
+    /// The imported file, relative to the current directory or the root, or package name
+    #[arg(value_name = "TO")]
+    pub to: String,
+
+    /// Number of chains to show, shortest first
+    #[arg(short = 'k', long, default_value_t = 1)]
+    pub count: usize,
+
+    /// Also follow type-only imports, which are erased at runtime and ignored by default
+    #[arg(long)]
+    pub include_type_imports: bool,
+}
+
+/// The `[path]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PathSection {
+    include_type_imports: bool,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+        if self.count == 0 {
+            return Err(anyhow!("--count must be at least 1"));
+        }
+
+        let section: PathSection = self.shared.check_section("path")?;
+        self.include_type_imports |= section.include_type_imports;
+        debug!(
+            "Looking for {} chains from {} to {}, type imports {}",
+            self.count,
+            self.from,
+            self.to,
+            if self.include_type_imports { "included" } else { "ignored" }
+        );
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
@@ -0,0 +1,49 @@
+//! Shortest-path queries between modules of JavaScript/TypeScript projects.
+//!
+//! Answers "how does this file end up importing that one?": the shortest chain
+//! of imports from one file to another file or to a package, or the K shortest
+//! chains, with the import line of every hop.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_path::{Config, run_path};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     from: "src/pages/home.tsx".to_string(),
+//!     to: "moment".to_string(),
+//!     count: 3,
+//!     include_type_imports: false,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_path(cfg.clone())?;
+//!
+//! // Use buffered output for better performance
+//! let mut stdout = BufWriter::new(std::io::stdout());
+//! oxiclean_path::print_chains(&mut stdout, &result, &cfg)?;
+//! stdout.flush()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod chains;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use chains::{query_graph, run_path};
+pub use config::Config;
+pub use reporter::print_chains;
+pub use types::{Chain, Hop, PathResult};
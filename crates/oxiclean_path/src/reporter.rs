@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::PathResult};
+
+/// Prints each chain found with the import line of every hop, or a note that
+/// the starting file doesn't import the target
+pub fn print_chains<W: Write>(writer: &mut W, result: &PathResult, cfg: &Config) -> io::Result<()> {
+    debug!("Printing {} chains", result.chains.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    let from = display(&result.from_file);
+    let to = match result.to_files.as_slice() {
+        [file] => display(file),
+        files => format!("{} ({} files)", result.to, files.len()),
+    };
+    if result.chains.is_empty() {
+        writeln!(
+            writer,
+            "{} {} does not import {}, directly or transitively.",
+            "✓".green().bold(),
+            from.bold(),
+            to.blue().bold()
+        )?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    let heading = if result.chains.len() == 1 {
+        "Shortest import chain".to_string()
+    } else {
+        format!("{} shortest import chains", result.chains.len())
+    };
+    writeln!(writer, "{} from {} to {}\n", heading, from.bold(), to.blue().bold())?;
+
+    for (n, chain) in result.chains.iter().enumerate() {
+        if chain.hops.is_empty() {
+            writeln!(writer, "{} {}\n", from.bold(), "(is the target)".dimmed())?;
+            continue;
+        }
+        writeln!(writer, "{}", format!("#{} ({} hops)", n + 1, chain.hops.len()).dimmed())?;
+        for (idx, hop) in chain.hops.iter().enumerate() {
+            let arrow = if idx == 0 { " " } else { "→" };
+            writeln!(
+                writer,
+                "  {} {}  {}",
+                arrow.dimmed(),
+                format!("{}:{}", display(&hop.from_file), hop.line).blue(),
+                format!("import '{}'", hop.request).dimmed()
+            )?;
+        }
+        if let Some(last) = chain.hops.last() {
+            writeln!(writer, "  {} {}", "→".dimmed(), display(&last.to_file).blue())?;
+        }
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
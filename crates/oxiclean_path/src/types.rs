@@ -0,0 +1,34 @@
+/// One import along a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hop {
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// The imported file, relative to the root
+    pub to_file: String,
+}
+
+/// A chain of imports from the starting file to a target file, visiting no
+/// file twice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain {
+    /// Imports in order; empty when the starting file is a target file
+    pub hops: Vec<Hop>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PathResult {
+    /// The starting file, relative to the root
+    pub from_file: String,
+    /// What was looked up, as given
+    pub to: String,
+    /// The files the target matched, relative to the root: one for a file,
+    /// every file of the package in the graph for a package
+    pub to_files: Vec<String>,
+    /// Up to `--count` chains, shortest first; empty when `from` doesn't
+    /// import the target
+    pub chains: Vec<Chain>,
+}
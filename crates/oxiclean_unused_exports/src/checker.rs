@@ -0,0 +1,334 @@
+use anyhow::{Result, anyhow};
+use log::{debug, info, trace, warn};
+use std::{borrow::Cow, collections::HashSet};
+
+use oxiclean_core::{EntryMode, ImportedNames, ModuleGraph, ModuleId, collect_entries};
+
+use crate::{
+    config::Config,
+    types::{CheckResult, Warning},
+};
+
+pub fn run_unused_exports_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting unused exports check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    check_graph(&graph, &cfg)
+}
+
+/// Runs the unused exports check as a pass over an already-built module graph.
+///
+/// Every export of an entry (including test files and extra entries) is public
+/// and counts as used; the exports of the modules they reach are used when some
+/// reached module imports them. When some entries are missing from `graph`
+/// (e.g. test files, which the shared graph leaves out), a graph of all entries
+/// is built and checked instead.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> Result<CheckResult> {
+    let collector_cfg = cfg.shared.collector_config()?;
+    if collector_cfg.entries == EntryMode::Files && collector_cfg.entry_glob.is_empty() {
+        warn!("Every source file is an entry, so every export is public; use --entries auto");
+    }
+
+    let extra = cfg
+        .extra
+        .as_ref()
+        .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))?;
+    let mut roots = collect_entries(&collector_cfg)?;
+    roots.extend(extra.collect(&collector_cfg)?);
+    roots.sort();
+    roots.dedup();
+    info!("Following {} entries", roots.len());
+
+    let graph = if roots.iter().all(|path| graph.id_of(path).is_some()) {
+        Cow::Borrowed(graph)
+    } else {
+        debug!("Building a graph of all {} entries", roots.len());
+        let cache = cfg.shared.analysis_cache()?;
+        let full =
+            ModuleGraph::build_with_cache(graph.root(), &cfg.shared.resolve, &roots, &cache)?;
+        if let Err(e) = cache.save() {
+            warn!("Failed to save cache: {:#}", e);
+        }
+        Cow::Owned(full)
+    };
+    let roots: Vec<ModuleId> = roots.iter().filter_map(|path| graph.id_of(path)).collect();
+
+    let reached = reachable(&graph, &roots);
+    let usage = Usage::collect(&graph, &roots, &reached);
+    debug!(
+        "{} modules reached, {} used wholesale, {} names used",
+        reached.len(),
+        usage.all.len(),
+        usage.names.len()
+    );
+
+    let mut modules_checked = 0;
+    let mut warnings = Vec::new();
+    for &id in &reached {
+        if graph.is_installed(id) {
+            continue;
+        }
+        let path = graph.path(id);
+        let rel_path = path.strip_prefix(&collector_cfg.root).unwrap_or(path);
+        if cfg.is_ignored(rel_path) {
+            trace!("Ignoring exports of {}", rel_path.display());
+            continue;
+        }
+        modules_checked += 1;
+        if usage.all.contains(&id) {
+            continue;
+        }
+
+        let mut seen = HashSet::new();
+        for export in graph.exports(id) {
+            // Overloads and declaration merging export one name several times
+            if !seen.insert(export.name.as_str())
+                || usage.names.contains(&(id, export.name.clone()))
+                || (cfg.skip_types && export.is_type)
+            {
+                continue;
+            }
+            warnings.push(Warning {
+                file: rel_path.to_string_lossy().to_string(),
+                name: export.name.clone(),
+                line: export.line,
+                is_type: export.is_type,
+            });
+        }
+    }
+    warnings.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+
+    info!("Unused exports check complete. Found {} unused exports", warnings.len());
+    Ok(CheckResult { warnings, modules_checked, files_analyzed: graph.len() })
+}
+
+/// Modules reachable from `roots`, following type-only imports too since their
+/// names count as used, in discovery order
+fn reachable(graph: &ModuleGraph, roots: &[ModuleId]) -> Vec<ModuleId> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack: Vec<ModuleId> = roots.iter().rev().copied().collect();
+    while let Some(id) = stack.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        order.push(id);
+        stack.extend(graph.imports(id).iter().rev().map(|edge| edge.to));
+    }
+    order
+}
+
+/// A use of a module's exports that still has to be propagated
+enum Use {
+    Name(ModuleId, String),
+    All(ModuleId),
+}
+
+/// The exports used by the reached modules
+struct Usage {
+    /// `(module, name)` pairs imported somewhere
+    names: HashSet<(ModuleId, String)>,
+    /// Modules whose every export may be used: entries, and the targets of
+    /// namespace imports, `require()` and `import()`
+    all: HashSet<ModuleId>,
+}
+
+impl Usage {
+    fn collect(graph: &ModuleGraph, roots: &[ModuleId], reached: &[ModuleId]) -> Self {
+        let mut pending: Vec<Use> = roots.iter().map(|&id| Use::All(id)).collect();
+        for &id in reached {
+            for edge in graph.imports(id) {
+                match &edge.names {
+                    ImportedNames::Named(names) => {
+                        pending.extend(names.iter().map(|name| Use::Name(edge.to, name.clone())))
+                    }
+                    ImportedNames::All => pending.push(Use::All(edge.to)),
+                    ImportedNames::Nothing => {}
+                }
+            }
+        }
+
+        let mut usage = Self { names: HashSet::new(), all: HashSet::new() };
+        while let Some(next) = pending.pop() {
+            match next {
+                Use::Name(id, name) if name == "*" => pending.push(Use::All(id)),
+                Use::Name(id, name) => usage.use_name(graph, id, name, &mut pending),
+                Use::All(id) => usage.use_all(graph, id, &mut pending),
+            }
+        }
+        usage
+    }
+
+    /// Marks `name` of `id` as used, following it through re-exports and, when
+    /// `id` doesn't export it itself, through `export * from`
+    fn use_name(
+        &mut self,
+        graph: &ModuleGraph,
+        id: ModuleId,
+        name: String,
+        pending: &mut Vec<Use>,
+    ) {
+        if !self.names.insert((id, name.clone())) {
+            return;
+        }
+        let mut found = false;
+        for export in graph.exports(id).iter().filter(|e| e.name == name) {
+            found = true;
+            if let Some(from) = &export.from
+                && let Some(target) = graph.resolved(id, &from.request)
+            {
+                pending.push(Use::Name(target, from.name.clone()));
+            }
+        }
+        // `export *` never re-exports the default export
+        if !found && name != "default" {
+            pending.extend(graph.star_exports(id).iter().map(|&s| Use::Name(s, name.clone())));
+        }
+    }
+
+    /// Marks every export of `id` as used, e.g. for `import * as ns`
+    fn use_all(&mut self, graph: &ModuleGraph, id: ModuleId, pending: &mut Vec<Use>) {
+        if !self.all.insert(id) {
+            return;
+        }
+        for export in graph.exports(id) {
+            if let Some(from) = &export.from
+                && let Some(target) = graph.resolved(id, &from.request)
+            {
+                pending.push(Use::Name(target, from.name.clone()));
+            }
+        }
+        pending.extend(graph.star_exports(id).iter().map(|&s| Use::All(s)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, skip_types: bool) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            extra_entries: Vec::new(),
+            ignore: Vec::new(),
+            skip_types,
+            extra: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn unused(result: &CheckResult) -> Vec<String> {
+        result.warnings.iter().map(|w| format!("{}:{}", w.file, w.name)).collect()
+    }
+
+    #[test]
+    fn test_named_and_default_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "package.json", r#"{ "name": "app", "main": "src/index.ts" }"#);
+        create_test_file(
+            root,
+            "src/index.ts",
+            "import fmt, { a } from './utils';\nexport const main = () => fmt(a);",
+        );
+        create_test_file(
+            root,
+            "src/utils.ts",
+            "export const a = 1;\nexport function b() {}\nexport default function fmt() {}\n\
+             export type T = string;\nexport interface I {}",
+        );
+
+        let result = run_unused_exports_check(config(root, false)).unwrap();
+        assert_eq!(unused(&result), vec!["src/utils.ts:b", "src/utils.ts:T", "src/utils.ts:I"]);
+        let types: Vec<&str> = result.type_warnings().map(|w| w.name.as_str()).collect();
+        assert_eq!(types, vec!["T", "I"]);
+        assert_eq!(result.warnings[0].line, 2);
+
+        let result = run_unused_exports_check(config(root, true)).unwrap();
+        assert_eq!(unused(&result), vec!["src/utils.ts:b"]);
+    }
+
+    #[test]
+    fn test_re_exports_and_export_star() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "package.json", r#"{ "name": "app", "main": "src/index.ts" }"#);
+        create_test_file(
+            root,
+            "src/index.ts",
+            "import { Button, icon } from './ui';\nButton(icon);",
+        );
+        create_test_file(
+            root,
+            "src/ui/index.ts",
+            "export * from './button';\nexport { icon, unusedIcon } from './icons';",
+        );
+        create_test_file(
+            root,
+            "src/ui/button.ts",
+            "export const Button = 1;\nexport const Unused = 2;\nexport default 3;",
+        );
+        create_test_file(
+            root,
+            "src/ui/icons.ts",
+            "export const icon = 1;\nexport const other = 2;\nexport const unusedIcon = 3;",
+        );
+
+        let result = run_unused_exports_check(config(root, false)).unwrap();
+        assert_eq!(
+            unused(&result),
+            vec![
+                "src/ui/button.ts:Unused",
+                "src/ui/button.ts:default",
+                "src/ui/icons.ts:other",
+                "src/ui/icons.ts:unusedIcon",
+                "src/ui/index.ts:unusedIcon",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_namespace_imports_and_tests_are_conservative() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_file(root, "package.json", r#"{ "name": "app", "main": "src/index.ts" }"#);
+        create_test_file(
+            root,
+            "src/index.ts",
+            "import * as ns from './barrel';\nimport('./lazy');\nns.x;",
+        );
+        create_test_file(root, "src/barrel.ts", "export * from './a';\nexport const x = 1;");
+        create_test_file(root, "src/a.ts", "export const a = 1;");
+        create_test_file(root, "src/lazy.ts", "export const lazy = 1;");
+        create_test_file(root, "src/tested.ts", "export const t = 1;\nexport const u = 2;");
+        create_test_file(root, "src/tested.test.ts", "import { t } from './tested';");
+
+        let result = run_unused_exports_check(config(root, false)).unwrap();
+        assert_eq!(unused(&result), vec!["src/tested.ts:u"]);
+    }
+}
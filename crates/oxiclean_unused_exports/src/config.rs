@@ -0,0 +1,90 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, EntryMode, EntrySection, ExtraEntries, SharedConfig};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "unused-exports")]
+#[command(about = "Find exported names that no module imports")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Glob pattern for extra entry files, whose exports are used from outside
+    /// the import graph (config files, scripts). Test files are always entries. Repeatable
+    #[arg(long = "entry")]
+    pub extra_entries: Vec<String>,
+
+    /// Glob pattern for files whose exports are never reported. Repeatable
+    #[arg(long)]
+    pub ignore: Vec<String>,
+
+    /// Don't report unused type-only exports (types and interfaces)
+    #[arg(long)]
+    pub skip_types: bool,
+
+    #[clap(skip)]
+    pub extra: Option<ExtraEntries>,
+}
+
+/// The `[unused-exports]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct UnusedExportsSection {
+    entries: Vec<String>,
+    ignore: Vec<String>,
+    skip_types: bool,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths.
+    ///
+    /// Unless entries are configured explicitly, only true roots (`--entries auto`)
+    /// are used: every export of an entry is public API and counts as used.
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.extra.is_some() {
+            return Ok(());
+        }
+        self.shared.initialize()?;
+        if self.shared.entries.is_none() && self.shared.entry_glob.is_empty() {
+            debug!("Defaulting to auto-detected entries");
+            self.shared.entries = Some(EntryMode::Auto);
+        }
+
+        // Patterns from the config file and the command line add up
+        let section: UnusedExportsSection = self.shared.check_section("unused-exports")?;
+        self.skip_types |= section.skip_types;
+        let entries = EntrySection { entries: section.entries, ignore: section.ignore };
+        self.extra = Some(ExtraEntries::new(entries, &self.extra_entries, &self.ignore)?);
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    /// Whether a file, relative to the root, should never be reported
+    pub fn is_ignored(&self, rel_path: &Path) -> bool {
+        self.extra.as_ref().is_some_and(|extra| extra.is_ignored(rel_path))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
@@ -0,0 +1,53 @@
+//! Unused export detection for JavaScript/TypeScript projects.
+//!
+//! This crate follows imports from a project's entry points and reports the
+//! exported names that no reached module imports. Names are followed through
+//! re-exports and `export * from`; namespace imports, `require()` and `import()`
+//! count as using every export of their target. Type-only exports are reported
+//! in their own category.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_unused_exports::{Config, run_unused_exports_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     extra_entries: vec![],
+//!     ignore: vec!["src/generated/**".to_string()],
+//!     skip_types: false,
+//!     extra: None,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_unused_exports_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_unused_exports::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_unused_exports_check};
+pub use config::Config;
+pub use reporter::{print_no_unused_exports_message, print_warnings_tree};
+pub use types::{CheckResult, Warning};
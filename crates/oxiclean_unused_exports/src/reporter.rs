@@ -0,0 +1,95 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{self, Write},
+};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{
+    config::Config,
+    types::{CheckResult, Warning},
+};
+
+pub fn print_no_unused_exports_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No unused exports detected");
+    writeln!(
+        writer,
+        "{} No unused exports. Every export of {} modules is imported somewhere.",
+        "✓".green().bold(),
+        result.modules_checked
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints the unused exports grouped by file, values first and type-only
+/// exports in their own section
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} unused exports", result.warnings.len());
+    let file_count = result.warnings.iter().map(|w| &w.file).collect::<HashSet<_>>().len();
+
+    writeln!(
+        writer,
+        "{} Unused exports detected ({} in {} files)\n",
+        "⚠".yellow().bold(),
+        result.warnings.len().to_string().yellow(),
+        file_count
+    )?;
+
+    let values: Vec<&Warning> = result.value_warnings().collect();
+    let types: Vec<&Warning> = result.type_warnings().collect();
+    if !values.is_empty() {
+        writeln!(writer, "{}\n", "Exports".bold())?;
+        print_section(writer, &values, cfg)?;
+    }
+    if !types.is_empty() {
+        writeln!(writer, "{}\n", "Type exports".bold())?;
+        print_section(writer, &types, cfg)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Unused exports: {}", values.len().to_string().yellow().bold())?;
+    writeln!(writer, "  Unused type exports: {}", types.len().to_string().yellow().bold())?;
+    writeln!(writer, "  Files: {}", file_count)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn print_section<W: Write>(writer: &mut W, warnings: &[&Warning], cfg: &Config) -> io::Result<()> {
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    let mut by_file: BTreeMap<&str, Vec<&Warning>> = BTreeMap::new();
+    for warning in warnings {
+        by_file.entry(&warning.file).or_default().push(warning);
+    }
+
+    for (file, exports) in &by_file {
+        writeln!(writer, "{}", display(file).blue())?;
+        for (idx, export) in exports.iter().enumerate() {
+            let prefix = if idx == exports.len() - 1 { "└──" } else { "├──" };
+            writeln!(
+                writer,
+                "{}  {} {}",
+                prefix.dimmed(),
+                export.name,
+                format!("(line {})", export.line).dimmed()
+            )?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
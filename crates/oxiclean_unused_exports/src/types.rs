@@ -0,0 +1,32 @@
+/// An exported name that no module imports
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// The exporting file, relative to the root
+    pub file: String,
+    pub name: String,
+    /// 1-based line of the export
+    pub line: u32,
+    /// Whether the export only exists as a type
+    pub is_type: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Unused exports, sorted by file and line
+    pub warnings: Vec<Warning>,
+    /// Modules whose exports were checked
+    pub modules_checked: usize,
+    pub files_analyzed: usize,
+}
+
+impl CheckResult {
+    /// Unused exports that exist at runtime
+    pub fn value_warnings(&self) -> impl Iterator<Item = &Warning> {
+        self.warnings.iter().filter(|w| !w.is_type)
+    }
+
+    /// Unused type-only exports
+    pub fn type_warnings(&self) -> impl Iterator<Item = &Warning> {
+        self.warnings.iter().filter(|w| w.is_type)
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Result, anyhow};
+use log::{debug, info, trace, warn};
+use std::{collections::HashSet, path::PathBuf};
+
+use oxiclean_core::{EntryMode, ModuleGraph, collect_entries, collect_source_files};
+
+use crate::{
+    config::Config,
+    types::{CheckResult, Warning},
+};
+
+pub fn run_unused_files_check(mut cfg: Config) -> Result<CheckResult> {
+    info!("Starting unused files check");
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    check_graph(&graph, &cfg)
+}
+
+/// Runs the unused files check as a pass over an already-built module graph.
+///
+/// The graph may have been built from different entries (e.g. every source file
+/// when run as part of `oxiclean check`): files are used when they are reachable
+/// from this check's own entries, and entries missing from the graph are
+/// followed in a separate, smaller graph.
+///
+/// `cfg` must be initialized.
+pub fn check_graph(graph: &ModuleGraph, cfg: &Config) -> Result<CheckResult> {
+    let collector_cfg = cfg.shared.collector_config()?;
+    let root = collector_cfg.root.clone();
+    if collector_cfg.entries == EntryMode::Files && collector_cfg.entry_glob.is_empty() {
+        warn!("Every source file is an entry, so no file can be unused; use --entries auto");
+    }
+
+    let extra = cfg
+        .extra
+        .as_ref()
+        .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))?;
+    let mut roots = collect_entries(&collector_cfg)?;
+    roots.extend(extra.collect(&collector_cfg)?);
+    info!("Following {} entries", roots.len());
+
+    let used = used_files(graph, cfg, &roots)?;
+    debug!("{} files are reachable from the entries", used.len());
+
+    let sources = collect_source_files(&collector_cfg)?;
+    let mut warnings: Vec<Warning> = sources
+        .iter()
+        .filter(|path| !used.contains(path.as_path()))
+        .map(|path| path.strip_prefix(&root).unwrap_or(path))
+        .filter(|rel_path| {
+            let ignored = cfg.is_ignored(rel_path);
+            if ignored {
+                trace!("Ignoring unused file: {}", rel_path.display());
+            }
+            !ignored
+        })
+        .map(|rel_path| Warning { file: rel_path.to_string_lossy().to_string() })
+        .collect();
+    warnings.sort_by(|a, b| a.file.cmp(&b.file));
+
+    info!("Unused files check complete. Found {} unused files", warnings.len());
+    Ok(CheckResult { warnings, source_files: sources.len(), files_analyzed: graph.len() })
+}
+
+/// Every file reachable from `roots`, following type-only imports too since
+/// the files they point at are still needed
+fn used_files(graph: &ModuleGraph, cfg: &Config, roots: &[PathBuf]) -> Result<HashSet<PathBuf>> {
+    let mut used: HashSet<PathBuf> = HashSet::new();
+    let mut missing = Vec::new();
+    let mut stack = Vec::new();
+    for path in roots {
+        match graph.id_of(path) {
+            Some(id) => stack.push(id),
+            None => missing.push(path.clone()),
+        }
+    }
+
+    let mut visited = HashSet::new();
+    while let Some(id) = stack.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        used.insert(graph.path(id).to_path_buf());
+        stack.extend(graph.imports(id).iter().map(|edge| edge.to));
+    }
+
+    if !missing.is_empty() {
+        debug!("Building a separate graph for {} entries outside the module graph", missing.len());
+        let extra = ModuleGraph::build(graph.root(), &cfg.shared.resolve, &missing)?;
+        used.extend(extra.modules().map(|id| extra.path(id).to_path_buf()));
+    }
+    Ok(used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{fs, path::Path};
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, extra_entries: &[&str], ignore: &[&str]) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                ..Default::default()
+            },
+            extra_entries: extra_entries.iter().map(|p| p.to_string()).collect(),
+            ignore: ignore.iter().map(|p| p.to_string()).collect(),
+            list: false,
+            extra: None,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        create_test_file(root, "package.json", r#"{ "name": "app", "main": "src/index.ts" }"#);
+        create_test_file(root, "src/index.ts", "import './a';\nimport type { T } from './types';");
+        create_test_file(root, "src/a.ts", "export const a = 1;");
+        create_test_file(root, "src/types.ts", "export type T = string;");
+        // b is dead, and so is c, which only b imports
+        create_test_file(root, "src/b.ts", "import './c';");
+        create_test_file(root, "src/c.ts", "export const c = 1;");
+        // Only used by a test and by a script
+        create_test_file(root, "src/tested.ts", "export const t = 1;");
+        create_test_file(root, "src/a.test.ts", "import './tested';");
+        create_test_file(root, "src/scripted.ts", "export const s = 1;");
+        create_test_file(root, "scripts/build.ts", "import '../src/scripted';");
+        create_test_file(root, "src/generated/api.ts", "export {};");
+    }
+
+    fn unused(result: &CheckResult) -> Vec<&str> {
+        result.warnings.iter().map(|w| w.file.as_str()).collect()
+    }
+
+    #[test]
+    fn test_unused_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let cfg = config(root, &[], &[]);
+        let result = run_unused_files_check(cfg).unwrap();
+        assert_eq!(
+            unused(&result),
+            vec!["src/b.ts", "src/c.ts", "src/generated/api.ts", "src/scripted.ts"]
+        );
+    }
+
+    #[test]
+    fn test_extra_entries_and_ignores() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let cfg = config(root, &["scripts/**"], &["src/generated/**"]);
+        let result = run_unused_files_check(cfg).unwrap();
+        assert_eq!(unused(&result), vec!["src/b.ts", "src/c.ts"]);
+    }
+
+    #[test]
+    fn test_graph_built_from_every_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        // As in `oxiclean check`, where the shared graph has every source file as an entry
+        let mut shared = SharedConfig {
+            root: Some(root.to_path_buf()),
+            no_cache: Some(true),
+            entries: Some(EntryMode::Files),
+            ..Default::default()
+        };
+        shared.initialize().unwrap();
+        let graph = ModuleGraph::from_config(&shared).unwrap();
+
+        let cfg = config(root, &["scripts/**"], &[]);
+        let result = check_graph(&graph, &cfg).unwrap();
+        assert_eq!(unused(&result), vec!["src/b.ts", "src/c.ts", "src/generated/api.ts"]);
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, EntryMode, EntrySection, ExtraEntries, SharedConfig};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "unused-files")]
+#[command(about = "Find source files that no entry point reaches")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// Glob pattern for extra entry files that are used outside the import graph
+    /// (config files, scripts, test setup). Test files are always entries. Repeatable
+    #[arg(long = "entry")]
+    pub extra_entries: Vec<String>,
+
+    /// Glob pattern for files never to report as unused. Repeatable
+    #[arg(long)]
+    pub ignore: Vec<String>,
+
+    /// Print only the unused paths, one per line, e.g. to pipe into `xargs rm`
+    #[arg(long)]
+    pub list: bool,
+
+    #[clap(skip)]
+    pub extra: Option<ExtraEntries>,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths.
+    ///
+    /// Unless entries are configured explicitly, only true roots (`--entries auto`)
+    /// are used: when every source file is an entry, nothing can be unused.
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.extra.is_some() {
+            return Ok(());
+        }
+        self.shared.initialize()?;
+        if self.shared.entries.is_none() && self.shared.entry_glob.is_empty() {
+            debug!("Defaulting to auto-detected entries");
+            self.shared.entries = Some(EntryMode::Auto);
+        }
+
+        // Patterns from the config file and the command line add up
+        let section: EntrySection = self.shared.check_section("unused-files")?;
+        self.extra = Some(ExtraEntries::new(section, &self.extra_entries, &self.ignore)?);
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+
+    /// Whether a file, relative to the root, should never be reported
+    pub fn is_ignored(&self, rel_path: &Path) -> bool {
+        self.extra.as_ref().is_some_and(|extra| extra.is_ignored(rel_path))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
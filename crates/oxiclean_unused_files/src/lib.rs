@@ -0,0 +1,51 @@
+//! Unused file detection for JavaScript/TypeScript projects.
+//!
+//! This crate follows imports from a project's entry points (package.json
+//! entries, framework routes, test files and any configured extra entries) and
+//! reports the source files that are never reached, which are dead code.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_unused_files::{Config, run_unused_files_check};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     extra_entries: vec!["scripts/**".to_string()],
+//!     ignore: vec![],
+//!     list: false,
+//!     extra: None,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_unused_files_check(cfg.clone())?;
+//!
+//! if !result.warnings.is_empty() {
+//!     // Use buffered output for better performance
+//!     let mut stdout = BufWriter::new(std::io::stdout());
+//!     oxiclean_unused_files::print_warnings_tree(&mut stdout, &result, &cfg)?;
+//!     stdout.flush()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod checker;
+mod config;
+mod reporter;
+mod types;
+
+// Re-export public API
+pub use checker::{check_graph, run_unused_files_check};
+pub use config::Config;
+pub use reporter::{print_file_list, print_no_unused_files_message, print_warnings_tree};
+pub use types::{CheckResult, Warning};
@@ -0,0 +1,91 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    path::Path,
+};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::CheckResult};
+
+pub fn print_no_unused_files_message<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+) -> io::Result<()> {
+    debug!("No unused files detected");
+    writeln!(
+        writer,
+        "{} No unused files. All {} source files are reachable from an entry.",
+        "✓".green().bold(),
+        result.source_files
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints the unused files grouped by directory
+pub fn print_warnings_tree<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    debug!("Printing {} unused files", result.warnings.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    writeln!(
+        writer,
+        "{} Unused files detected ({} of {} source files)\n",
+        "⚠".yellow().bold(),
+        result.warnings.len().to_string().yellow(),
+        result.source_files
+    )?;
+
+    let mut by_dir: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for warning in &result.warnings {
+        let path = Path::new(&warning.file);
+        let dir = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or(&warning.file);
+        by_dir.entry(dir).or_default().push(name);
+    }
+
+    for (dir, files) in &by_dir {
+        let dir_display = if dir.is_empty() { ".".to_string() } else { display(dir) };
+        writeln!(writer, "{}", format!("{}/", dir_display).blue())?;
+        for (idx, file) in files.iter().enumerate() {
+            let prefix = if idx == files.len() - 1 { "└──" } else { "├──" };
+            writeln!(writer, "{}  {}", prefix.dimmed(), file)?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(writer, "  Unused files: {}", result.warnings.len().to_string().yellow().bold())?;
+    writeln!(writer, "  Directories: {}", by_dir.len())?;
+    writeln!(writer, "  {}", "Run with --list for a plain list of paths to delete".dimmed())?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints only the unused paths, one per line, relative to the working directory
+pub fn print_file_list<W: Write>(
+    writer: &mut W,
+    result: &CheckResult,
+    cfg: &Config,
+) -> io::Result<()> {
+    for warning in &result.warnings {
+        let path = match cfg.root() {
+            Ok(root) => relativize_to_cwd(root, &warning.file),
+            Err(_) => warning.file.clone(),
+        };
+        writeln!(writer, "{}", path)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
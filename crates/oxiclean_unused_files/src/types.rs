@@ -0,0 +1,15 @@
+/// A source file that no entry reaches
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// The file, relative to the root
+    pub file: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Unused files, sorted by path
+    pub warnings: Vec<Warning>,
+    /// Source files considered, used or not
+    pub source_files: usize,
+    pub files_analyzed: usize,
+}
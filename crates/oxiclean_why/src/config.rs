@@ -0,0 +1,75 @@
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::debug;
+use oxiclean_core::{CommandConfig, EntryMode, SharedConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Parser)]
+#[command(name = "why")]
+#[command(about = "Show which entries import a file or package, and through which imports")]
+pub struct Config {
+    #[command(flatten)]
+    pub shared: SharedConfig,
+
+    /// The file (relative to the current directory or the root) or package name
+    #[arg(value_name = "FILE|PACKAGE")]
+    pub target: String,
+
+    /// Also follow type-only imports, which are erased at runtime and ignored by default
+    #[arg(long)]
+    pub include_type_imports: bool,
+}
+
+/// The `[why]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct WhySection {
+    include_type_imports: bool,
+}
+
+impl Config {
+    /// Initialize the config by resolving the root directory, loading the config file
+    /// and tsconfig paths.
+    ///
+    /// Unless entries are configured explicitly, only true roots (`--entries auto`)
+    /// are used, so the routes start at pages, binaries and package entry points
+    /// rather than at every source file.
+    pub fn initialize(&mut self) -> Result<()> {
+        self.shared.initialize()?;
+        if self.shared.entries.is_none() && self.shared.entry_glob.is_empty() {
+            debug!("Defaulting to auto-detected entries");
+            self.shared.entries = Some(EntryMode::Auto);
+        }
+
+        let section: WhySection = self.shared.check_section("why")?;
+        self.include_type_imports |= section.include_type_imports;
+        debug!(
+            "Looking for {}, type imports {}",
+            self.target,
+            if self.include_type_imports { "included" } else { "ignored" }
+        );
+        Ok(())
+    }
+
+    /// Get the root directory, returning an error if not initialized
+    pub fn root(&self) -> Result<&PathBuf> {
+        self.shared
+            .root()
+            .ok_or_else(|| anyhow!("Config not initialized - call initialize() first"))
+    }
+}
+
+impl CommandConfig for Config {
+    fn shared(&self) -> &SharedConfig {
+        &self.shared
+    }
+
+    fn shared_mut(&mut self) -> &mut SharedConfig {
+        &mut self.shared
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        Config::initialize(self)
+    }
+}
@@ -0,0 +1,49 @@
+//! Reverse reachability queries for JavaScript/TypeScript projects.
+//!
+//! The checks look forward from entries; this crate answers the reverse
+//! question, like `npm why` for modules: which entries transitively import a
+//! file or package, and through which chain of imports. It searches the
+//! reversed module graph from the target and reports the shortest route from
+//! every entry that reaches it.
+//!
+//! # Examples
+//!
+//! ## Basic Usage
+//!
+//! ```no_run
+//! use oxiclean_why::{Config, run_why};
+//! use oxiclean_core::SharedConfig;
+//! use std::io::{BufWriter, Write};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut cfg = Config {
+//!     shared: SharedConfig {
+//!         root: Some(std::path::PathBuf::from("/path/to/project")),
+//!         ..Default::default()
+//!     },
+//!     target: "src/lib/heavyChart.ts".to_string(),
+//!     include_type_imports: false,
+//! };
+//!
+//! // Resolve the root and merge in oxiclean.json / oxiclean.toml, if any
+//! cfg.initialize()?;
+//! let result = run_why(cfg.clone())?;
+//!
+//! // Use buffered output for better performance
+//! let mut stdout = BufWriter::new(std::io::stdout());
+//! oxiclean_why::print_routes(&mut stdout, &result, &cfg)?;
+//! stdout.flush()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod config;
+mod reporter;
+mod types;
+mod why;
+
+// Re-export public API
+pub use config::Config;
+pub use reporter::print_routes;
+pub use types::{Hop, Route, WhyResult};
+pub use why::{query_graph, run_why};
@@ -0,0 +1,84 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+use log::debug;
+use oxiclean_core::relativize_to_cwd;
+
+use crate::{config::Config, types::WhyResult};
+
+/// Prints every entry reaching the target with the shortest chain of imports
+/// from it, or a note that no entry does
+pub fn print_routes<W: Write>(writer: &mut W, result: &WhyResult, cfg: &Config) -> io::Result<()> {
+    debug!("Printing {} routes", result.routes.len());
+    let display = |file: &str| match cfg.root() {
+        Ok(root) => relativize_to_cwd(root, file),
+        Err(_) => file.to_string(),
+    };
+
+    let target = match result.target_files.as_slice() {
+        [file] => display(file),
+        files => format!("{} ({} files)", result.target, files.len()),
+    };
+    if result.routes.is_empty() {
+        writeln!(
+            writer,
+            "{} {} is not imported by any of the {} entries.",
+            "✓".green().bold(),
+            target.blue().bold(),
+            result.entries
+        )?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    writeln!(
+        writer,
+        "{} is reached from {} of {} entries\n",
+        target.blue().bold(),
+        result.routes.len().to_string().yellow().bold(),
+        result.entries
+    )?;
+
+    for route in &result.routes {
+        if route.hops.is_empty() {
+            writeln!(writer, "{} {}\n", display(&route.entry).bold(), "(is the target)".dimmed())?;
+            continue;
+        }
+        writeln!(
+            writer,
+            "{} {}",
+            display(&route.entry).bold(),
+            format!("({} hops)", route.hops.len()).dimmed()
+        )?;
+        for (idx, hop) in route.hops.iter().enumerate() {
+            let arrow = if idx == 0 { " " } else { "→" };
+            writeln!(
+                writer,
+                "  {} {}  {}",
+                arrow.dimmed(),
+                format!("{}:{}", display(&hop.from_file), hop.line).blue(),
+                format!("import '{}'", hop.request).dimmed()
+            )?;
+        }
+        if let Some(last) = route.hops.last() {
+            writeln!(writer, "  {} {}", "→".dimmed(), display(&last.to_file).blue())?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "{}", "─".repeat(60).dimmed())?;
+    writeln!(writer, "{}", "Summary".bold())?;
+    writeln!(
+        writer,
+        "  Entries reaching the target: {} of {}",
+        result.routes.len(),
+        result.entries
+    )?;
+    writeln!(writer, "  Direct importers: {}", result.direct_importers)?;
+    if let Some(longest) = result.routes.iter().map(|r| r.hops.len()).max() {
+        writeln!(writer, "  Longest route: {} hops", longest)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+/// One import along a route
+#[derive(Debug, Clone)]
+pub struct Hop {
+    /// The importing file, relative to the root
+    pub from_file: String,
+    /// 1-based line of the import in `from_file`
+    pub line: u32,
+    /// The import specifier as written in the source
+    pub request: String,
+    /// The imported file, relative to the root
+    pub to_file: String,
+}
+
+/// The shortest chain of imports from an entry to the target
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// The entry, relative to the root
+    pub entry: String,
+    /// Imports from the entry to a target file; empty when the entry is one
+    pub hops: Vec<Hop>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WhyResult {
+    /// What was looked up, as given
+    pub target: String,
+    /// The files the target matched, relative to the root: one for a file,
+    /// every file of the package in the graph for a package
+    pub target_files: Vec<String>,
+    /// One route per entry reaching the target, shortest first
+    pub routes: Vec<Route>,
+    /// Modules importing a target file directly, outside the target
+    pub direct_importers: usize,
+    /// Entries in the graph
+    pub entries: usize,
+}
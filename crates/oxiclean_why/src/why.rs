@@ -0,0 +1,208 @@
+use anyhow::Result;
+use log::{debug, info, trace};
+use std::collections::VecDeque;
+
+use oxiclean_core::{ModuleGraph, ModuleId};
+
+use crate::{
+    config::Config,
+    types::{Hop, Route, WhyResult},
+};
+
+const UNREACHED: u32 = u32::MAX;
+
+pub fn run_why(mut cfg: Config) -> Result<WhyResult> {
+    info!("Starting why query for {}", cfg.target);
+
+    // Initialize config (resolve root, load tsconfig paths)
+    cfg.initialize()?;
+
+    let graph = ModuleGraph::from_config(&cfg.shared)?;
+    query_graph(&graph, &cfg)
+}
+
+/// Answers the query over an already-built module graph: every entry that
+/// reaches the target, with the shortest chain of imports from each.
+///
+/// The target is a file when it names one, relative to the current directory
+/// or the root, and a package otherwise: every file in the graph under
+/// `node_modules/<name>`, in whichever copy of the package. A breadth-first
+/// search over the reversed graph starting at the target files finds the
+/// distance of every module to the target and the import to follow from it.
+///
+/// `cfg` must be initialized.
+pub fn query_graph(graph: &ModuleGraph, cfg: &Config) -> Result<WhyResult> {
+    let (targets, target_files) = graph.resolve_target(&cfg.target)?;
+    debug!("Target {} matched {} modules", cfg.target, targets.len());
+
+    // Distance of every module to the nearest target file, and the index of the
+    // import to follow towards it
+    let mut dist = vec![UNREACHED; graph.len()];
+    let mut next: Vec<usize> = vec![0; graph.len()];
+    let mut queue: VecDeque<ModuleId> = VecDeque::new();
+    for &target in &targets {
+        dist[target.index()] = 0;
+        queue.push_back(target);
+    }
+    while let Some(cur) = queue.pop_front() {
+        for &importer in graph.importers(cur) {
+            if dist[importer.index()] != UNREACHED {
+                continue;
+            }
+            let Some(edge) = graph
+                .imports(importer)
+                .iter()
+                .position(|e| e.to == cur && (cfg.include_type_imports || e.kind.is_runtime()))
+            else {
+                continue;
+            };
+            dist[importer.index()] = dist[cur.index()] + 1;
+            next[importer.index()] = edge;
+            queue.push_back(importer);
+        }
+    }
+
+    let mut routes: Vec<Route> = graph
+        .entries()
+        .iter()
+        .filter(|entry| dist[entry.index()] != UNREACHED)
+        .map(|&entry| {
+            let mut hops = Vec::new();
+            let mut cur = entry;
+            while dist[cur.index()] > 0 {
+                let edge = &graph.imports(cur)[next[cur.index()]];
+                hops.push(Hop {
+                    from_file: graph.rel_path(cur),
+                    line: edge.line,
+                    request: edge.request.clone(),
+                    to_file: graph.rel_path(edge.to),
+                });
+                cur = edge.to;
+            }
+            trace!("{} reaches the target in {} hops", graph.rel_path(entry), hops.len());
+            Route { entry: graph.rel_path(entry), hops }
+        })
+        .collect();
+    routes.sort_by(|a, b| a.hops.len().cmp(&b.hops.len()).then_with(|| a.entry.cmp(&b.entry)));
+
+    let direct_importers = dist.iter().filter(|&&d| d == 1).count();
+    info!(
+        "Why query complete. {} of {} entries reach the target",
+        routes.len(),
+        graph.entries().len()
+    );
+    Ok(WhyResult {
+        target: cfg.target.clone(),
+        target_files,
+        routes,
+        direct_importers,
+        entries: graph.entries().len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxiclean_core::SharedConfig;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &Path, path: &str, content: &str) -> PathBuf {
+        let file_path = dir.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent directory");
+        }
+        fs::write(&file_path, content).expect("Failed to write test file");
+        file_path
+    }
+
+    fn config(root: &Path, target: &str, include_type_imports: bool) -> Config {
+        let mut cfg = Config {
+            shared: SharedConfig {
+                root: Some(root.to_path_buf()),
+                no_cache: Some(true),
+                entry_glob: vec!["src/pages/**".to_string()],
+                ..Default::default()
+            },
+            target: target.to_string(),
+            include_type_imports,
+        };
+        cfg.initialize().unwrap();
+        cfg
+    }
+
+    fn create_project(root: &Path) {
+        create_test_file(root, "src/pages/home.ts", "import '../widgets';\nimport '../lib/chart';");
+        create_test_file(root, "src/pages/admin.ts", "import '../widgets';");
+        create_test_file(root, "src/pages/about.ts", "import type { C } from '../lib/chart';");
+        create_test_file(root, "src/widgets/index.ts", "import './table';");
+        create_test_file(root, "src/widgets/table.ts", "import '../lib/chart';\nimport 'lodash';");
+        create_test_file(root, "src/lib/chart.ts", "export type C = 1;");
+        create_test_file(root, "src/lib/unused.ts", "export const u = 1;");
+        create_test_file(
+            root,
+            "node_modules/lodash/package.json",
+            r#"{ "name": "lodash", "main": "lodash.js" }"#,
+        );
+        create_test_file(root, "node_modules/lodash/lodash.js", "require('./internal');");
+        create_test_file(root, "node_modules/lodash/internal.js", "module.exports = 1;");
+    }
+
+    fn routes(result: &WhyResult) -> Vec<String> {
+        result
+            .routes
+            .iter()
+            .map(|r| {
+                let hops: Vec<String> =
+                    r.hops.iter().map(|h| format!("{}:{}", h.from_file, h.line)).collect();
+                format!("{} [{}]", r.entry, hops.join(" -> "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_why_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let result = run_why(config(root, "src/lib/chart.ts", false)).unwrap();
+        assert_eq!(result.target_files, vec!["src/lib/chart.ts"]);
+        assert_eq!(result.entries, 3);
+        assert_eq!(result.direct_importers, 2);
+        assert_eq!(
+            routes(&result),
+            vec![
+                "src/pages/home.ts [src/pages/home.ts:2]",
+                "src/pages/admin.ts [src/pages/admin.ts:1 -> src/widgets/index.ts:1 -> src/widgets/table.ts:1]",
+            ]
+        );
+
+        let result = run_why(config(root, "src/lib/chart.ts", true)).unwrap();
+        assert_eq!(result.routes.len(), 3);
+
+        let result = run_why(config(root, "src/lib/unused.ts", false)).unwrap();
+        assert_eq!(result.target_files, vec!["src/lib/unused.ts"]);
+        assert!(result.routes.is_empty());
+    }
+
+    #[test]
+    fn test_why_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_project(root);
+
+        let result = run_why(config(root, "lodash", false)).unwrap();
+        assert_eq!(result.target_files.len(), 2);
+        assert_eq!(result.direct_importers, 1);
+        assert_eq!(
+            routes(&result)[0],
+            "src/pages/admin.ts [src/pages/admin.ts:1 -> src/widgets/index.ts:1 -> src/widgets/table.ts:2]"
+        );
+
+        assert!(run_why(config(root, "left-pad", false)).is_err());
+    }
+}